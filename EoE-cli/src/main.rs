@@ -0,0 +1,572 @@
+//! `eoe`: generate keys, sign and verify artifacts, inspect encodings.
+//!
+//! PEM, hex, base64, envelopes and raw bytes are all accepted wherever a key
+//! or signature is read. PEM and envelopes are recognised by their headers;
+//! hex and base64 text is only accepted when exactly one of the two decodes
+//! to a known length, and `--encoding` settles the rest. `verify` exits with
+//! status 0 for a valid signature, 1 for an invalid one and 2 on usage or
+//! decoding errors, so it can be used directly in scripts.
+
+use std::{
+  ffi::OsString,
+  fs,
+  io::{self, Read, Write},
+  path::{Path, PathBuf},
+  process::ExitCode,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use echoes_of_eternity::{
+  base64_decode, base64_encode, decode_private_key_der, decode_public_key_der,
+  hex_decode, hex_encode, open_envelope, pem_decode, pem_encode,
+  seal_envelope, verify_transformation, AlgorithmId, ArtifactPublicKey,
+  ArtifactSecretKey, ArtifactSignature, EternityError, KeyPair, ParameterSet,
+  ESSENCEBYTES, PEM_PRIVATE_KEY, PEM_PUBLIC_KEY,
+};
+use zeroize::Zeroizing;
+
+/// PEM label for detached signatures, which have no standard one.
+const PEM_SIGNATURE: &str = "ARTIFACT SIGNATURE";
+
+#[derive(Parser)]
+#[command(name = "eoe", version, about = "Echoes of Eternity signatures")]
+struct Cli {
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+  /// Generate a key pair, writing OUT.pub and OUT.key
+  Keygen {
+    /// Parameter set; must be the one the tool was built for
+    #[arg(long, value_enum, default_value_t = ParameterArg::current())]
+    parameter_set: ParameterArg,
+    /// Output encoding
+    #[arg(long, value_enum, default_value_t = Format::Pem)]
+    format: Format,
+    /// Derive the keys from a 32-byte hex seed instead of OS randomness
+    #[arg(long)]
+    seed: Option<String>,
+    /// Path prefix of the key files
+    #[arg(long, short)]
+    out: PathBuf,
+  },
+  /// Write a detached signature over a file or stdin
+  Sign {
+    /// Secret key file
+    #[arg(long, short)]
+    key: PathBuf,
+    /// Encoding of the key; detected if omitted
+    #[arg(long, value_enum)]
+    encoding: Option<Format>,
+    /// Output encoding
+    #[arg(long, value_enum, default_value_t = Format::Base64)]
+    format: Format,
+    /// Signature file; stdout if omitted
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+    /// File to sign; `-` or omitted for stdin
+    input: Option<PathBuf>,
+  },
+  /// Check a detached signature over a file or stdin
+  Verify {
+    /// Public key file
+    #[arg(long, short)]
+    key: PathBuf,
+    /// Signature file
+    #[arg(long, short)]
+    signature: PathBuf,
+    /// Encoding of the key and signature; detected if omitted
+    #[arg(long, value_enum)]
+    encoding: Option<Format>,
+    /// Signed file; `-` or omitted for stdin
+    input: Option<PathBuf>,
+  },
+  /// Describe a key or signature
+  Inspect {
+    /// Key or signature file; `-` for stdin
+    path: PathBuf,
+    /// Encoding of the file; detected if omitted
+    #[arg(long, value_enum)]
+    encoding: Option<Format>,
+  },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ParameterArg {
+  #[value(alias = "2")]
+  Mode2,
+  #[value(alias = "3")]
+  Mode3,
+  #[value(alias = "5")]
+  Mode5,
+}
+
+impl ParameterArg {
+  fn current() -> ParameterArg {
+    match ParameterSet::CURRENT {
+      ParameterSet::Mode2 => ParameterArg::Mode2,
+      ParameterSet::Mode3 => ParameterArg::Mode3,
+      ParameterSet::Mode5 => ParameterArg::Mode5,
+    }
+  }
+
+  fn parameter_set(self) -> ParameterSet {
+    match self {
+      ParameterArg::Mode2 => ParameterSet::Mode2,
+      ParameterArg::Mode3 => ParameterSet::Mode3,
+      ParameterArg::Mode5 => ParameterSet::Mode5,
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+  Raw,
+  Hex,
+  Base64,
+  Pem,
+  Envelope,
+}
+
+impl Format {
+  fn name(self) -> &'static str {
+    match self {
+      Format::Raw => "raw",
+      Format::Hex => "hex",
+      Format::Base64 => "base64",
+      Format::Pem => "pem",
+      Format::Envelope => "envelope",
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+  PublicKey,
+  SecretKey,
+  Signature,
+}
+
+impl Kind {
+  fn name(self) -> &'static str {
+    match self {
+      Kind::PublicKey => "public key",
+      Kind::SecretKey => "secret key",
+      Kind::Signature => "signature",
+    }
+  }
+
+  fn bytes(self, set: ParameterSet) -> usize {
+    match self {
+      Kind::PublicKey => set.artifact_key_bytes(),
+      Kind::SecretKey => set.secret_key_bytes(),
+      Kind::Signature => set.signature_bytes(),
+    }
+  }
+}
+
+/// A key or signature read from a file, with what could be told about it.
+struct Decoded {
+  format: Format,
+  algorithm: Option<AlgorithmId>,
+  bytes: Zeroizing<Vec<u8>>,
+}
+
+impl Decoded {
+  /// Bytes in an encoding that does not name the algorithm.
+  fn plain(format: Format, bytes: Zeroizing<Vec<u8>>) -> Decoded {
+    Decoded {
+      format,
+      algorithm: None,
+      bytes,
+    }
+  }
+
+  /// Kind and parameter set implied by the length.
+  fn classify(&self) -> Option<(Kind, ParameterSet)> {
+    let sets = match self.algorithm {
+      Some(algorithm) if algorithm.is_composite() => return None,
+      Some(algorithm) => vec![algorithm.parameter_set()],
+      None => ParameterSet::ALL.to_vec(),
+    };
+    let kinds = [Kind::PublicKey, Kind::SecretKey, Kind::Signature];
+    sets.into_iter().find_map(|set| {
+      kinds
+        .into_iter()
+        .find(|kind| kind.bytes(set) == self.bytes.len())
+        .map(|kind| (kind, set))
+    })
+  }
+
+  /// The raw encoding, if it is a `kind` for this build.
+  fn expect(&self, kind: Kind) -> Result<&[u8], String> {
+    if let Some(algorithm) = self.algorithm {
+      if algorithm != AlgorithmId::CURRENT {
+        return Err(format!(
+          "{} is not supported by this build ({})",
+          algorithm.name(),
+          AlgorithmId::CURRENT.name()
+        ));
+      }
+    }
+    match self.classify() {
+      Some((found, set)) if found == kind => {
+        if set != ParameterSet::CURRENT {
+          return Err(format!(
+            "{} is for {}, but this build implements {}",
+            kind.name(),
+            set.name(),
+            ParameterSet::CURRENT.name()
+          ));
+        }
+        Ok(&self.bytes)
+      }
+      Some((found, _)) => {
+        Err(format!("expected a {}, found a {}", kind.name(), found.name()))
+      }
+      None => Err(format!("not a {}", kind.name())),
+    }
+  }
+}
+
+fn read_input(path: Option<&Path>) -> Result<Vec<u8>, String> {
+  match path {
+    None => read_stdin(),
+    Some(path) if path == Path::new("-") => read_stdin(),
+    Some(path) => {
+      fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+  }
+}
+
+fn read_stdin() -> Result<Vec<u8>, String> {
+  let mut data = Vec::new();
+  io::stdin()
+    .read_to_end(&mut data)
+    .map_err(|e| format!("stdin: {}", e))?;
+  Ok(data)
+}
+
+fn write_output(
+  path: Option<&Path>,
+  data: &[u8],
+  secret: bool,
+) -> Result<(), String> {
+  let Some(path) = path else {
+    return io::stdout()
+      .write_all(data)
+      .map_err(|e| format!("stdout: {}", e));
+  };
+  let mut options = fs::OpenOptions::new();
+  options.write(true).create(true).truncate(true);
+  #[cfg(unix)]
+  if secret {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  options
+    .open(path)
+    .and_then(|mut file| {
+      // The mode above only applies to new files; tighten existing ones
+      // before anything is written to them.
+      #[cfg(unix)]
+      if secret {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+      }
+      #[cfg(not(unix))]
+      let _ = secret;
+      file.write_all(data)
+    })
+    .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn error_message(error: EternityError) -> String {
+  error.to_string()
+}
+
+fn decode(data: &[u8], encoding: Option<Format>) -> Result<Decoded, String> {
+  let utf8 = std::str::from_utf8(data).map(str::trim);
+  let text = || utf8.map_err(|_| "input is not text".to_string());
+  let decoded = |format: Format, bytes: Option<Vec<u8>>| {
+    bytes
+      .map(|bytes| Decoded::plain(format, Zeroizing::new(bytes)))
+      .ok_or(format!("invalid {}", format.name()))
+  };
+  match encoding {
+    Some(Format::Pem) => return decode_pem(text()?),
+    Some(Format::Envelope) => return decode_envelope(data),
+    Some(Format::Hex) => return decoded(Format::Hex, hex_decode(text()?)),
+    Some(Format::Base64) => {
+      return decoded(Format::Base64, base64_decode(text()?))
+    }
+    Some(Format::Raw) => {
+      return Ok(Decoded::plain(Format::Raw, Zeroizing::new(data.to_vec())))
+    }
+    None => {}
+  }
+
+  if let Ok(text) = text() {
+    if text.starts_with("-----BEGIN ") {
+      return decode_pem(text);
+    }
+  }
+  if open_envelope(data).is_ok() {
+    return decode_envelope(data);
+  }
+  if let Ok(text) = text() {
+    // A string of hex digits is often valid base64 as well, so neither
+    // reading is preferred: only one that gives a known length counts.
+    let mut candidates: Vec<Decoded> = [
+      (Format::Hex, hex_decode(text)),
+      (Format::Base64, base64_decode(text)),
+    ]
+    .into_iter()
+    .filter_map(|(format, bytes)| {
+      Some(Decoded::plain(format, Zeroizing::new(bytes?)))
+    })
+    .filter(|decoded| decoded.classify().is_some())
+    .collect();
+    match candidates.len() {
+      0 => {}
+      1 => return Ok(candidates.remove(0)),
+      _ => {
+        return Err("ambiguous encoding, pass --encoding hex or base64".into())
+      }
+    }
+  }
+  Ok(Decoded::plain(Format::Raw, Zeroizing::new(data.to_vec())))
+}
+
+fn decode_envelope(data: &[u8]) -> Result<Decoded, String> {
+  let envelope = open_envelope(data).map_err(error_message)?;
+  Ok(Decoded {
+    format: Format::Envelope,
+    algorithm: Some(envelope.algorithm),
+    bytes: Zeroizing::new(envelope.payload.to_vec()),
+  })
+}
+
+fn decode_pem(text: &str) -> Result<Decoded, String> {
+  let label = text
+    .strip_prefix("-----BEGIN ")
+    .and_then(|rest| rest.split_once("-----"))
+    .map(|(label, _)| label)
+    .ok_or("missing PEM header")?;
+  let der = Zeroizing::new(pem_decode(text, label).map_err(error_message)?);
+  let (algorithm, bytes) = match label {
+    PEM_PUBLIC_KEY => {
+      let (algorithm, public_key) =
+        decode_public_key_der(&der).map_err(error_message)?;
+      (Some(algorithm), public_key.to_vec())
+    }
+    PEM_PRIVATE_KEY => {
+      let document = decode_private_key_der(&der).map_err(error_message)?;
+      let expanded = match (&document.expanded, &document.seed) {
+        (Some(expanded), _) => expanded.clone(),
+        (None, Some(seed)) if document.algorithm == AlgorithmId::CURRENT => {
+          KeyPair::from_seed(seed).expose_secret().to_vec()
+        }
+        (None, _) => return Err("private key holds only a seed".into()),
+      };
+      (Some(document.algorithm), expanded)
+    }
+    PEM_SIGNATURE => (None, der.to_vec()),
+    _ => return Err(format!("unexpected PEM label {:?}", label)),
+  };
+  Ok(Decoded {
+    format: Format::Pem,
+    algorithm,
+    bytes: Zeroizing::new(bytes),
+  })
+}
+
+fn encode(kind: Kind, bytes: &[u8], format: Format) -> Result<Vec<u8>, String> {
+  let text = match format {
+    Format::Raw => return Ok(bytes.to_vec()),
+    Format::Envelope => {
+      return seal_envelope(AlgorithmId::CURRENT, bytes).map_err(error_message)
+    }
+    Format::Hex => hex_encode(bytes) + "\n",
+    Format::Base64 => base64_encode(bytes) + "\n",
+    Format::Pem => match kind {
+      Kind::PublicKey => ArtifactPublicKey::from_bytes(bytes)
+        .map_err(error_message)?
+        .to_public_key_pem(),
+      Kind::SecretKey => ArtifactSecretKey::from_bytes(bytes)
+        .map_err(error_message)?
+        .to_pkcs8_pem(),
+      Kind::Signature => pem_encode(PEM_SIGNATURE, bytes),
+    },
+  };
+  Ok(text.into_bytes())
+}
+
+/// `path` with `suffix` appended to its file name, so that dots already in
+/// the name are kept.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+  let mut name = OsString::from(path.as_os_str());
+  name.push(suffix);
+  PathBuf::from(name)
+}
+
+fn keygen(
+  parameter_set: ParameterArg,
+  format: Format,
+  seed: Option<&str>,
+  out: &Path,
+) -> Result<(), String> {
+  let parameter_set = parameter_set.parameter_set();
+  if parameter_set != ParameterSet::CURRENT {
+    return Err(format!(
+      "this build implements {}; rebuild with --features artifact_{}",
+      ParameterSet::CURRENT.name(),
+      parameter_set.name()
+    ));
+  }
+
+  let keys = match seed {
+    Some(seed) => {
+      let seed = Zeroizing::new(hex_decode(seed).unwrap_or_default());
+      let seed: &[u8; ESSENCEBYTES] = seed[..]
+        .try_into()
+        .map_err(|_| "--seed must be 32 bytes of hex")?;
+      KeyPair::from_seed(seed)
+    }
+    None => KeyPair::generate(),
+  };
+
+  let public_path = with_suffix(out, ".pub");
+  let secret_path = with_suffix(out, ".key");
+  let public_key = encode(Kind::PublicKey, &keys.public, format)?;
+  let secret_key =
+    Zeroizing::new(encode(Kind::SecretKey, keys.expose_secret(), format)?);
+  write_output(Some(&public_path), &public_key, false)?;
+  write_output(Some(&secret_path), &secret_key, true)?;
+  eprintln!(
+    "wrote {} and {}",
+    public_path.display(),
+    secret_path.display()
+  );
+  Ok(())
+}
+
+fn sign(
+  key: &Path,
+  encoding: Option<Format>,
+  format: Format,
+  out: Option<&Path>,
+  input: Option<&Path>,
+) -> Result<(), String> {
+  let key = decode(&Zeroizing::new(read_input(Some(key))?), encoding)?;
+  let secret_key = ArtifactSecretKey::from_bytes(key.expect(Kind::SecretKey)?)
+    .map_err(error_message)?;
+  let message = read_input(input)?;
+  let signature = secret_key.sign(&message);
+  let encoded = encode(Kind::Signature, signature.as_bytes(), format)?;
+  write_output(out, &encoded, false)
+}
+
+fn verify(
+  key: &Path,
+  signature: &Path,
+  encoding: Option<Format>,
+  input: Option<&Path>,
+) -> Result<bool, String> {
+  let key = decode(&read_input(Some(key))?, encoding)?;
+  let signature = decode(&read_input(Some(signature))?, encoding)?;
+  let message = read_input(input)?;
+  let result = verify_transformation(
+    signature.expect(Kind::Signature)?,
+    &message,
+    key.expect(Kind::PublicKey)?,
+  );
+  match result {
+    Ok(()) => Ok(true),
+    Err(EternityError::VerificationFailed) => Ok(false),
+    Err(error) => Err(error_message(error)),
+  }
+}
+
+fn inspect(path: &Path, encoding: Option<Format>) -> Result<(), String> {
+  let decoded = decode(&Zeroizing::new(read_input(Some(path))?), encoding)?;
+  println!("format:         {}", decoded.format.name());
+  if let Some(algorithm) = decoded.algorithm {
+    println!("algorithm:      {}", algorithm.name());
+  }
+  println!("size:           {} bytes", decoded.bytes.len());
+
+  let Some((kind, set)) = decoded.classify() else {
+    println!("kind:           unknown");
+    return Ok(());
+  };
+  let (k, l) = set.dimensions();
+  println!("kind:           {}", kind.name());
+  println!("parameter set:  {} (K = {}, L = {})", set.name(), k, l);
+
+  if kind != Kind::Signature {
+    return Ok(());
+  }
+  if set != ParameterSet::CURRENT {
+    println!(
+      "statistics:     unavailable, this build implements {}",
+      ParameterSet::CURRENT.name()
+    );
+    return Ok(());
+  }
+  let stats = ArtifactSignature::from_bytes(&decoded.bytes)
+    .and_then(|signature| signature.stats())
+    .map_err(error_message)?;
+  println!(
+    "hint weight:    {} / {}",
+    stats.hint_weight, stats.max_hint_weight
+  );
+  println!("z norm:         {} (bound {})", stats.z_norm, stats.z_bound);
+  Ok(())
+}
+
+fn main() -> ExitCode {
+  let cli = Cli::parse();
+  let result = match &cli.command {
+    Command::Keygen {
+      parameter_set,
+      format,
+      seed,
+      out,
+    } => keygen(*parameter_set, *format, seed.as_deref(), out),
+    Command::Sign {
+      key,
+      encoding,
+      format,
+      out,
+      input,
+    } => sign(key, *encoding, *format, out.as_deref(), input.as_deref()),
+    Command::Verify {
+      key,
+      signature,
+      encoding,
+      input,
+    } => match verify(key, signature, *encoding, input.as_deref()) {
+      Ok(true) => {
+        println!("valid");
+        Ok(())
+      }
+      Ok(false) => {
+        println!("invalid");
+        return ExitCode::from(1);
+      }
+      Err(error) => Err(error),
+    },
+    Command::Inspect { path, encoding } => inspect(path, *encoding),
+  };
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(error) => {
+      eprintln!("eoe: {}", error);
+      ExitCode::from(2)
+    }
+  }
+}
@@ -0,0 +1,176 @@
+//! Runs the `eoe` binary end to end.
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  process::{Command, Output},
+};
+
+const SEED: &str =
+  "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+/// A scratch directory, removed when dropped.
+struct Scratch(PathBuf);
+
+impl Scratch {
+  fn new(name: &str) -> Scratch {
+    let path = std::env::temp_dir().join(format!(
+      "eoe-cli-{}-{}",
+      std::process::id(),
+      name
+    ));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    Scratch(path)
+  }
+
+  fn path(&self, name: &str) -> PathBuf {
+    self.0.join(name)
+  }
+}
+
+impl Drop for Scratch {
+  fn drop(&mut self) {
+    let _ = fs::remove_dir_all(&self.0);
+  }
+}
+
+fn eoe(args: &[&Path]) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_eoe"))
+    .args(args)
+    .output()
+    .unwrap()
+}
+
+fn keygen(scratch: &Scratch, format: &str, prefix: &str) -> Output {
+  let out = scratch.path(prefix);
+  eoe(&[
+    "keygen".as_ref(),
+    "--seed".as_ref(),
+    SEED.as_ref(),
+    "--format".as_ref(),
+    format.as_ref(),
+    "--out".as_ref(),
+    &out,
+  ])
+}
+
+#[test]
+fn keygen_sign_and_verify_round_trip() {
+  for format in ["pem", "hex", "base64", "envelope", "raw"] {
+    let scratch = Scratch::new(&format!("round-trip-{format}"));
+    let message = scratch.path("message");
+    let other = scratch.path("other");
+    let signature = scratch.path("message.sig");
+    fs::write(&message, b"release artifact").unwrap();
+    fs::write(&other, b"tampered artifact").unwrap();
+
+    // Dots in the prefix survive; the suffixes are appended.
+    let output = keygen(&scratch, format, "release.v1");
+    assert!(
+      output.status.success(),
+      "keygen --format {format}: {output:?}"
+    );
+    let public = scratch.path("release.v1.pub");
+    let secret = scratch.path("release.v1.key");
+    assert!(public.exists() && secret.exists());
+
+    let output = eoe(&[
+      "sign".as_ref(),
+      "--key".as_ref(),
+      &secret,
+      "--format".as_ref(),
+      format.as_ref(),
+      "--out".as_ref(),
+      &signature,
+      &message,
+    ]);
+    assert!(
+      output.status.success(),
+      "sign --format {format}: {output:?}"
+    );
+
+    let verify = |input: &Path| {
+      eoe(&[
+        "verify".as_ref(),
+        "--key".as_ref(),
+        &public,
+        "--signature".as_ref(),
+        &signature,
+        input,
+      ])
+    };
+    let output = verify(&message);
+    assert_eq!(output.status.code(), Some(0), "{format}: {output:?}");
+    assert_eq!(output.stdout, b"valid\n");
+    let output = verify(&other);
+    assert_eq!(output.status.code(), Some(1), "{format}: {output:?}");
+    assert_eq!(output.stdout, b"invalid\n");
+
+    let output = eoe(&["inspect".as_ref(), &public]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+      .unwrap()
+      .contains("kind:           public key"));
+  }
+}
+
+#[test]
+fn encoding_flag_overrides_detection() {
+  let scratch = Scratch::new("encoding");
+  assert!(keygen(&scratch, "hex", "keys").status.success());
+  let public = scratch.path("keys.pub");
+
+  let inspect = |encoding: &str| {
+    eoe(&[
+      "inspect".as_ref(),
+      "--encoding".as_ref(),
+      encoding.as_ref(),
+      &public,
+    ])
+  };
+  let output = inspect("hex");
+  assert!(output.status.success(), "{output:?}");
+  assert!(String::from_utf8(output.stdout)
+    .unwrap()
+    .contains("format:         hex"));
+
+  // Hex digits are valid base64 too, but decode to no known length.
+  let output = inspect("base64");
+  assert!(output.status.success(), "{output:?}");
+  assert!(String::from_utf8(output.stdout)
+    .unwrap()
+    .contains("kind:           unknown"));
+
+  let output = inspect("pem");
+  assert_eq!(output.status.code(), Some(2), "{output:?}");
+}
+
+#[test]
+fn undecodable_keys_are_usage_errors() {
+  let scratch = Scratch::new("undecodable");
+  let key = scratch.path("garbage.key");
+  let message = scratch.path("message");
+  fs::write(&key, b"not a key").unwrap();
+  fs::write(&message, b"message").unwrap();
+  let output = eoe(&["sign".as_ref(), "--key".as_ref(), &key, &message]);
+  assert_eq!(output.status.code(), Some(2), "{output:?}");
+  assert!(String::from_utf8(output.stderr)
+    .unwrap()
+    .contains("not a secret key"));
+}
+
+#[cfg(unix)]
+#[test]
+fn existing_secret_key_files_are_restricted() {
+  use std::os::unix::fs::PermissionsExt;
+
+  let scratch = Scratch::new("permissions");
+  let secret = scratch.path("keys.key");
+  fs::write(&secret, b"old").unwrap();
+  fs::set_permissions(&secret, fs::Permissions::from_mode(0o644)).unwrap();
+
+  assert!(keygen(&scratch, "pem", "keys").status.success());
+  let mode = fs::metadata(&secret).unwrap().permissions().mode();
+  assert_eq!(mode & 0o777, 0o600);
+}
@@ -0,0 +1,29 @@
+from typing import List, Optional, Tuple
+
+ALGORITHM: str
+PARAMETER_SET: str
+PUBLIC_KEY_BYTES: int
+SECRET_KEY_BYTES: int
+SIGNATURE_BYTES: int
+SEED_BYTES: int
+
+class EternityError(Exception): ...
+class InvalidInput(EternityError): ...
+class VerificationFailed(EternityError): ...
+class ParameterMismatch(EternityError): ...
+class UnsupportedAlgorithm(EternityError): ...
+
+def keypair(seed: Optional[bytes] = None) -> Tuple[bytes, bytes]: ...
+def sign(secret_key: bytes, message: bytes) -> bytes: ...
+def verify(signature: bytes, message: bytes, public_key: bytes) -> None: ...
+def verify_batch(items: List[Tuple[bytes, bytes, bytes]]) -> List[bool]: ...
+def public_key_to_der(public_key: bytes) -> bytes: ...
+def public_key_from_der(der: bytes) -> bytes: ...
+def public_key_to_pem(public_key: bytes) -> str: ...
+def public_key_from_pem(pem: str) -> bytes: ...
+def secret_key_to_der(secret_key: bytes) -> bytes: ...
+def secret_key_from_der(der: bytes) -> bytes: ...
+def secret_key_to_pem(secret_key: bytes) -> str: ...
+def secret_key_from_pem(pem: str) -> bytes: ...
+def seal(payload: bytes) -> bytes: ...
+def open(envelope: bytes) -> Tuple[str, str, bytes]: ...
@@ -0,0 +1,247 @@
+//! Python bindings.
+//!
+//! Keys and signatures cross the boundary as `bytes` in their raw encodings;
+//! every function checks lengths and raises one of the exceptions below
+//! instead of returning a status. Errors map one to one onto the variants
+//! of the core `EternityError`, all deriving from `eoe.EternityError`.
+
+use echoes_of_eternity::{
+  open_envelope, seal_envelope, verify_transformation, AlgorithmId,
+  ArtifactPublicKey, ArtifactSecretKey, EnvelopeKind,
+  EternityError as CoreError, KeyPair, ParameterSet, ARTIFACTKEYBYTES,
+  ESSENCEBYTES, SECRETKEYBYTES, SIGNATUREBYTES,
+};
+use pyo3::{
+  create_exception, exceptions::PyException, prelude::*, types::PyBytes,
+};
+
+create_exception!(eoe, EternityError, PyException);
+create_exception!(eoe, InvalidInput, EternityError);
+create_exception!(eoe, VerificationFailed, EternityError);
+create_exception!(eoe, ParameterMismatch, EternityError);
+create_exception!(eoe, UnsupportedAlgorithm, EternityError);
+
+fn to_py_err(error: CoreError) -> PyErr {
+  let message = error.to_string();
+  match error {
+    CoreError::InvalidInput => InvalidInput::new_err(message),
+    CoreError::VerificationFailed => VerificationFailed::new_err(message),
+    CoreError::ParameterMismatch => ParameterMismatch::new_err(message),
+    CoreError::UnsupportedAlgorithm => {
+      UnsupportedAlgorithm::new_err(message)
+    }
+  }
+}
+
+fn bytes<'py>(py: Python<'py>, data: &[u8]) -> Bound<'py, PyBytes> {
+  PyBytes::new_bound(py, data)
+}
+
+/// Generate a key pair, deterministically if a 32-byte seed is given.
+///
+/// Returns `(public_key, secret_key)`.
+#[pyfunction]
+#[pyo3(signature = (seed=None))]
+fn keypair<'py>(
+  py: Python<'py>,
+  seed: Option<&[u8]>,
+) -> PyResult<(Bound<'py, PyBytes>, Bound<'py, PyBytes>)> {
+  let keys = match seed {
+    Some(seed) => {
+      let seed = seed
+        .try_into()
+        .map_err(|_| to_py_err(CoreError::InvalidInput))?;
+      KeyPair::from_seed(seed)
+    }
+    None => KeyPair::generate(),
+  };
+  Ok((bytes(py, &keys.public), bytes(py, keys.expose_secret())))
+}
+
+/// Sign `message` with a raw secret key.
+#[pyfunction]
+fn sign<'py>(
+  py: Python<'py>,
+  secret_key: &[u8],
+  message: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+  let secret_key =
+    ArtifactSecretKey::from_bytes(secret_key).map_err(to_py_err)?;
+  let signature = py.allow_threads(|| secret_key.sign(message));
+  Ok(bytes(py, signature.as_bytes()))
+}
+
+/// Verify a signature, raising `VerificationFailed` if it does not hold.
+#[pyfunction]
+fn verify(
+  py: Python<'_>,
+  signature: &[u8],
+  message: &[u8],
+  public_key: &[u8],
+) -> PyResult<()> {
+  py.allow_threads(|| verify_transformation(signature, message, public_key))
+    .map_err(to_py_err)
+}
+
+/// Verify many `(signature, message, public_key)` triples.
+///
+/// Returns one boolean per item instead of raising, so a single bad
+/// signature does not hide the results of the others.
+#[pyfunction]
+fn verify_batch(
+  py: Python<'_>,
+  items: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+) -> Vec<bool> {
+  py.allow_threads(|| {
+    items
+      .iter()
+      .map(|(signature, message, public_key)| {
+        verify_transformation(signature, message, public_key).is_ok()
+      })
+      .collect()
+  })
+}
+
+/// Encode a raw public key as a DER SubjectPublicKeyInfo.
+#[pyfunction]
+fn public_key_to_der<'py>(
+  py: Python<'py>,
+  public_key: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+  let public_key =
+    ArtifactPublicKey::from_bytes(public_key).map_err(to_py_err)?;
+  Ok(bytes(py, &public_key.to_public_key_der()))
+}
+
+/// Decode a DER SubjectPublicKeyInfo into the raw public key.
+#[pyfunction]
+fn public_key_from_der<'py>(
+  py: Python<'py>,
+  der: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+  let public_key =
+    ArtifactPublicKey::from_public_key_der(der).map_err(to_py_err)?;
+  Ok(bytes(py, public_key.as_bytes()))
+}
+
+/// Encode a raw public key as a PEM `PUBLIC KEY` block.
+#[pyfunction]
+fn public_key_to_pem(public_key: &[u8]) -> PyResult<String> {
+  let public_key =
+    ArtifactPublicKey::from_bytes(public_key).map_err(to_py_err)?;
+  Ok(public_key.to_public_key_pem())
+}
+
+/// Decode a PEM `PUBLIC KEY` block into the raw public key.
+#[pyfunction]
+fn public_key_from_pem<'py>(
+  py: Python<'py>,
+  pem: &str,
+) -> PyResult<Bound<'py, PyBytes>> {
+  let public_key =
+    ArtifactPublicKey::from_public_key_pem(pem).map_err(to_py_err)?;
+  Ok(bytes(py, public_key.as_bytes()))
+}
+
+/// Encode a raw secret key as a DER PKCS#8 document.
+#[pyfunction]
+fn secret_key_to_der<'py>(
+  py: Python<'py>,
+  secret_key: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+  let secret_key =
+    ArtifactSecretKey::from_bytes(secret_key).map_err(to_py_err)?;
+  Ok(bytes(py, &secret_key.to_pkcs8_der()))
+}
+
+/// Decode a DER PKCS#8 document into the raw secret key.
+#[pyfunction]
+fn secret_key_from_der<'py>(
+  py: Python<'py>,
+  der: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+  let secret_key = ArtifactSecretKey::from_pkcs8_der(der).map_err(to_py_err)?;
+  Ok(bytes(py, secret_key.as_bytes()))
+}
+
+/// Encode a raw secret key as a PEM `PRIVATE KEY` block.
+#[pyfunction]
+fn secret_key_to_pem(secret_key: &[u8]) -> PyResult<String> {
+  let secret_key =
+    ArtifactSecretKey::from_bytes(secret_key).map_err(to_py_err)?;
+  Ok(secret_key.to_pkcs8_pem())
+}
+
+/// Decode a PEM `PRIVATE KEY` block into the raw secret key.
+#[pyfunction]
+fn secret_key_from_pem<'py>(
+  py: Python<'py>,
+  pem: &str,
+) -> PyResult<Bound<'py, PyBytes>> {
+  let secret_key = ArtifactSecretKey::from_pkcs8_pem(pem).map_err(to_py_err)?;
+  Ok(bytes(py, secret_key.as_bytes()))
+}
+
+/// Wrap a raw key or signature in a versioned envelope.
+#[pyfunction]
+fn seal<'py>(
+  py: Python<'py>,
+  payload: &[u8],
+) -> PyResult<Bound<'py, PyBytes>> {
+  let envelope =
+    seal_envelope(AlgorithmId::CURRENT, payload).map_err(to_py_err)?;
+  Ok(bytes(py, &envelope))
+}
+
+/// Open an envelope, returning `(algorithm, kind, payload)`.
+///
+/// `kind` is one of `"public_key"`, `"secret_key"` and `"signature"`.
+#[pyfunction]
+fn open<'py>(
+  py: Python<'py>,
+  envelope: &[u8],
+) -> PyResult<(&'static str, &'static str, Bound<'py, PyBytes>)> {
+  let envelope = open_envelope(envelope).map_err(to_py_err)?;
+  let kind = match envelope.kind {
+    EnvelopeKind::PublicKey => "public_key",
+    EnvelopeKind::SecretKey => "secret_key",
+    EnvelopeKind::Signature => "signature",
+  };
+  Ok((envelope.algorithm.name(), kind, bytes(py, envelope.payload)))
+}
+
+#[pymodule]
+fn eoe(m: &Bound<'_, PyModule>) -> PyResult<()> {
+  let py = m.py();
+  m.add("EternityError", py.get_type_bound::<EternityError>())?;
+  m.add("InvalidInput", py.get_type_bound::<InvalidInput>())?;
+  m.add("VerificationFailed", py.get_type_bound::<VerificationFailed>())?;
+  m.add("ParameterMismatch", py.get_type_bound::<ParameterMismatch>())?;
+  m.add(
+    "UnsupportedAlgorithm",
+    py.get_type_bound::<UnsupportedAlgorithm>(),
+  )?;
+
+  m.add("ALGORITHM", AlgorithmId::CURRENT.name())?;
+  m.add("PARAMETER_SET", ParameterSet::CURRENT.name())?;
+  m.add("PUBLIC_KEY_BYTES", ARTIFACTKEYBYTES)?;
+  m.add("SECRET_KEY_BYTES", SECRETKEYBYTES)?;
+  m.add("SIGNATURE_BYTES", SIGNATUREBYTES)?;
+  m.add("SEED_BYTES", ESSENCEBYTES)?;
+
+  m.add_function(wrap_pyfunction!(keypair, m)?)?;
+  m.add_function(wrap_pyfunction!(sign, m)?)?;
+  m.add_function(wrap_pyfunction!(verify, m)?)?;
+  m.add_function(wrap_pyfunction!(verify_batch, m)?)?;
+  m.add_function(wrap_pyfunction!(public_key_to_der, m)?)?;
+  m.add_function(wrap_pyfunction!(public_key_from_der, m)?)?;
+  m.add_function(wrap_pyfunction!(public_key_to_pem, m)?)?;
+  m.add_function(wrap_pyfunction!(public_key_from_pem, m)?)?;
+  m.add_function(wrap_pyfunction!(secret_key_to_der, m)?)?;
+  m.add_function(wrap_pyfunction!(secret_key_from_der, m)?)?;
+  m.add_function(wrap_pyfunction!(secret_key_to_pem, m)?)?;
+  m.add_function(wrap_pyfunction!(secret_key_from_pem, m)?)?;
+  m.add_function(wrap_pyfunction!(seal, m)?)?;
+  m.add_function(wrap_pyfunction!(open, m)?)?;
+  Ok(())
+}
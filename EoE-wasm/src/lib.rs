@@ -0,0 +1,107 @@
+//! WebAssembly bindings.
+//!
+//! Byte arrays cross the boundary as `Uint8Array`. One package is built per
+//! parameter set:
+//!
+//! ```text
+//! wasm-pack build --target web                                  # mode 3
+//! wasm-pack build --target web --out-dir pkg-mode2 -- --features artifact_mode2
+//! wasm-pack build --target web --out-dir pkg-mode5 -- --features artifact_mode5
+//! ```
+
+use echoes_of_eternity::{
+  verify_transformation, AlgorithmId, ArtifactSecretKey, EternityError,
+  ParameterSet, ARTIFACTKEYBYTES, ESSENCEBYTES, SECRETKEYBYTES,
+  SIGNATUREBYTES,
+};
+use wasm_bindgen::prelude::*;
+
+fn to_js_error(error: EternityError) -> JsError {
+  JsError::new(&error.to_string())
+}
+
+/// Name of the parameter set this package was built for.
+#[wasm_bindgen(js_name = parameterSet)]
+pub fn parameter_set() -> String {
+  ParameterSet::CURRENT.name().into()
+}
+
+/// Name of the signature algorithm this package implements.
+#[wasm_bindgen]
+pub fn algorithm() -> String {
+  AlgorithmId::CURRENT.name().into()
+}
+
+#[wasm_bindgen(js_name = publicKeyBytes)]
+pub fn public_key_bytes() -> usize {
+  ARTIFACTKEYBYTES
+}
+
+#[wasm_bindgen(js_name = secretKeyBytes)]
+pub fn secret_key_bytes() -> usize {
+  SECRETKEYBYTES
+}
+
+#[wasm_bindgen(js_name = signatureBytes)]
+pub fn signature_bytes() -> usize {
+  SIGNATUREBYTES
+}
+
+/// A generated key pair.
+#[wasm_bindgen]
+pub struct KeyPair {
+  inner: echoes_of_eternity::KeyPair,
+}
+
+#[wasm_bindgen]
+impl KeyPair {
+  #[wasm_bindgen(getter, js_name = publicKey)]
+  pub fn public_key(&self) -> Vec<u8> {
+    self.inner.public.to_vec()
+  }
+
+  #[wasm_bindgen(getter, js_name = secretKey)]
+  pub fn secret_key(&self) -> Vec<u8> {
+    self.inner.expose_secret().to_vec()
+  }
+}
+
+/// Generate a key pair, deterministically if a 32-byte seed is given.
+#[wasm_bindgen]
+pub fn keypair(seed: Option<Vec<u8>>) -> Result<KeyPair, JsError> {
+  let inner = match seed {
+    Some(seed) => {
+      let seed: &[u8; ESSENCEBYTES] = seed[..]
+        .try_into()
+        .map_err(|_| to_js_error(EternityError::InvalidInput))?;
+      echoes_of_eternity::KeyPair::from_seed(seed)
+    }
+    None => echoes_of_eternity::KeyPair::generate(),
+  };
+  Ok(KeyPair { inner })
+}
+
+/// Sign `message` with a raw secret key.
+#[wasm_bindgen]
+pub fn sign(secret_key: &[u8], message: &[u8]) -> Result<Vec<u8>, JsError> {
+  let secret_key =
+    ArtifactSecretKey::from_bytes(secret_key).map_err(to_js_error)?;
+  Ok(secret_key.sign(message).as_bytes().to_vec())
+}
+
+/// Verify a signature.
+///
+/// Resolves to `false` for a well-formed signature that does not verify and
+/// throws for inputs of the wrong size or another parameter set.
+#[wasm_bindgen]
+pub fn verify(
+  signature: &[u8],
+  message: &[u8],
+  public_key: &[u8],
+) -> Result<bool, JsError> {
+  match verify_transformation(signature, message, public_key) {
+    Ok(()) => Ok(true),
+    Err(EternityError::VerificationFailed) => Ok(false),
+    Err(error) => Err(to_js_error(error)),
+  }
+}
@@ -0,0 +1,38 @@
+//! Run with `wasm-pack test --node`.
+
+use eoe_wasm::*;
+use wasm_bindgen_test::*;
+
+const MESSAGE: &[u8] = b"artifact";
+
+fn seeded() -> KeyPair {
+  keypair(Some(vec![7; 32])).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn sizes_match_keys() {
+  let keys = seeded();
+  assert_eq!(keys.public_key().len(), public_key_bytes());
+  assert_eq!(keys.secret_key().len(), secret_key_bytes());
+  let signature = sign(&keys.secret_key(), MESSAGE).unwrap();
+  assert_eq!(signature.len(), signature_bytes());
+}
+
+#[wasm_bindgen_test]
+fn seeded_keypair_is_deterministic() {
+  assert_eq!(seeded().public_key(), seeded().public_key());
+}
+
+#[wasm_bindgen_test]
+fn sign_then_verify() {
+  let keys = keypair(None).unwrap();
+  let signature = sign(&keys.secret_key(), MESSAGE).unwrap();
+  assert!(verify(&signature, MESSAGE, &keys.public_key()).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn tampered_message_is_rejected() {
+  let keys = seeded();
+  let signature = sign(&keys.secret_key(), MESSAGE).unwrap();
+  assert!(!verify(&signature, b"forgery", &keys.public_key()).unwrap());
+}
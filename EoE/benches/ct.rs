@@ -0,0 +1,412 @@
+//! dudect-style timing-leak harness.
+//!
+//! Each target is timed on two classes of inputs, a fixed one and a random
+//! one, picked at random per measurement. If the running time does not
+//! depend on the input the two timing distributions agree; a large Welch
+//! t-statistic between them is evidence of a data-dependent branch or memory
+//! access. As in dudect the statistic is also computed on the measurements
+//! below several percentiles, which strips interrupts and other one-sided
+//! noise.
+//!
+//! ```text
+//! cargo bench --features internals --bench ct [-- <filter>...]
+//! ```
+//!
+//! `EOE_CT_SCALE` multiplies the number of measurements. Verdicts follow
+//! dudect: `|t| < 4.5` shows no evidence of a leak, `|t| > 10` is a leak.
+//! Passing `--strict` turns any flagged target into a failing exit status.
+//! Results are only meaningful on an idle machine with frequency scaling off.
+
+use std::{hint::black_box, process::ExitCode, time::Instant};
+
+use echoes_of_eternity::{
+  internals::{artifact_keypair, artifact_sign, poly, reduce, rounding},
+  ARTIFACTKEYBYTES, CRYSTALBYTES, ESSENCEBYTES, GAMMA1_I32, GAMMA2_I32,
+  QUANTA_I32, SECRETKEYBYTES, SIGNATUREBYTES,
+};
+
+const POSSIBLE_LEAK: f64 = 4.5;
+const LEAK: f64 = 10.0;
+const MIN_SAMPLES: usize = 100;
+const PERCENTILES: [f64; 5] = [1.0, 0.9, 0.75, 0.5, 0.25];
+
+/// splitmix64; the harness needs speed and reproducibility, not secrecy.
+struct Rng(u64);
+
+impl Rng {
+  fn next_u64(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  fn below(&mut self, bound: i32) -> i32 {
+    (self.next_u64() % bound as u64) as i32
+  }
+
+  fn fill(&mut self, bytes: &mut [u8]) {
+    for byte in bytes {
+      *byte = self.next_u64() as u8;
+    }
+  }
+}
+
+/// Online mean and variance.
+#[derive(Default)]
+struct Moments {
+  n: f64,
+  mean: f64,
+  m2: f64,
+}
+
+impl Moments {
+  fn push(&mut self, x: f64) {
+    self.n += 1.0;
+    let delta = x - self.mean;
+    self.mean += delta / self.n;
+    self.m2 += delta * (x - self.mean);
+  }
+
+  fn variance(&self) -> f64 {
+    self.m2 / (self.n - 1.0)
+  }
+}
+
+fn welch_t(a: &Moments, b: &Moments) -> f64 {
+  if a.n < 2.0 || b.n < 2.0 {
+    return 0.0;
+  }
+  let denominator = (a.variance() / a.n + b.variance() / b.n).sqrt();
+  if denominator == 0.0 {
+    0.0
+  } else {
+    (a.mean - b.mean) / denominator
+  }
+}
+
+/// Largest |t| over the uncropped data and the percentile crops.
+fn max_t(samples: &[(bool, u64)]) -> f64 {
+  let mut sorted: Vec<u64> = samples.iter().map(|&(_, t)| t).collect();
+  sorted.sort_unstable();
+  PERCENTILES
+    .iter()
+    .map(|&p| {
+      let index = ((sorted.len() - 1) as f64 * p) as usize;
+      let cutoff = sorted[index];
+      let mut classes = [Moments::default(), Moments::default()];
+      for &(class, t) in samples.iter().filter(|&&(_, t)| t <= cutoff) {
+        classes[class as usize].push(t as f64);
+      }
+      welch_t(&classes[0], &classes[1]).abs()
+    })
+    .fold(0.0, f64::max)
+}
+
+/// Take `count` measurements of `operation`, each over `batch` inputs of
+/// one class, the class picked at random. As in dudect every input is
+/// prepared before the first measurement, so input generation cannot leave
+/// class-dependent state in caches or predictors. The first tenth of the
+/// measurements warms up and is dropped.
+fn measure<I>(
+  rng: &mut Rng,
+  count: usize,
+  batch: usize,
+  fixed: impl Fn() -> I,
+  random: impl Fn(&mut Rng) -> I,
+  operation: impl Fn(&mut I),
+) -> f64 {
+  let warmup = count / 10;
+  let mut runs: Vec<(bool, Vec<I>)> = (0..warmup + count)
+    .map(|_| {
+      let class = rng.next_u64() & 1 == 1;
+      let inputs = (0..batch)
+        .map(|_| if class { random(rng) } else { fixed() })
+        .collect();
+      (class, inputs)
+    })
+    .collect();
+
+  let mut samples = Vec::with_capacity(runs.len());
+  for (class, inputs) in runs.iter_mut() {
+    let start = Instant::now();
+    for input in inputs.iter_mut() {
+      operation(black_box(input));
+    }
+    samples.push((*class, start.elapsed().as_nanos() as u64));
+  }
+  black_box(&runs);
+  max_t(&samples[warmup..])
+}
+
+fn random_artifact(rng: &mut Rng, bound: i32) -> poly::Artifact {
+  let mut a = poly::Artifact::default();
+  for element in a.elements.iter_mut() {
+    *element = rng.below(2 * bound + 1) - bound;
+  }
+  a
+}
+
+struct Target {
+  name: &'static str,
+  samples: usize,
+  run: fn(&mut Rng, usize) -> f64,
+}
+
+/// Inputs per measurement for the single-element functions, which are far
+/// below the resolution of the clock on their own.
+const ELEMENT_BATCH: usize = 256;
+
+const TARGETS: &[Target] = &[
+  Target {
+    name: "montgomery_reduce",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        ELEMENT_BATCH,
+        || 0i64,
+        |rng| rng.below(QUANTA_I32) as i64 * rng.below(QUANTA_I32) as i64,
+        |a| *a = reduce::artifact_montgomery_reduce(*a) as i64,
+      )
+    },
+  },
+  Target {
+    name: "reduce32",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        ELEMENT_BATCH,
+        || 0i32,
+        |rng| rng.next_u64() as i32 >> 1,
+        |a| *a = reduce::artifact_reduce32(*a),
+      )
+    },
+  },
+  Target {
+    name: "caddq",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        ELEMENT_BATCH,
+        || 0i32,
+        |rng| rng.below(2 * QUANTA_I32) - QUANTA_I32,
+        |a| *a = reduce::artifact_caddq(*a),
+      )
+    },
+  },
+  Target {
+    name: "decompose",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        ELEMENT_BATCH,
+        || 0i32,
+        |rng| rng.below(QUANTA_I32),
+        |a| {
+          let mut low = 0;
+          *a = rounding::artifact_decompose(&mut low, *a) ^ low;
+        },
+      )
+    },
+  },
+  Target {
+    name: "power2round",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        ELEMENT_BATCH,
+        || 0i32,
+        |rng| rng.below(QUANTA_I32),
+        |a| {
+          let mut low = 0;
+          *a = rounding::artifact_power2round(&mut low, *a) ^ low;
+        },
+      )
+    },
+  },
+  Target {
+    name: "make_hint",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        ELEMENT_BATCH,
+        || (0i32, 0i32),
+        |rng| {
+          let low = rng.below(4 * GAMMA2_I32) - 2 * GAMMA2_I32;
+          (low, rng.below((QUANTA_I32 - 1) / (2 * GAMMA2_I32)))
+        },
+        |(low, high)| {
+          *low = rounding::artifact_make_hint(*low, *high) as i32;
+        },
+      )
+    },
+  },
+  Target {
+    name: "use_hint",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        ELEMENT_BATCH,
+        || (0i32, 0u8),
+        |rng| (rng.below(QUANTA_I32), (rng.next_u64() & 1) as u8),
+        |(a, hint)| *a = rounding::artifact_use_hint(*a, *hint),
+      )
+    },
+  },
+  Target {
+    name: "chknorm",
+    samples: 100_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        1,
+        poly::Artifact::default,
+        |rng| random_artifact(rng, GAMMA1_I32),
+        |a| {
+          let exceeded = poly::artifact_chknorm(a, GAMMA1_I32 / 2);
+          a.elements[0] = exceeded as i32;
+        },
+      )
+    },
+  },
+  Target {
+    name: "uniform_eta",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        1,
+        || (poly::Artifact::default(), [0u8; CRYSTALBYTES]),
+        |rng| {
+          let mut seed = [0u8; CRYSTALBYTES];
+          rng.fill(&mut seed);
+          (poly::Artifact::default(), seed)
+        },
+        |(a, seed)| poly::artifact_uniform_eta(a, &seed[..], 0),
+      )
+    },
+  },
+  Target {
+    name: "uniform_gamma1",
+    samples: 20_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        1,
+        || (poly::Artifact::default(), [0u8; CRYSTALBYTES]),
+        |rng| {
+          let mut seed = [0u8; CRYSTALBYTES];
+          rng.fill(&mut seed);
+          (poly::Artifact::default(), seed)
+        },
+        |(a, seed)| poly::artifact_uniform_gamma1(a, &seed[..], 0),
+      )
+    },
+  },
+  Target {
+    name: "keypair",
+    samples: 2_000,
+    run: |rng, n| {
+      measure(
+        rng,
+        n,
+        1,
+        || [0u8; ESSENCEBYTES],
+        |rng| {
+          let mut seed = [0u8; ESSENCEBYTES];
+          rng.fill(&mut seed);
+          seed
+        },
+        |seed| {
+          let mut public_key = [0u8; ARTIFACTKEYBYTES];
+          let mut secret_key = [0u8; SECRETKEYBYTES];
+          artifact_keypair(&mut public_key, &mut secret_key, seed);
+          black_box(&public_key);
+        },
+      )
+    },
+  },
+  Target {
+    name: "sign",
+    samples: 2_000,
+    run: |rng, n| {
+      let keypair = |seed: &[u8; ESSENCEBYTES]| {
+        let mut public_key = [0u8; ARTIFACTKEYBYTES];
+        let mut secret_key = Box::new([0u8; SECRETKEYBYTES]);
+        artifact_keypair(&mut public_key, &mut secret_key, seed);
+        secret_key
+      };
+      measure(
+        rng,
+        n,
+        1,
+        || keypair(&[0u8; ESSENCEBYTES]),
+        |rng| {
+          let mut seed = [0u8; ESSENCEBYTES];
+          rng.fill(&mut seed);
+          keypair(&seed)
+        },
+        |secret_key| {
+          let mut signature = [0u8; SIGNATUREBYTES];
+          artifact_sign(&mut signature, b"timing harness", secret_key);
+          black_box(&signature);
+        },
+      )
+    },
+  },
+];
+
+fn main() -> ExitCode {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  let strict = args.iter().any(|arg| arg == "--strict");
+  let filters: Vec<&String> =
+    args.iter().filter(|arg| !arg.starts_with('-')).collect();
+  let scale: f64 = std::env::var("EOE_CT_SCALE")
+    .ok()
+    .and_then(|scale| scale.parse().ok())
+    .unwrap_or(1.0);
+
+  let mut rng = Rng(0x454f_455f_4354);
+  let mut flagged = false;
+  println!("{:<18} {:>10} {:>9}  verdict", "target", "samples", "max |t|");
+  let selected = TARGETS.iter().filter(|target| {
+    filters.is_empty() || filters.iter().any(|f| target.name.contains(*f))
+  });
+  for target in selected {
+    let samples = ((target.samples as f64 * scale) as usize).max(MIN_SAMPLES);
+    let t = (target.run)(&mut rng, samples);
+    let verdict = if t > LEAK {
+      "leak"
+    } else if t > POSSIBLE_LEAK {
+      "possible leak"
+    } else {
+      "no evidence of leak"
+    };
+    flagged |= t > POSSIBLE_LEAK;
+    println!("{:<18} {:>10} {:>9.2}  {}", target.name, samples, t, verdict);
+  }
+
+  if strict && flagged {
+    ExitCode::FAILURE
+  } else {
+    ExitCode::SUCCESS
+  }
+}
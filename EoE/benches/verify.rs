@@ -0,0 +1,254 @@
+//! Verification throughput, stage by stage.
+//!
+//! Times full verification for the parameter set of this build, each of
+//! `artifact_verify_stage1` to `stage8` on the intermediate values of a real
+//! verification, and the primitives they spend their time in: the NTT,
+//! matrix expansion and SHAKE.
+//!
+//! ```text
+//! cargo bench --features internals --bench verify
+//! cargo bench --features internals,artifact_mode2 --bench verify
+//! cargo bench --features internals,artifact_mode5 --bench verify
+//! ```
+//!
+//! Benchmark names carry the parameter set, so the three runs land side by
+//! side in `target/criterion`. To compare against a baseline, save one with
+//! `-- --save-baseline <name>` and later pass `-- --baseline <name>`;
+//! criterion reports the change and whether it is significant.
+//!
+//! The verification figures are single-core verifications per second.
+
+use criterion::{
+  criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion,
+  Throughput,
+};
+use std::hint::black_box;
+
+use echoes_of_eternity::{
+  internals::{
+    artifact_verify_stage1, artifact_verify_stage2, artifact_verify_stage3,
+    artifact_verify_stage4, artifact_verify_stage5, artifact_verify_stage6,
+    artifact_verify_stage7, artifact_verify_stage8,
+    ntt::{transform_forward, transform_inverse},
+    polyvec::{artifact_matrix_expand, ArtifactArrayL},
+    symmetric::*,
+  },
+  verify_transformation, KeyPair, ParameterSet, CRYSTALBYTES, ELEMENTS,
+  ESSENCEBYTES, K, QUANTA_I32,
+};
+
+const MESSAGE: &[u8] = b"benchmark artifact";
+
+fn mode() -> &'static str {
+  ParameterSet::CURRENT.name()
+}
+
+fn signed() -> (KeyPair, Vec<u8>) {
+  let keys = KeyPair::from_seed(&[7; ESSENCEBYTES]);
+  let signature = keys.sign(MESSAGE).to_vec();
+  (keys, signature)
+}
+
+fn full_verification(c: &mut Criterion) {
+  let (keys, signature) = signed();
+  let mut group = c.benchmark_group("verify");
+  group.throughput(Throughput::Elements(1));
+  group.bench_function(mode(), |b| {
+    b.iter(|| {
+      verify_transformation(
+        black_box(&signature),
+        black_box(MESSAGE),
+        black_box(&keys.public),
+      )
+    })
+  });
+  group.finish();
+}
+
+fn stages(c: &mut Criterion) {
+  let (keys, signature) = signed();
+  let public = &keys.public[..];
+
+  // One pass through the pipeline supplies every stage's inputs.
+  let (essence, elements) =
+    artifact_verify_stage1(&signature, public).expect("stage 1");
+  let (seal, fragments, glyphs) =
+    artifact_verify_stage2(&signature).expect("stage 2");
+  let digest = artifact_verify_stage3(public, MESSAGE);
+  let challenge = artifact_verify_stage4(&mut seal.clone());
+  let matrix = artifact_verify_stage5(&mut essence.clone());
+  let (transformed, scaled) = artifact_verify_stage6(
+    challenge.clone(),
+    matrix.clone(),
+    fragments.clone(),
+    elements.clone(),
+  )
+  .expect("stage 6");
+  let refined = artifact_verify_stage7(transformed.clone(), scaled.clone())
+    .expect("stage 7");
+  // Timings of a pipeline that computes the wrong w' would be meaningless.
+  artifact_verify_stage8(
+    Box::new(core::array::from_fn(|_| 0)),
+    refined.clone(),
+    &glyphs,
+    digest.clone(),
+    &seal,
+    &mut [0u8; ESSENCEBYTES],
+  )
+  .expect("stages 1 to 8 accept the signature");
+
+  let mut group = c.benchmark_group(format!("verify-stages/{}", mode()));
+  group.bench_function("stage1", |b| {
+    b.iter(|| artifact_verify_stage1(black_box(&signature), black_box(public)))
+  });
+  group.bench_function("stage2", |b| {
+    b.iter(|| artifact_verify_stage2(black_box(&signature)))
+  });
+  group.bench_function("stage3", |b| {
+    b.iter(|| artifact_verify_stage3(black_box(public), black_box(MESSAGE)))
+  });
+  group.bench_function("stage4", |b| {
+    b.iter_batched_ref(
+      || seal.clone(),
+      |seal| artifact_verify_stage4(seal),
+      BatchSize::SmallInput,
+    )
+  });
+  group.bench_function("stage5", |b| {
+    b.iter_batched_ref(
+      || essence.clone(),
+      |essence| artifact_verify_stage5(essence),
+      BatchSize::SmallInput,
+    )
+  });
+  group.bench_function("stage6", |b| {
+    b.iter_batched(
+      || {
+        (
+          challenge.clone(),
+          matrix.clone(),
+          fragments.clone(),
+          elements.clone(),
+        )
+      },
+      |(challenge, matrix, fragments, elements)| {
+        artifact_verify_stage6(challenge, matrix, fragments, elements)
+      },
+      BatchSize::SmallInput,
+    )
+  });
+  group.bench_function("stage7", |b| {
+    b.iter_batched(
+      || (transformed.clone(), scaled.clone()),
+      |(transformed, scaled)| artifact_verify_stage7(transformed, scaled),
+      BatchSize::SmallInput,
+    )
+  });
+  group.bench_function("stage8", |b| {
+    b.iter_batched(
+      || {
+        (
+          Box::new(core::array::from_fn(|_| 0)),
+          refined.clone(),
+          digest.clone(),
+          [0u8; ESSENCEBYTES],
+        )
+      },
+      |(buffer, refined, digest, mut computed_seal)| {
+        artifact_verify_stage8(
+          buffer,
+          refined,
+          &glyphs,
+          digest,
+          &seal,
+          &mut computed_seal,
+        )
+      },
+      BatchSize::SmallInput,
+    )
+  });
+  group.finish();
+}
+
+fn ntt(c: &mut Criterion) {
+  let mut x = 1u32;
+  let elements: [i32; ELEMENTS] = core::array::from_fn(|_| {
+    x = x.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+    (x % QUANTA_I32 as u32) as i32
+  });
+
+  let mut group = c.benchmark_group("ntt");
+  group.bench_function("forward", |b| {
+    b.iter_batched_ref(
+      || elements,
+      |a| transform_forward(a),
+      BatchSize::SmallInput,
+    )
+  });
+  group.bench_function("inverse", |b| {
+    b.iter_batched_ref(
+      || elements,
+      |a| transform_inverse(a),
+      BatchSize::SmallInput,
+    )
+  });
+  group.finish();
+}
+
+fn matrix_expansion(c: &mut Criterion) {
+  let essence = [7u8; ESSENCEBYTES];
+  let mut matrix = vec![ArtifactArrayL::default(); K];
+  c.bench_function(&format!("matrix-expand/{}", mode()), |b| {
+    b.iter(|| artifact_matrix_expand(&mut matrix, black_box(&essence)))
+  });
+}
+
+fn shake(c: &mut Criterion) {
+  let mut group = c.benchmark_group("shake256-absorb");
+  for len in [64, 1024, 16 * 1024] {
+    let input = vec![0xa5u8; len];
+    let mut digest = [0u8; CRYSTALBYTES];
+    group.throughput(Throughput::Bytes(len as u64));
+    group.bench_with_input(BenchmarkId::from_parameter(len), &input, |b, i| {
+      b.iter(|| compute_crh(&mut digest, black_box(i), i.len()))
+    });
+  }
+  group.finish();
+
+  // Squeezing dominates matrix expansion (SHAKE128) and secret sampling
+  // (SHAKE256).
+  const BLOCKS: usize = 32;
+  let mut group = c.benchmark_group("shake-squeeze");
+  let essence = [7u8; CRYSTALBYTES];
+
+  let mut output = vec![0u8; BLOCKS * STREAM_BLOCKBYTES];
+  group.throughput(Throughput::Bytes(output.len() as u64));
+  group.bench_function("shake128", |b| {
+    b.iter(|| {
+      let mut state = EternityStreamState::default();
+      eternity_stream_init(&mut state, &essence, 0);
+      eternity_stream_squeeze_blocks(&mut output, BLOCKS as u64, &mut state);
+    })
+  });
+
+  let mut output = vec![0u8; BLOCKS * STREAM256_BLOCKBYTES];
+  group.throughput(Throughput::Bytes(output.len() as u64));
+  group.bench_function("shake256", |b| {
+    b.iter(|| {
+      let mut state = EternityStream256State::default();
+      eternity_stream256_init(&mut state, &essence, 0);
+      eternity_stream256_squeeze_blocks(&mut output, BLOCKS as u64, &mut state);
+    })
+  });
+  group.finish();
+}
+
+criterion_group!(
+  benches,
+  full_verification,
+  stages,
+  ntt,
+  matrix_expansion,
+  shake
+);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+#![no_main]
+
+use echoes_of_eternity::*;
+use libfuzzer_sys::fuzz_target;
+
+// The DER reader only accepts minimal encodings, so decoded keys encode
+// back to the input.
+fuzz_target!(|data: &[u8]| {
+  if let Ok(key) = ArtifactPublicKey::from_public_key_der(data) {
+    assert_eq!(key.to_public_key_der(), data);
+  }
+  if let Ok(seed) = ArtifactSeed::from_pkcs8_der(data) {
+    let reparsed = ArtifactSeed::from_pkcs8_der(&seed.to_pkcs8_der())
+      .expect("re-encoded seed document");
+    assert_eq!(reparsed.as_bytes(), seed.as_bytes());
+  }
+  let _ = ArtifactSecretKey::from_pkcs8_der(data);
+  let _ = Certificate::from_der(data);
+  let _ = CertificationRequest::from_der(data);
+});
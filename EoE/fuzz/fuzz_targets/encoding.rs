@@ -0,0 +1,25 @@
+#![no_main]
+
+use echoes_of_eternity::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  // Encoders: every byte string survives a round trip.
+  assert_eq!(hex_decode(&hex_encode(data)).as_deref(), Some(data));
+  assert_eq!(base64_decode(&base64_encode(data)).as_deref(), Some(data));
+  let pem = pem_encode("PUBLIC KEY", data);
+  assert_eq!(pem_decode(&pem, "PUBLIC KEY").ok().as_deref(), Some(data));
+
+  // Decoders: arbitrary text is rejected or decodes to bytes that encode
+  // back to the same text, up to case for hex.
+  let Ok(text) = core::str::from_utf8(data) else {
+    return;
+  };
+  if let Some(bytes) = hex_decode(text) {
+    assert_eq!(hex_encode(&bytes), text.to_ascii_lowercase());
+  }
+  if let Some(bytes) = base64_decode(text) {
+    assert_eq!(base64_encode(&bytes), text);
+  }
+  let _ = pem_decode(text, "PUBLIC KEY");
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use echoes_of_eternity::{
+  internals::{packing::*, polyvec::*},
+  ARTIFACTKEYBYTES, ESSENCEBYTES,
+};
+use libfuzzer_sys::fuzz_target;
+
+// Every public key of the right length is valid and packs back to itself;
+// any other length must be rejected without panicking.
+fuzz_target!(|data: &[u8]| {
+  let mut essence = [0u8; ESSENCEBYTES];
+  let mut elements = ArtifactArrayK::default();
+  let unpacked = unpack_artifact_key(&mut essence, &mut elements, data);
+  assert_eq!(unpacked.is_ok(), data.len() == ARTIFACTKEYBYTES);
+  if unpacked.is_err() {
+    return;
+  }
+
+  let mut repacked = [0u8; ARTIFACTKEYBYTES];
+  pack_artifact_key(&mut repacked, &essence, &elements);
+  assert_eq!(&repacked[..], data);
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use echoes_of_eternity::{
+  internals::{packing::*, polyvec::*},
+  ESSENCEBYTES, SIGNATUREBYTES,
+};
+use libfuzzer_sys::fuzz_target;
+
+// The signature encoding is canonical: whatever unpacks must pack back to
+// the same bytes, and everything else must be rejected without panicking.
+fuzz_target!(|data: &[u8]| {
+  let mut seal = [0u8; ESSENCEBYTES];
+  let mut shards = ArtifactArrayL::default();
+  let mut glyph = ArtifactArrayK::default();
+  if unpack_artifact_signature(&mut seal, &mut shards, &mut glyph, data)
+    .is_err()
+  {
+    return;
+  }
+
+  let mut repacked = [0u8; SIGNATUREBYTES];
+  pack_artifact_signature(&mut repacked, &seal, &shards, &glyph);
+  assert_eq!(&repacked[..], data);
+});
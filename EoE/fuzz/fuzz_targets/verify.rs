@@ -0,0 +1,26 @@
+#![no_main]
+
+use echoes_of_eternity::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  // Arbitrary lengths must be rejected, never sliced.
+  let (left, right) = data.split_at(data.len() / 2);
+  let _ = verify_transformation(left, right, right);
+  let _ = prevalidate_transformation(left, right);
+  let _ = open_envelope(data);
+
+  // Cut well-sized inputs at the encoded lengths so they reach the
+  // arithmetic.
+  if data.len() < SIGNATUREBYTES + ARTIFACTKEYBYTES {
+    return;
+  }
+  let (signature, rest) = data.split_at(SIGNATUREBYTES);
+  let (public_key, message) = rest.split_at(ARTIFACTKEYBYTES);
+  let verified = verify_transformation(signature, message, public_key);
+
+  // Prevalidation runs a subset of the checks of verification.
+  if verified.is_ok() {
+    assert!(prevalidate_transformation(signature, public_key).is_ok());
+  }
+});
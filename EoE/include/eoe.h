@@ -0,0 +1,116 @@
+#ifndef EOE_H
+#define EOE_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The call succeeded.
+#define EOE_OK 0
+
+// A required pointer was null.
+#define EOE_ERR_NULL_POINTER -1
+
+// A buffer has the wrong length or malformed contents.
+#define EOE_ERR_INVALID_INPUT -2
+
+// The signature does not verify.
+#define EOE_ERR_VERIFICATION_FAILED -3
+
+// The input is sized for a different parameter set than the build.
+#define EOE_ERR_PARAMETER_MISMATCH -4
+
+// The algorithm is not implemented by the build.
+#define EOE_ERR_UNSUPPORTED_ALGORITHM -5
+
+// The operating system could not provide randomness.
+#define EOE_ERR_RANDOMNESS -6
+
+// The library hit an internal error; this is a bug.
+#define EOE_ERR_INTERNAL -7
+
+#ifdef __cplusplus
+extern "C" {
+#endif  // __cplusplus
+
+// Security level of the parameter set the library was built for: 2, 3 or 5.
+uint32_t eoe_parameter_set(void);
+
+// Envelope algorithm identifier of the library's algorithm.
+uint8_t eoe_algorithm_id(void);
+
+// Size of a public key for a parameter set, or 0 if the level is unknown.
+size_t eoe_public_key_bytes(uint32_t parameter_set);
+
+// Size of a secret key for a parameter set, or 0 if the level is unknown.
+size_t eoe_secret_key_bytes(uint32_t parameter_set);
+
+// Size of a signature for a parameter set, or 0 if the level is unknown.
+size_t eoe_signature_bytes(uint32_t parameter_set);
+
+// Size of a key generation seed.
+size_t eoe_seed_bytes(void);
+
+// Static, NUL-terminated description of a status code.
+const char *eoe_strerror(int32_t code);
+
+// Derive a key pair deterministically from a seed.
+//
+// # Safety
+//
+// `public_key` and `secret_key` must be valid for writes of their lengths
+// and `seed` valid for reads of `seed_len` bytes.
+int32_t eoe_keypair_from_seed(uint8_t *public_key,
+                              size_t public_key_len,
+                              uint8_t *secret_key,
+                              size_t secret_key_len,
+                              const uint8_t *seed,
+                              size_t seed_len);
+
+// Generate a key pair from operating system randomness.
+//
+// Returns `EOE_ERR_RANDOMNESS` if the library was built without the
+// `getrandom` feature or the operating system has no randomness to give.
+//
+// # Safety
+//
+// `public_key` and `secret_key` must be valid for writes of their lengths.
+int32_t eoe_keypair(uint8_t *public_key,
+                    size_t public_key_len,
+                    uint8_t *secret_key,
+                    size_t secret_key_len);
+
+// Sign a message.
+//
+// # Safety
+//
+// `signature` must be valid for writes of `signature_len` bytes, `message`
+// and `secret_key` valid for reads of their lengths.
+int32_t eoe_sign(uint8_t *signature,
+                 size_t signature_len,
+                 const uint8_t *message,
+                 size_t message_len,
+                 const uint8_t *secret_key,
+                 size_t secret_key_len);
+
+// Verify a signature, returning `EOE_OK` only if it is valid.
+//
+// # Safety
+//
+// Each pointer must be valid for reads of its length.
+int32_t eoe_verify(const uint8_t *signature,
+                   size_t signature_len,
+                   const uint8_t *message,
+                   size_t message_len,
+                   const uint8_t *public_key,
+                   size_t public_key_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* EOE_H */
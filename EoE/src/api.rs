@@ -1,14 +1,105 @@
+use crate::envelope::{verify_with_algorithm, AlgorithmId};
+use crate::keys::{ArtifactPublicKey, ArtifactSecretKey};
 use crate::params::SIGNATURE_BYTES;
+use crate::params::{
+  ARTIFACTKEYBYTES, ESSENCEBYTES, SECRETKEYBYTES, SIGNATUREBYTES,
+};
+use crate::sign::{artifact_keypair, artifact_prevalidate, artifact_sign};
 use crate::transform::*;
+#[cfg(feature = "artifact-trace")]
+use crate::trace::{self, ArtifactTrace};
+use zeroize::{Zeroize, Zeroizing};
 
 /// Enum representing possible errors in verification
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum EternityError {
   InvalidInput,
   VerificationFailed,
+  /// The encoding is well formed for a different parameter set than the one
+  /// this build was compiled for
+  ParameterMismatch,
+  /// The algorithm is unknown or not implemented by this build
+  UnsupportedAlgorithm,
+}
+
+impl core::fmt::Display for EternityError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let message = match self {
+      EternityError::InvalidInput => "invalid input",
+      EternityError::VerificationFailed => "verification failed",
+      EternityError::ParameterMismatch => "parameter set mismatch",
+      EternityError::UnsupportedAlgorithm => "unsupported algorithm",
+    };
+    f.write_str(message)
+  }
+}
+
+impl std::error::Error for EternityError {}
+
+/// An artifact key pair
+///
+/// The public key is exposed as raw bytes for use with
+/// [`verify_transformation`]; the secret key stays boxed inside the pair.
+#[derive(Clone)]
+pub struct KeyPair {
+  pub public: [u8; ARTIFACTKEYBYTES],
+  secret: Box<[u8; SECRETKEYBYTES]>,
+}
+
+impl KeyPair {
+  /// Derive a key pair deterministically from a 32-byte seed
+  pub fn from_seed(seed: &[u8; ESSENCEBYTES]) -> KeyPair {
+    let mut public = [0u8; ARTIFACTKEYBYTES];
+    let mut secret = Box::new([0u8; SECRETKEYBYTES]);
+    artifact_keypair(&mut public, &mut secret, seed);
+    KeyPair { public, secret }
+  }
+
+  /// Generate a key pair from operating system randomness
+  ///
+  /// Panics if the operating system cannot provide randomness.
+  #[cfg(feature = "getrandom")]
+  pub fn generate() -> KeyPair {
+    let mut seed = Zeroizing::new([0u8; ESSENCEBYTES]);
+    getrandom::getrandom(&mut seed[..]).expect("OS randomness unavailable");
+    KeyPair::from_seed(&seed)
+  }
+
+  /// Sign an artifact, returning a detached signature
+  pub fn sign(&self, artifact: &[u8]) -> [u8; SIGNATUREBYTES] {
+    let mut signature = [0u8; SIGNATUREBYTES];
+    artifact_sign(&mut signature, artifact, &self.secret);
+    signature
+  }
+
+  /// The raw secret key bytes
+  pub fn expose_secret(&self) -> &[u8] {
+    &self.secret[..]
+  }
+
+  /// The public key as a validated type
+  pub fn public_key(&self) -> ArtifactPublicKey {
+    ArtifactPublicKey::from_array(&self.public)
+  }
+
+  /// The secret key as a validated type
+  pub fn secret_key(&self) -> ArtifactSecretKey {
+    ArtifactSecretKey::from_array(&self.secret)
+  }
+}
+
+impl Drop for KeyPair {
+  fn drop(&mut self) {
+    self.secret.zeroize();
+  }
 }
 
 /// Verify an artifact transformation using the provided key
 ///
+/// Dispatches through the [`crate::SignatureScheme`] registered for
+/// [`AlgorithmId::CURRENT`]; use [`verify_with_algorithm`] for keys and
+/// signatures of another algorithm.
+///
 /// Example:
 /// ```
 /// # use echoes_of_eternity::*;
@@ -19,6 +110,15 @@ pub enum EternityError {
 /// assert!(verification_result.is_ok());
 /// ```
 pub fn verify_transformation(
+  signature: &[u8],
+  artifact: &[u8],
+  public_key: &[u8],
+) -> Result<(), EternityError> {
+  verify_with_algorithm(AlgorithmId::CURRENT, signature, artifact, public_key)
+}
+
+/// The transformation behind [`AlgorithmId::CURRENT`]
+pub(crate) fn verify_artifact(
   signature: &[u8], 
   artifact: &[u8], 
   public_key: &[u8]
@@ -36,3 +136,57 @@ pub fn verify_transformation(
   transform_verify(&signature_box, &artifact_box, &public_key_box)
 }
 
+
+/// Cheaply reject structurally malformed signatures
+///
+/// Runs the length, hint-encoding and norm checks of verification without
+/// the matrix expansion or NTTs. Intended for filtering spam before full
+/// verification: `Ok(())` only means the signature is well formed, it still
+/// has to pass [`verify_transformation`]. A key or signature length that
+/// belongs to another parameter set is reported as `ParameterMismatch`.
+pub fn prevalidate_transformation(
+  signature: &[u8],
+  public_key: &[u8],
+) -> Result<(), EternityError> {
+  artifact_prevalidate(signature, public_key)
+}
+
+/// Derive a key pair and capture its intermediate values
+///
+/// Requires the `artifact-trace` feature. The returned trace holds rho, t1
+/// and tr; see [`verify_transformation_traced`].
+#[cfg(feature = "artifact-trace")]
+pub fn keypair_traced(seed: &[u8; ESSENCEBYTES]) -> (KeyPair, ArtifactTrace) {
+  trace::with_trace("keypair", || KeyPair::from_seed(seed))
+}
+
+/// Sign an artifact and capture the intermediate values of every attempt
+///
+/// Requires the `artifact-trace` feature. After mu, each attempt of the
+/// rejection loop records its counter under `attempt`, then y, w1, c_tilde
+/// and z, and h once it gets past the norm checks. The last attempt is the
+/// one that produced the returned signature.
+#[cfg(feature = "artifact-trace")]
+pub fn sign_transformation_traced(
+  keys: &KeyPair,
+  artifact: &[u8],
+) -> ([u8; SIGNATUREBYTES], ArtifactTrace) {
+  trace::with_trace("sign", || keys.sign(artifact))
+}
+
+/// Verify an artifact transformation and capture its intermediate values
+///
+/// Requires the `artifact-trace` feature. The returned trace holds mu, the
+/// challenge, w' before and after the hint and the packed w1, and can be
+/// exported with [`ArtifactTrace::to_json`] for diffing against other
+/// implementations.
+#[cfg(feature = "artifact-trace")]
+pub fn verify_transformation_traced(
+  signature: &[u8],
+  artifact: &[u8],
+  public_key: &[u8],
+) -> (Result<(), EternityError>, ArtifactTrace) {
+  trace::with_trace("verify", || {
+    verify_transformation(signature, artifact, public_key)
+  })
+}
@@ -0,0 +1,101 @@
+//! Parallel batch verification.
+//!
+//! Spreads a batch of `(signature, message, public_key)` items over the rayon
+//! thread pool. The message-independent work of verification (matrix
+//! expansion and the NTT of the key) is done once per distinct key and shared
+//! by every thread verifying under that key, so a batch dominated by a few
+//! keys costs little more than the per-signature work.
+
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::{
+  api::EternityError,
+  sign::{artifact_prepare_key, artifact_verify_prepared, ArtifactPreparedKey},
+  ValidationError,
+};
+
+fn verification_error(error: ValidationError) -> EternityError {
+  match error {
+    ValidationError::Verify => EternityError::VerificationFailed,
+    _ => EternityError::InvalidInput,
+  }
+}
+
+/// A public key with its verification precomputation done
+///
+/// Worth building for a key that verifies many signatures; it is `Send` and
+/// `Sync`, so one instance can serve every thread.
+#[derive(Clone)]
+pub struct PreparedKey {
+  inner: ArtifactPreparedKey,
+}
+
+impl PreparedKey {
+  /// Prepare an encoded public key of the compiled parameter set
+  pub fn new(public_key: &[u8]) -> Result<Self, EternityError> {
+    artifact_prepare_key(public_key)
+      .map(|inner| PreparedKey { inner })
+      .map_err(|_| EternityError::InvalidInput)
+  }
+
+  /// Verify `signature` over `artifact`, as [`verify_transformation`] does
+  ///
+  /// [`verify_transformation`]: crate::verify_transformation
+  pub fn verify(
+    &self,
+    signature: &[u8],
+    artifact: &[u8],
+  ) -> Result<(), EternityError> {
+    artifact_verify_prepared(signature, artifact, &self.inner)
+      .map_err(verification_error)
+  }
+}
+
+/// Verify a batch of `(signature, artifact, public_key)` items in parallel
+///
+/// Returns one result per item, in input order: `Ok(())` exactly when
+/// [`verify_transformation`] accepts the item. Distinct keys are
+/// prepared once, in parallel, before the signatures are spread over the
+/// current rayon pool; run inside [`rayon::ThreadPool::install`] to choose
+/// the pool.
+///
+/// [`verify_transformation`]: crate::verify_transformation
+pub fn verify_batch<S, A, P>(
+  items: &[(S, A, P)],
+) -> Vec<Result<(), EternityError>>
+where
+  S: AsRef<[u8]> + Sync,
+  A: AsRef<[u8]> + Sync,
+  P: AsRef<[u8]> + Sync,
+{
+  let mut slots = HashMap::new();
+  let mut keys = Vec::new();
+  let key_slots: Vec<usize> = items
+    .iter()
+    .map(|(_, _, public_key)| {
+      let public_key = public_key.as_ref();
+      *slots.entry(public_key).or_insert_with(|| {
+        keys.push(public_key);
+        keys.len() - 1
+      })
+    })
+    .collect();
+
+  let prepared: Vec<Result<PreparedKey, EternityError>> = keys
+    .par_iter()
+    .map(|public_key| PreparedKey::new(public_key))
+    .collect();
+
+  items
+    .par_iter()
+    .zip(key_slots.par_iter())
+    .map(|((signature, artifact, _), &slot)| {
+      prepared[slot]
+        .as_ref()
+        .map_err(|&error| error)?
+        .verify(signature.as_ref(), artifact.as_ref())
+    })
+    .collect()
+}
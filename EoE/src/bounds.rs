@@ -0,0 +1,128 @@
+//! Claimed element bounds for artifact pipelines.
+//!
+//! With the `artifact-bounds` feature every [`Artifact`] carries a claim on
+//! the absolute value of its elements. Each operation in `poly.rs` asserts
+//! that the claims of its inputs meet its documented precondition and derives
+//! the claim of its output from its documented postcondition. A reordered
+//! pipeline therefore panics at the first stage whose worst case could
+//! overflow an `i32` or leave a reduction's range, whether or not the values
+//! at hand happen to. Claims are checked against the actual elements on the
+//! way, so a function that writes elements directly must finish with
+//! [`claim`].
+//!
+//! Without the feature every function here is an inlined no-op.
+
+use crate::poly::Artifact;
+
+/// Largest claim an `i32` element can carry.
+pub const I32_BOUND: i64 = i32::MAX as i64;
+
+/// The claimed bound of `a`, or `0` without the feature.
+#[cfg(feature = "artifact-bounds")]
+pub fn claimed(a: &Artifact) -> i64 {
+  a.bound
+}
+
+/// Assert that a derived claim still fits in an `i32`, before `operation`
+/// computes elements that could reach it.
+#[cfg(feature = "artifact-bounds")]
+#[track_caller]
+pub fn fits_i32(bound: i64, operation: &str) {
+  assert!(
+    bound <= I32_BOUND,
+    "{operation}: claimed bound {bound} overflows i32"
+  );
+}
+
+/// Assert that `a` claims at most `limit`, the precondition of `operation`.
+#[cfg(feature = "artifact-bounds")]
+#[track_caller]
+pub fn require(a: &Artifact, limit: i64, operation: &str) {
+  check_elements(a, a.bound, operation);
+  assert!(
+    a.bound <= limit,
+    "{operation}: input claims bound {} but at most {limit} is allowed",
+    a.bound
+  );
+}
+
+/// Assert that the claims of `a` and `b` bound their elementwise products
+/// by `limit`, the precondition of `operation`.
+#[cfg(feature = "artifact-bounds")]
+#[track_caller]
+pub fn require_product(
+  a: &Artifact,
+  b: &Artifact,
+  limit: i64,
+  operation: &str,
+) {
+  let product = a.bound * b.bound;
+  assert!(
+    product <= limit,
+    "{operation}: input claims {} and {} allow products up to {product} \
+     but at most {limit} is allowed",
+    a.bound,
+    b.bound
+  );
+}
+
+/// Assert that every element of `a` is a standard representative in
+/// `[0, QUANTA)`, the precondition of `operation`.
+///
+/// Checked on the elements rather than the claim: `caddq` only yields
+/// standard representatives from inputs above `-QUANTA`, which the claims
+/// after `t = A*s1 + s2` cannot show.
+#[cfg(feature = "artifact-bounds")]
+#[track_caller]
+pub fn require_standard(a: &Artifact, operation: &str) {
+  use crate::params::QUANTA_I32;
+
+  let standard = 0..QUANTA_I32;
+  if let Some(i) = a.elements.iter().position(|x| !standard.contains(x)) {
+    panic!("{operation}: element {i} is not a standard representative");
+  }
+}
+
+/// Set the claim of `a` to `bound`, the postcondition of `operation`, after
+/// checking that its elements meet it.
+#[cfg(feature = "artifact-bounds")]
+#[track_caller]
+pub fn claim(a: &mut Artifact, bound: i64, operation: &str) {
+  fits_i32(bound, operation);
+  check_elements(a, bound, operation);
+  a.bound = bound;
+}
+
+#[cfg(feature = "artifact-bounds")]
+#[track_caller]
+fn check_elements(a: &Artifact, bound: i64, operation: &str) {
+  if let Some(i) = a.elements.iter().position(|&x| (x as i64).abs() > bound) {
+    panic!("{operation}: element {i} exceeds claimed bound {bound}");
+  }
+}
+
+#[cfg(not(feature = "artifact-bounds"))]
+#[inline(always)]
+pub fn claimed(_: &Artifact) -> i64 {
+  0
+}
+
+#[cfg(not(feature = "artifact-bounds"))]
+#[inline(always)]
+pub fn fits_i32(_: i64, _: &str) {}
+
+#[cfg(not(feature = "artifact-bounds"))]
+#[inline(always)]
+pub fn require(_: &Artifact, _: i64, _: &str) {}
+
+#[cfg(not(feature = "artifact-bounds"))]
+#[inline(always)]
+pub fn require_product(_: &Artifact, _: &Artifact, _: i64, _: &str) {}
+
+#[cfg(not(feature = "artifact-bounds"))]
+#[inline(always)]
+pub fn require_standard(_: &Artifact, _: &str) {}
+
+#[cfg(not(feature = "artifact-bounds"))]
+#[inline(always)]
+pub fn claim(_: &mut Artifact, _: i64, _: &str) {}
@@ -0,0 +1,369 @@
+//! Composite Ed25519 + artifact signatures.
+//!
+//! Follows the construction of the IETF LAMPS composite signature draft
+//! (draft-ietf-lamps-pq-composite-sigs): both components sign the same
+//! domain-separated representative
+//!
+//! ```text
+//! M' = Prefix || Label || len(ctx) || ctx || SHA-512(M)
+//! ```
+//!
+//! and keys and signatures are the artifact encoding followed by the Ed25519
+//! one. A composite signature verifies only if both components verify, so it
+//! stays secure as long as either algorithm does.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+use crate::{
+  api::*, envelope::*, keys::ArtifactSecretKey, params::*,
+  scheme::SignatureScheme,
+};
+
+/// Prefix shared by every composite signature representative.
+pub const COMPOSITE_PREFIX: &[u8] = b"CompositeAlgorithmSignatures2025";
+
+/// Longest context string a representative can carry.
+pub const MAX_CONTEXT_BYTES: usize = 255;
+
+/// An artifact algorithm paired with Ed25519 and SHA-512 prehashing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CompositeAlgorithm {
+  pub component: AlgorithmId,
+}
+
+impl CompositeAlgorithm {
+  /// The composite built on the algorithm implemented by this build.
+  pub const CURRENT: CompositeAlgorithm = CompositeAlgorithm {
+    component: AlgorithmId::CURRENT,
+  };
+
+  /// Domain separation label mixed into every representative.
+  ///
+  /// FIPS 204 pairings use the labels of the draft; round 3 Dilithium, which
+  /// the draft does not cover, follows the same naming pattern.
+  pub const fn label(self) -> &'static [u8] {
+    match self.component {
+      AlgorithmId::MlDsa44 | AlgorithmId::MlDsa44Ed25519 => {
+        b"COMPSIG-MLDSA44-Ed25519-SHA512"
+      }
+      AlgorithmId::MlDsa65 | AlgorithmId::MlDsa65Ed25519 => {
+        b"COMPSIG-MLDSA65-Ed25519-SHA512"
+      }
+      AlgorithmId::MlDsa87 => b"COMPSIG-MLDSA87-Ed25519-SHA512",
+      AlgorithmId::Dilithium2 | AlgorithmId::Dilithium2Ed25519 => {
+        b"COMPSIG-Dilithium2-Ed25519-SHA512"
+      }
+      AlgorithmId::Dilithium3 | AlgorithmId::Dilithium3Ed25519 => {
+        b"COMPSIG-Dilithium3-Ed25519-SHA512"
+      }
+      AlgorithmId::Dilithium5 | AlgorithmId::Dilithium5Ed25519 => {
+        b"COMPSIG-Dilithium5-Ed25519-SHA512"
+      }
+      AlgorithmId::HashMlDsa44Sha512 => b"COMPSIG-HashMLDSA44-Ed25519-SHA512",
+      AlgorithmId::HashMlDsa65Sha512 => b"COMPSIG-HashMLDSA65-Ed25519-SHA512",
+      AlgorithmId::HashMlDsa87Sha512 => b"COMPSIG-HashMLDSA87-Ed25519-SHA512",
+    }
+  }
+
+  /// The composite for an algorithm identifier, if it names one.
+  pub const fn from_id(id: AlgorithmId) -> Option<CompositeAlgorithm> {
+    if id.is_composite() {
+      Some(CompositeAlgorithm {
+        component: id.component(),
+      })
+    } else {
+      None
+    }
+  }
+
+  /// The algorithm identifier of the composite, where one is assigned.
+  pub const fn id(self) -> Option<AlgorithmId> {
+    match self.component.component() {
+      AlgorithmId::Dilithium2 => Some(AlgorithmId::Dilithium2Ed25519),
+      AlgorithmId::Dilithium3 => Some(AlgorithmId::Dilithium3Ed25519),
+      AlgorithmId::Dilithium5 => Some(AlgorithmId::Dilithium5Ed25519),
+      AlgorithmId::MlDsa44 => Some(AlgorithmId::MlDsa44Ed25519),
+      AlgorithmId::MlDsa65 => Some(AlgorithmId::MlDsa65Ed25519),
+      _ => None,
+    }
+  }
+
+  /// Object identifier arcs assigned by the draft, where there is one.
+  pub const fn oid(self) -> Option<&'static [u32]> {
+    match self.id() {
+      Some(id) => id.oid(),
+      None => None,
+    }
+  }
+
+  /// Size of a composite public key.
+  pub const fn public_key_bytes(self) -> usize {
+    self.component.public_key_bytes() + ED25519_PUBLIC_KEY_BYTES
+  }
+
+  /// Size of a composite signature.
+  pub const fn signature_bytes(self) -> usize {
+    self.component.signature_bytes() + ED25519_SIGNATURE_BYTES
+  }
+
+  /// Build the representative `M'` both components sign.
+  pub fn message_representative(
+    self,
+    message: &[u8],
+    context: &[u8],
+  ) -> Result<Vec<u8>, EternityError> {
+    if context.len() > MAX_CONTEXT_BYTES {
+      return Err(EternityError::InvalidInput);
+    }
+    let label = self.label();
+    let mut out = Vec::with_capacity(
+      COMPOSITE_PREFIX.len() + label.len() + 1 + context.len() + 64,
+    );
+    out.extend_from_slice(COMPOSITE_PREFIX);
+    out.extend_from_slice(label);
+    out.push(context.len() as u8);
+    out.extend_from_slice(context);
+    out.extend_from_slice(&Sha512::digest(message));
+    Ok(out)
+  }
+}
+
+/// A composite public key: artifact key followed by the Ed25519 key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompositePublicKey {
+  algorithm: CompositeAlgorithm,
+  artifact_key: Vec<u8>,
+  ed25519: VerifyingKey,
+}
+
+impl CompositePublicKey {
+  /// Assemble a key from its components.
+  pub fn from_components(
+    algorithm: CompositeAlgorithm,
+    artifact_key: &[u8],
+    ed25519: &[u8; ED25519_PUBLIC_KEY_BYTES],
+  ) -> Result<Self, EternityError> {
+    if artifact_key.len() != algorithm.component.public_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    let ed25519 = VerifyingKey::from_bytes(ed25519)
+      .map_err(|_| EternityError::InvalidInput)?;
+    Ok(CompositePublicKey {
+      algorithm,
+      artifact_key: artifact_key.to_vec(),
+      ed25519,
+    })
+  }
+
+  /// Decode the concatenated encoding.
+  pub fn from_bytes(
+    algorithm: CompositeAlgorithm,
+    bytes: &[u8],
+  ) -> Result<Self, EternityError> {
+    if bytes.len() != algorithm.public_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    let (artifact_key, ed25519) =
+      bytes.split_at(algorithm.component.public_key_bytes());
+    let ed25519 = ed25519
+      .try_into()
+      .map_err(|_| EternityError::InvalidInput)?;
+    CompositePublicKey::from_components(algorithm, artifact_key, ed25519)
+  }
+
+  /// The concatenated encoding.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut out = Vec::with_capacity(self.algorithm.public_key_bytes());
+    out.extend_from_slice(&self.artifact_key);
+    out.extend_from_slice(self.ed25519.as_bytes());
+    out
+  }
+
+  pub fn algorithm(&self) -> CompositeAlgorithm {
+    self.algorithm
+  }
+
+  pub fn artifact_key(&self) -> &[u8] {
+    &self.artifact_key
+  }
+
+  pub fn ed25519_key(&self) -> &VerifyingKey {
+    &self.ed25519
+  }
+
+  /// Verify a composite signature over `message` in `context`.
+  ///
+  /// Both components are always checked; the result is `Ok` only if both
+  /// verify.
+  pub fn verify(
+    &self,
+    message: &[u8],
+    context: &[u8],
+    signature: &CompositeSignature,
+  ) -> Result<(), EternityError> {
+    if signature.algorithm != self.algorithm {
+      return Err(EternityError::InvalidInput);
+    }
+    let representative =
+      self.algorithm.message_representative(message, context)?;
+
+    let artifact = verify_with_algorithm(
+      self.algorithm.component,
+      &signature.artifact_signature,
+      &representative,
+      &self.artifact_key,
+    );
+    let ed25519 = self
+      .ed25519
+      .verify_strict(&representative, &signature.ed25519)
+      .map_err(|_| EternityError::VerificationFailed);
+
+    artifact.and(ed25519)
+  }
+}
+
+/// A composite signature: artifact signature followed by the Ed25519 one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompositeSignature {
+  algorithm: CompositeAlgorithm,
+  artifact_signature: Vec<u8>,
+  ed25519: Signature,
+}
+
+impl CompositeSignature {
+  /// Assemble a signature from component signatures over
+  /// [`CompositeAlgorithm::message_representative`].
+  pub fn from_components(
+    algorithm: CompositeAlgorithm,
+    artifact_signature: &[u8],
+    ed25519: &[u8; ED25519_SIGNATURE_BYTES],
+  ) -> Result<Self, EternityError> {
+    if artifact_signature.len() != algorithm.component.signature_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    Ok(CompositeSignature {
+      algorithm,
+      artifact_signature: artifact_signature.to_vec(),
+      ed25519: Signature::from_bytes(ed25519),
+    })
+  }
+
+  /// Decode the concatenated encoding.
+  pub fn from_bytes(
+    algorithm: CompositeAlgorithm,
+    bytes: &[u8],
+  ) -> Result<Self, EternityError> {
+    if bytes.len() != algorithm.signature_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    let (artifact_signature, ed25519) =
+      bytes.split_at(algorithm.component.signature_bytes());
+    let ed25519 = ed25519
+      .try_into()
+      .map_err(|_| EternityError::InvalidInput)?;
+    CompositeSignature::from_components(algorithm, artifact_signature, ed25519)
+  }
+
+  /// The concatenated encoding.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut out = Vec::with_capacity(self.algorithm.signature_bytes());
+    out.extend_from_slice(&self.artifact_signature);
+    out.extend_from_slice(&self.ed25519.to_bytes());
+    out
+  }
+
+  pub fn algorithm(&self) -> CompositeAlgorithm {
+    self.algorithm
+  }
+
+  pub fn artifact_signature(&self) -> &[u8] {
+    &self.artifact_signature
+  }
+
+  pub fn ed25519_signature(&self) -> &Signature {
+    &self.ed25519
+  }
+}
+
+/// The composite over [`AlgorithmId::CURRENT`], signing with an empty
+/// context.
+///
+/// Secret keys are the artifact secret key followed by the 32-byte Ed25519
+/// seed, and key generation takes the artifact seed followed by the Ed25519
+/// one.
+pub(crate) struct CompositeScheme;
+
+impl SignatureScheme for CompositeScheme {
+  fn algorithm(&self) -> AlgorithmId {
+    AlgorithmId::CURRENT_COMPOSITE
+  }
+
+  fn seed_bytes(&self) -> usize {
+    ESSENCEBYTES + ED25519_SECRET_KEY_BYTES
+  }
+
+  fn keypair_from_seed(
+    &self,
+    seed: &[u8],
+  ) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), EternityError> {
+    if seed.len() != self.seed_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    let (artifact_seed, ed25519_seed) = seed.split_at(ESSENCEBYTES);
+    let artifact_seed = artifact_seed
+      .try_into()
+      .map_err(|_| EternityError::InvalidInput)?;
+    let ed25519_seed = ed25519_seed
+      .try_into()
+      .map_err(|_| EternityError::InvalidInput)?;
+
+    let keys = KeyPair::from_seed(artifact_seed);
+    let ed25519 = SigningKey::from_bytes(ed25519_seed);
+
+    let mut public_key = keys.public.to_vec();
+    public_key.extend_from_slice(ed25519.verifying_key().as_bytes());
+    // Sized up front so growing it never leaves a secret copy behind.
+    let mut secret_key = Zeroizing::new(Vec::with_capacity(
+      self.algorithm().secret_key_bytes(),
+    ));
+    secret_key.extend_from_slice(keys.expose_secret());
+    secret_key.extend_from_slice(ed25519_seed);
+    Ok((public_key, secret_key))
+  }
+
+  fn sign(
+    &self,
+    secret_key: &[u8],
+    message: &[u8],
+  ) -> Result<Vec<u8>, EternityError> {
+    if secret_key.len() != self.algorithm().secret_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    let (artifact_key, ed25519_seed) = secret_key.split_at(SECRETKEYBYTES);
+    let artifact_key = ArtifactSecretKey::from_bytes(artifact_key)?;
+    let ed25519_seed = ed25519_seed
+      .try_into()
+      .map_err(|_| EternityError::InvalidInput)?;
+    let ed25519 = SigningKey::from_bytes(ed25519_seed);
+
+    let representative =
+      CompositeAlgorithm::CURRENT.message_representative(message, &[])?;
+    let mut signature = artifact_key.sign(&representative).as_bytes().to_vec();
+    signature.extend_from_slice(&ed25519.sign(&representative).to_bytes());
+    Ok(signature)
+  }
+
+  fn verify(
+    &self,
+    signature: &[u8],
+    message: &[u8],
+    public_key: &[u8],
+  ) -> Result<(), EternityError> {
+    let algorithm = CompositeAlgorithm::CURRENT;
+    let public_key = CompositePublicKey::from_bytes(algorithm, public_key)?;
+    let signature = CompositeSignature::from_bytes(algorithm, signature)?;
+    public_key.verify(message, &[], &signature)
+  }
+}
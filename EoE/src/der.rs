@@ -0,0 +1,258 @@
+//! Minimal DER reader and writer.
+//!
+//! Covers what the key and certificate encodings need: low tag numbers,
+//! definite minimal lengths up to 4 bytes, and object identifiers. Anything
+//! outside that (BER indefinite lengths, high tag numbers, non-minimal
+//! lengths) is rejected as `InvalidInput`.
+
+use zeroize::Zeroizing;
+
+use crate::api::EternityError;
+
+pub const TAG_BOOLEAN: u8 = 0x01;
+pub const TAG_INTEGER: u8 = 0x02;
+pub const TAG_BIT_STRING: u8 = 0x03;
+pub const TAG_OCTET_STRING: u8 = 0x04;
+pub const TAG_NULL: u8 = 0x05;
+pub const TAG_OID: u8 = 0x06;
+pub const TAG_UTC_TIME: u8 = 0x17;
+pub const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub const TAG_SEQUENCE: u8 = 0x30;
+pub const TAG_SET: u8 = 0x31;
+
+/// Tag of a constructed, context-specific `[n]` element.
+pub const fn tag_context(n: u8) -> u8 {
+  0xa0 | n
+}
+
+/// Tag of a primitive, context-specific `[n] IMPLICIT` element.
+pub const fn tag_context_primitive(n: u8) -> u8 {
+  0x80 | n
+}
+
+/// One decoded tag-length-value element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DerElement<'a> {
+  pub tag: u8,
+  /// The value bytes.
+  pub contents: &'a [u8],
+  /// The whole element, header included.
+  pub raw: &'a [u8],
+}
+
+/// Sequential reader over concatenated DER elements.
+pub struct DerReader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> DerReader<'a> {
+  pub fn new(bytes: &'a [u8]) -> Self {
+    DerReader { bytes, pos: 0 }
+  }
+
+  /// Whether every element has been consumed.
+  pub fn is_empty(&self) -> bool {
+    self.pos == self.bytes.len()
+  }
+
+  /// Tag of the next element, if any.
+  pub fn peek_tag(&self) -> Option<u8> {
+    self.bytes.get(self.pos).copied()
+  }
+
+  /// Read the next element whatever its tag.
+  pub fn read_any(&mut self) -> Result<DerElement<'a>, EternityError> {
+    let start = self.pos;
+    let rest = &self.bytes[start..];
+    if rest.len() < 2 || rest[0] & 0x1f == 0x1f {
+      return Err(EternityError::InvalidInput);
+    }
+
+    let (len, header) = match rest[1] {
+      n if n < 0x80 => (n as usize, 2),
+      0x81..=0x84 => {
+        let count = (rest[1] & 0x7f) as usize;
+        if rest.len() < 2 + count || rest[2] == 0 {
+          return Err(EternityError::InvalidInput);
+        }
+        let len = rest[2..2 + count]
+          .iter()
+          .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        if len < 0x80 {
+          return Err(EternityError::InvalidInput);
+        }
+        (len, 2 + count)
+      }
+      _ => return Err(EternityError::InvalidInput),
+    };
+
+    let end = header
+      .checked_add(len)
+      .filter(|&end| end <= rest.len())
+      .ok_or(EternityError::InvalidInput)?;
+    self.pos += end;
+
+    Ok(DerElement {
+      tag: rest[0],
+      contents: &rest[header..end],
+      raw: &rest[..end],
+    })
+  }
+
+  /// Read the next element, which must carry `tag`.
+  pub fn read_element(
+    &mut self,
+    tag: u8,
+  ) -> Result<DerElement<'a>, EternityError> {
+    if self.peek_tag() != Some(tag) {
+      return Err(EternityError::InvalidInput);
+    }
+    self.read_any()
+  }
+
+  /// Read the contents of the next element, which must carry `tag`.
+  pub fn read(&mut self, tag: u8) -> Result<&'a [u8], EternityError> {
+    Ok(self.read_element(tag)?.contents)
+  }
+
+  /// Read the contents of the next element only if it carries `tag`.
+  pub fn read_optional(
+    &mut self,
+    tag: u8,
+  ) -> Result<Option<&'a [u8]>, EternityError> {
+    if self.peek_tag() == Some(tag) {
+      self.read(tag).map(Some)
+    } else {
+      Ok(None)
+    }
+  }
+
+  /// Read a BIT STRING with no unused bits.
+  pub fn read_bit_string(&mut self) -> Result<&'a [u8], EternityError> {
+    bit_string_bytes(self.read(TAG_BIT_STRING)?)
+  }
+
+  /// Read a small non-negative INTEGER.
+  pub fn read_small_uint(&mut self) -> Result<u64, EternityError> {
+    uint_value(self.read(TAG_INTEGER)?)
+  }
+
+  /// Fail unless every element has been consumed.
+  pub fn finish(self) -> Result<(), EternityError> {
+    if self.is_empty() {
+      Ok(())
+    } else {
+      Err(EternityError::InvalidInput)
+    }
+  }
+}
+
+/// Parse a single element that must span all of `bytes`.
+pub fn read_single(
+  bytes: &[u8],
+  tag: u8,
+) -> Result<DerElement<'_>, EternityError> {
+  let mut reader = DerReader::new(bytes);
+  let element = reader.read_element(tag)?;
+  reader.finish()?;
+  Ok(element)
+}
+
+/// Strip the unused-bits byte of a BIT STRING holding whole octets.
+pub fn bit_string_bytes(contents: &[u8]) -> Result<&[u8], EternityError> {
+  match contents.split_first() {
+    Some((0, bytes)) => Ok(bytes),
+    _ => Err(EternityError::InvalidInput),
+  }
+}
+
+/// Decode the contents of a non-negative INTEGER that fits in a `u64`.
+pub fn uint_value(contents: &[u8]) -> Result<u64, EternityError> {
+  let minimal = match contents {
+    [] => false,
+    [first, ..] if first & 0x80 != 0 => false,
+    [0, second, ..] => second & 0x80 != 0,
+    _ => true,
+  };
+  let digits = match contents {
+    [0, rest @ ..] if !rest.is_empty() => rest,
+    _ => contents,
+  };
+  if !minimal || digits.len() > 8 {
+    return Err(EternityError::InvalidInput);
+  }
+  Ok(digits.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
+}
+
+/// Encode one element.
+pub fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(contents.len() + 6);
+  out.push(tag);
+  let len = contents.len();
+  if len < 0x80 {
+    out.push(len as u8);
+  } else {
+    let bytes = (len as u32).to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    out.push(0x80 | (4 - skip) as u8);
+    out.extend_from_slice(&bytes[skip..]);
+  }
+  out.extend_from_slice(contents);
+  out
+}
+
+/// Encode a constructed element from already encoded children.
+pub fn encode_constructed(tag: u8, children: &[&[u8]]) -> Vec<u8> {
+  // Children may hold secret keys; do not leave a copy behind.
+  let contents = Zeroizing::new(children.concat());
+  encode(tag, &contents)
+}
+
+/// Encode a BIT STRING holding whole octets.
+pub fn encode_bit_string(bytes: &[u8]) -> Vec<u8> {
+  let mut contents = Vec::with_capacity(bytes.len() + 1);
+  contents.push(0);
+  contents.extend_from_slice(bytes);
+  encode(TAG_BIT_STRING, &contents)
+}
+
+/// Encode a small non-negative INTEGER.
+pub fn encode_small_uint(value: u64) -> Vec<u8> {
+  let bytes = value.to_be_bytes();
+  let skip = bytes[..7].iter().take_while(|&&b| b == 0).count();
+  let mut contents = Vec::with_capacity(9);
+  if bytes[skip] & 0x80 != 0 {
+    contents.push(0);
+  }
+  contents.extend_from_slice(&bytes[skip..]);
+  encode(TAG_INTEGER, &contents)
+}
+
+/// Encode the contents of an OBJECT IDENTIFIER from its arcs.
+pub fn oid_contents(arcs: &[u32]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(arcs.len() + 4);
+  let first = arcs[0] * 40 + arcs[1];
+  for &arc in core::iter::once(&first).chain(&arcs[2..]) {
+    let mut groups = [0u8; 5];
+    let mut n = 0;
+    let mut value = arc;
+    loop {
+      groups[n] = (value & 0x7f) as u8;
+      n += 1;
+      value >>= 7;
+      if value == 0 {
+        break;
+      }
+    }
+    for i in (0..n).rev() {
+      out.push(groups[i] | if i > 0 { 0x80 } else { 0 });
+    }
+  }
+  out
+}
+
+/// Encode a complete OBJECT IDENTIFIER element.
+pub fn encode_oid(arcs: &[u32]) -> Vec<u8> {
+  encode(TAG_OID, &oid_contents(arcs))
+}
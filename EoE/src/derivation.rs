@@ -0,0 +1,204 @@
+//! Hierarchical deterministic derivation of artifact key seeds.
+//!
+//! Modelled on SLIP-0010 for Ed25519: every node is a 32-byte key-generation
+//! seed plus a 32-byte chain code, and children are derived along a path such
+//! as `m/44'/7'/0'`. HMAC-SHA512 is replaced by domain-separated SHAKE256:
+//!
+//! ```text
+//! master        = SHAKE256("EoE-HD-v1 master" || master_seed)
+//! child(n, i)   = SHAKE256("EoE-HD-v1 child" || chain_code || seed || i)
+//! seed || chain = first 32 bytes || next 32 bytes
+//! ```
+//!
+//! with `i` big-endian. Artifact keys have no public-key derivation, so, as in
+//! SLIP-0010, only hardened indices are allowed.
+
+use core::{fmt, str::FromStr};
+
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{api::*, fips202::*, params::ESSENCEBYTES};
+
+/// Offset of the hardened index range; `i'` is `HARDENED + i`.
+pub const HARDENED: u32 = 1 << 31;
+
+/// Shortest master seed accepted, matching BIP32.
+pub const MIN_MASTER_SEED_BYTES: usize = 16;
+
+/// Longest master seed accepted, matching BIP32.
+pub const MAX_MASTER_SEED_BYTES: usize = 64;
+
+const MASTER_DOMAIN: &[u8] = b"EoE-HD-v1 master";
+const CHILD_DOMAIN: &[u8] = b"EoE-HD-v1 child";
+
+/// A path of hardened child indices.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DerivationPath {
+  indices: Vec<u32>,
+}
+
+impl DerivationPath {
+  /// Build a path from raw indices, which must all be hardened.
+  pub fn from_indices(indices: &[u32]) -> Result<Self, EternityError> {
+    if indices.iter().any(|&index| index < HARDENED) {
+      return Err(EternityError::InvalidInput);
+    }
+    Ok(DerivationPath {
+      indices: indices.to_vec(),
+    })
+  }
+
+  /// The raw indices, hardened offset included.
+  pub fn indices(&self) -> &[u32] {
+    &self.indices
+  }
+}
+
+impl FromStr for DerivationPath {
+  type Err = EternityError;
+
+  /// Parse `m/a'/b'/...`; `h` is accepted in place of `'`.
+  fn from_str(path: &str) -> Result<Self, EternityError> {
+    let mut components = path.split('/');
+    if components.next() != Some("m") {
+      return Err(EternityError::InvalidInput);
+    }
+    let indices = components
+      .map(|component| {
+        let index = component
+          .strip_suffix('\'')
+          .or_else(|| component.strip_suffix('h'))
+          .ok_or(EternityError::InvalidInput)?;
+        if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+          return Err(EternityError::InvalidInput);
+        }
+        match index.parse::<u32>() {
+          Ok(index) if index < HARDENED => Ok(HARDENED + index),
+          _ => Err(EternityError::InvalidInput),
+        }
+      })
+      .collect::<Result<_, _>>()?;
+    Ok(DerivationPath { indices })
+  }
+}
+
+impl fmt::Display for DerivationPath {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("m")?;
+    for index in &self.indices {
+      write!(f, "/{}'", index - HARDENED)?;
+    }
+    Ok(())
+  }
+}
+
+/// A node of the derivation tree.
+#[derive(Clone)]
+pub struct ExtendedSeed {
+  seed: [u8; ESSENCEBYTES],
+  chain_code: [u8; ESSENCEBYTES],
+  depth: u8,
+}
+
+impl ExtendedSeed {
+  /// The root node for a master seed of 16 to 64 bytes.
+  pub fn from_master(master_seed: &[u8]) -> Result<Self, EternityError> {
+    if !(MIN_MASTER_SEED_BYTES..=MAX_MASTER_SEED_BYTES)
+      .contains(&master_seed.len())
+    {
+      return Err(EternityError::InvalidInput);
+    }
+    Ok(ExtendedSeed::expand(&[MASTER_DOMAIN, master_seed], 0))
+  }
+
+  /// Derive the hardened child `index`, which must include [`HARDENED`].
+  pub fn derive_child(&self, index: u32) -> Result<Self, EternityError> {
+    if index < HARDENED {
+      return Err(EternityError::InvalidInput);
+    }
+    let depth = self.depth.checked_add(1).ok_or(EternityError::InvalidInput)?;
+    Ok(ExtendedSeed::expand(
+      &[
+        CHILD_DOMAIN,
+        &self.chain_code,
+        &self.seed,
+        &index.to_be_bytes(),
+      ],
+      depth,
+    ))
+  }
+
+  /// Derive the node at `path` below this one.
+  pub fn derive_path(
+    &self,
+    path: &DerivationPath,
+  ) -> Result<Self, EternityError> {
+    path
+      .indices
+      .iter()
+      .try_fold(self.clone(), |node, &index| node.derive_child(index))
+  }
+
+  /// The key-generation seed of this node.
+  pub fn seed(&self) -> &[u8; ESSENCEBYTES] {
+    &self.seed
+  }
+
+  /// The chain code of this node.
+  pub fn chain_code(&self) -> &[u8; ESSENCEBYTES] {
+    &self.chain_code
+  }
+
+  /// Number of derivation steps from the master node.
+  pub fn depth(&self) -> u8 {
+    self.depth
+  }
+
+  /// The artifact key pair generated from this node's seed.
+  pub fn keypair(&self) -> KeyPair {
+    KeyPair::from_seed(&self.seed)
+  }
+
+  fn expand(inputs: &[&[u8]], depth: u8) -> Self {
+    let mut output = Zeroizing::new([0u8; 2 * ESSENCEBYTES]);
+    let mut state = KeccakState::default();
+    for input in inputs {
+      shake256_absorb(&mut state, input, input.len());
+    }
+    shake256_finalize(&mut state);
+    shake256_squeeze(&mut output[..], 2 * ESSENCEBYTES, &mut state);
+
+    let mut seed = [0u8; ESSENCEBYTES];
+    let mut chain_code = [0u8; ESSENCEBYTES];
+    seed.copy_from_slice(&output[..ESSENCEBYTES]);
+    chain_code.copy_from_slice(&output[ESSENCEBYTES..]);
+    ExtendedSeed {
+      seed,
+      chain_code,
+      depth,
+    }
+  }
+}
+
+impl Drop for ExtendedSeed {
+  fn drop(&mut self) {
+    self.seed.zeroize();
+    self.chain_code.zeroize();
+  }
+}
+
+impl fmt::Debug for ExtendedSeed {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ExtendedSeed")
+      .field("depth", &self.depth)
+      .finish_non_exhaustive()
+  }
+}
+
+/// Derive the key pair at `path` from a master seed.
+pub fn derive_keypair(
+  master_seed: &[u8],
+  path: &DerivationPath,
+) -> Result<KeyPair, EternityError> {
+  Ok(ExtendedSeed::from_master(master_seed)?.derive_path(path)?.keypair())
+}
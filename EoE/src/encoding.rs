@@ -0,0 +1,104 @@
+/// Lowercase hexadecimal alphabet used for all text encodings of artifacts.
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encode bytes as a lowercase hexadecimal string.
+pub fn hex_encode(bytes: &[u8]) -> String {
+  let mut out = String::with_capacity(2 * bytes.len());
+  for &b in bytes {
+    out.push(HEX_DIGITS[(b >> 4) as usize] as char);
+    out.push(HEX_DIGITS[(b & 15) as usize] as char);
+  }
+  out
+}
+
+/// Decode a hexadecimal string, accepting either case.
+///
+/// Returns `None` on odd lengths or non-hex characters.
+pub fn hex_decode(text: &str) -> Option<Vec<u8>> {
+  let text = text.as_bytes();
+  if text.len() % 2 != 0 {
+    return None;
+  }
+  text
+    .chunks(2)
+    .map(|pair| Some((hex_value(pair[0])? << 4) | hex_value(pair[1])?))
+    .collect()
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+  match c {
+    b'0'..=b'9' => Some(c - b'0'),
+    b'a'..=b'f' => Some(c - b'a' + 10),
+    b'A'..=b'F' => Some(c - b'A' + 10),
+    _ => None,
+  }
+}
+
+/// Standard base64 alphabet (RFC 4648, section 4).
+const BASE64_ALPHABET: &[u8; 64] =
+  b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as padded standard base64.
+pub fn base64_encode(bytes: &[u8]) -> String {
+  let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+  for chunk in bytes.chunks(3) {
+    let mut n = (chunk[0] as u32) << 16;
+    if chunk.len() > 1 {
+      n |= (chunk[1] as u32) << 8;
+    }
+    if chunk.len() > 2 {
+      n |= chunk[2] as u32;
+    }
+    for i in 0..4 {
+      if i <= chunk.len() {
+        out.push(BASE64_ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+      } else {
+        out.push('=');
+      }
+    }
+  }
+  out
+}
+
+/// Decode padded standard base64.
+///
+/// Returns `None` on bad lengths, misplaced padding, non-zero bits under
+/// the padding or characters outside the alphabet. Whitespace is not
+/// skipped.
+pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+  let text = text.as_bytes();
+  if text.len() % 4 != 0 {
+    return None;
+  }
+  let blocks = text.len() / 4;
+  let mut out = Vec::with_capacity(blocks * 3);
+  for (i, chunk) in text.chunks(4).enumerate() {
+    let padding = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+    if padding > 2 || (padding > 0 && i + 1 != blocks) {
+      return None;
+    }
+    let mut n = 0u32;
+    for &c in &chunk[..4 - padding] {
+      n = (n << 6) | base64_value(c)? as u32;
+    }
+    n <<= 6 * padding as u32;
+    // Reject bits the padding drops, so each byte string has one encoding
+    if n & ((1 << (8 * padding)) - 1) != 0 {
+      return None;
+    }
+    let decoded = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+    out.extend_from_slice(&decoded[..3 - padding]);
+  }
+  Some(out)
+}
+
+fn base64_value(c: u8) -> Option<u8> {
+  match c {
+    b'A'..=b'Z' => Some(c - b'A'),
+    b'a'..=b'z' => Some(c - b'a' + 26),
+    b'0'..=b'9' => Some(c - b'0' + 52),
+    b'+' => Some(62),
+    b'/' => Some(63),
+    _ => None,
+  }
+}
@@ -0,0 +1,332 @@
+//! Self-describing envelopes for keys and signatures.
+//!
+//! A bare key or signature does not say which algorithm produced it, and this
+//! crate only knows the parameter set it was compiled for. An envelope is
+//!
+//! ```text
+//! version (1 byte) || algorithm id (1 byte) || payload
+//! ```
+//!
+//! where the payload is the raw encoding. Whether the payload is a public
+//! key, a secret key or a signature follows from its length, which is
+//! distinct for each kind within an algorithm.
+
+use crate::{api::*, params::*, scheme::resolve_scheme};
+
+/// Current envelope format version.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Size of the envelope header in front of the payload.
+pub const ENVELOPE_HEADER_BYTES: usize = 2;
+
+/// Size of an Ed25519 public key.
+pub const ED25519_PUBLIC_KEY_BYTES: usize = 32;
+
+/// Size of an Ed25519 secret key seed.
+pub const ED25519_SECRET_KEY_BYTES: usize = 32;
+
+/// Size of an Ed25519 signature.
+pub const ED25519_SIGNATURE_BYTES: usize = 64;
+
+/// Algorithms an envelope can carry.
+///
+/// Identifiers are stable: new algorithms get new values, existing values are
+/// never reused. Composite algorithms encode the lattice half followed by the
+/// Ed25519 half, see the `composite` module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum AlgorithmId {
+  /// CRYSTALS-Dilithium round 3, security level 2
+  Dilithium2 = 0x01,
+  /// CRYSTALS-Dilithium round 3, security level 3
+  Dilithium3 = 0x02,
+  /// CRYSTALS-Dilithium round 3, security level 5
+  Dilithium5 = 0x03,
+  /// FIPS 204 ML-DSA-44
+  MlDsa44 = 0x11,
+  /// FIPS 204 ML-DSA-65
+  MlDsa65 = 0x12,
+  /// FIPS 204 ML-DSA-87
+  MlDsa87 = 0x13,
+  /// FIPS 204 HashML-DSA-44 with SHA-512
+  HashMlDsa44Sha512 = 0x21,
+  /// FIPS 204 HashML-DSA-65 with SHA-512
+  HashMlDsa65Sha512 = 0x22,
+  /// FIPS 204 HashML-DSA-87 with SHA-512
+  HashMlDsa87Sha512 = 0x23,
+  /// Dilithium2 composed with Ed25519
+  Dilithium2Ed25519 = 0x41,
+  /// Dilithium3 composed with Ed25519
+  Dilithium3Ed25519 = 0x42,
+  /// Dilithium5 composed with Ed25519
+  Dilithium5Ed25519 = 0x43,
+  /// ML-DSA-44 composed with Ed25519, as in the LAMPS composite draft
+  MlDsa44Ed25519 = 0x51,
+  /// ML-DSA-65 composed with Ed25519, as in the LAMPS composite draft
+  MlDsa65Ed25519 = 0x52,
+}
+
+impl AlgorithmId {
+  /// Every known algorithm.
+  pub const ALL: [AlgorithmId; 14] = [
+    AlgorithmId::Dilithium2,
+    AlgorithmId::Dilithium3,
+    AlgorithmId::Dilithium5,
+    AlgorithmId::MlDsa44,
+    AlgorithmId::MlDsa65,
+    AlgorithmId::MlDsa87,
+    AlgorithmId::HashMlDsa44Sha512,
+    AlgorithmId::HashMlDsa65Sha512,
+    AlgorithmId::HashMlDsa87Sha512,
+    AlgorithmId::Dilithium2Ed25519,
+    AlgorithmId::Dilithium3Ed25519,
+    AlgorithmId::Dilithium5Ed25519,
+    AlgorithmId::MlDsa44Ed25519,
+    AlgorithmId::MlDsa65Ed25519,
+  ];
+
+  /// The algorithm implemented by this build.
+  ///
+  /// The transformation hashes the key to a 32-byte `tr`, uses a 32-byte
+  /// challenge seed and takes no context string, which is Dilithium round 3
+  /// rather than FIPS 204.
+  pub const CURRENT: AlgorithmId = match ParameterSet::CURRENT {
+    ParameterSet::Mode2 => AlgorithmId::Dilithium2,
+    ParameterSet::Mode3 => AlgorithmId::Dilithium3,
+    ParameterSet::Mode5 => AlgorithmId::Dilithium5,
+  };
+
+  /// [`AlgorithmId::CURRENT`] composed with Ed25519.
+  pub const CURRENT_COMPOSITE: AlgorithmId = match ParameterSet::CURRENT {
+    ParameterSet::Mode2 => AlgorithmId::Dilithium2Ed25519,
+    ParameterSet::Mode3 => AlgorithmId::Dilithium3Ed25519,
+    ParameterSet::Mode5 => AlgorithmId::Dilithium5Ed25519,
+  };
+
+  /// Look up an algorithm by its identifier byte.
+  pub fn from_u8(id: u8) -> Option<AlgorithmId> {
+    AlgorithmId::ALL.into_iter().find(|&alg| alg as u8 == id)
+  }
+
+  /// Human-readable algorithm name.
+  pub const fn name(self) -> &'static str {
+    match self {
+      AlgorithmId::Dilithium2 => "Dilithium2",
+      AlgorithmId::Dilithium3 => "Dilithium3",
+      AlgorithmId::Dilithium5 => "Dilithium5",
+      AlgorithmId::MlDsa44 => "ML-DSA-44",
+      AlgorithmId::MlDsa65 => "ML-DSA-65",
+      AlgorithmId::MlDsa87 => "ML-DSA-87",
+      AlgorithmId::HashMlDsa44Sha512 => "HashML-DSA-44-SHA512",
+      AlgorithmId::HashMlDsa65Sha512 => "HashML-DSA-65-SHA512",
+      AlgorithmId::HashMlDsa87Sha512 => "HashML-DSA-87-SHA512",
+      AlgorithmId::Dilithium2Ed25519 => "Dilithium2-Ed25519-SHA512",
+      AlgorithmId::Dilithium3Ed25519 => "Dilithium3-Ed25519-SHA512",
+      AlgorithmId::Dilithium5Ed25519 => "Dilithium5-Ed25519-SHA512",
+      AlgorithmId::MlDsa44Ed25519 => "ML-DSA-44-Ed25519-SHA512",
+      AlgorithmId::MlDsa65Ed25519 => "ML-DSA-65-Ed25519-SHA512",
+    }
+  }
+
+  /// Whether the algorithm pairs a lattice signature with Ed25519.
+  pub const fn is_composite(self) -> bool {
+    matches!(
+      self,
+      AlgorithmId::Dilithium2Ed25519
+        | AlgorithmId::Dilithium3Ed25519
+        | AlgorithmId::Dilithium5Ed25519
+        | AlgorithmId::MlDsa44Ed25519
+        | AlgorithmId::MlDsa65Ed25519
+    )
+  }
+
+  /// The lattice half of a composite, or the algorithm itself.
+  pub const fn component(self) -> AlgorithmId {
+    match self {
+      AlgorithmId::Dilithium2Ed25519 => AlgorithmId::Dilithium2,
+      AlgorithmId::Dilithium3Ed25519 => AlgorithmId::Dilithium3,
+      AlgorithmId::Dilithium5Ed25519 => AlgorithmId::Dilithium5,
+      AlgorithmId::MlDsa44Ed25519 => AlgorithmId::MlDsa44,
+      AlgorithmId::MlDsa65Ed25519 => AlgorithmId::MlDsa65,
+      other => other,
+    }
+  }
+
+  /// The lattice parameter set underneath the algorithm.
+  pub const fn parameter_set(self) -> ParameterSet {
+    match self {
+      AlgorithmId::Dilithium2
+      | AlgorithmId::MlDsa44
+      | AlgorithmId::HashMlDsa44Sha512
+      | AlgorithmId::Dilithium2Ed25519
+      | AlgorithmId::MlDsa44Ed25519 => ParameterSet::Mode2,
+      AlgorithmId::Dilithium3
+      | AlgorithmId::MlDsa65
+      | AlgorithmId::HashMlDsa65Sha512
+      | AlgorithmId::Dilithium3Ed25519
+      | AlgorithmId::MlDsa65Ed25519 => ParameterSet::Mode3,
+      AlgorithmId::Dilithium5
+      | AlgorithmId::MlDsa87
+      | AlgorithmId::HashMlDsa87Sha512
+      | AlgorithmId::Dilithium5Ed25519 => ParameterSet::Mode5,
+    }
+  }
+
+  /// Whether this is a FIPS 204 algorithm rather than round 3 Dilithium.
+  pub const fn is_fips204(self) -> bool {
+    !matches!(
+      self.component(),
+      AlgorithmId::Dilithium2
+        | AlgorithmId::Dilithium3
+        | AlgorithmId::Dilithium5
+    )
+  }
+
+  /// Size of a public key.
+  pub const fn public_key_bytes(self) -> usize {
+    let bytes = self.parameter_set().artifact_key_bytes();
+    if self.is_composite() {
+      bytes + ED25519_PUBLIC_KEY_BYTES
+    } else {
+      bytes
+    }
+  }
+
+  /// Size of a secret key. FIPS 204 widens `tr` from 32 to 64 bytes.
+  pub const fn secret_key_bytes(self) -> usize {
+    if self.is_composite() {
+      return self.component().secret_key_bytes() + ED25519_SECRET_KEY_BYTES;
+    }
+    let bytes = self.parameter_set().secret_key_bytes();
+    if self.is_fips204() {
+      bytes + ESSENCEBYTES
+    } else {
+      bytes
+    }
+  }
+
+  /// Size of a signature. FIPS 204 widens the challenge seed to
+  /// 48 and 64 bytes at the two higher levels.
+  pub const fn signature_bytes(self) -> usize {
+    if self.is_composite() {
+      return self.component().signature_bytes() + ED25519_SIGNATURE_BYTES;
+    }
+    let bytes = self.parameter_set().signature_bytes();
+    if !self.is_fips204() {
+      return bytes;
+    }
+    match self.parameter_set() {
+      ParameterSet::Mode2 => bytes,
+      ParameterSet::Mode3 => bytes + 16,
+      ParameterSet::Mode5 => bytes + 32,
+    }
+  }
+}
+
+/// What an envelope payload holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvelopeKind {
+  PublicKey,
+  SecretKey,
+  Signature,
+}
+
+impl EnvelopeKind {
+  /// Infer the payload kind from its length.
+  pub fn from_payload_len(
+    algorithm: AlgorithmId,
+    len: usize,
+  ) -> Option<EnvelopeKind> {
+    if len == algorithm.public_key_bytes() {
+      Some(EnvelopeKind::PublicKey)
+    } else if len == algorithm.secret_key_bytes() {
+      Some(EnvelopeKind::SecretKey)
+    } else if len == algorithm.signature_bytes() {
+      Some(EnvelopeKind::Signature)
+    } else {
+      None
+    }
+  }
+}
+
+/// A parsed envelope borrowing its payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Envelope<'a> {
+  pub version: u8,
+  pub algorithm: AlgorithmId,
+  pub kind: EnvelopeKind,
+  pub payload: &'a [u8],
+}
+
+/// Wrap a raw key or signature in an envelope.
+pub fn seal_envelope(
+  algorithm: AlgorithmId,
+  payload: &[u8],
+) -> Result<Vec<u8>, EternityError> {
+  if EnvelopeKind::from_payload_len(algorithm, payload.len()).is_none() {
+    return Err(EternityError::InvalidInput);
+  }
+  let mut out = Vec::with_capacity(ENVELOPE_HEADER_BYTES + payload.len());
+  out.push(ENVELOPE_VERSION);
+  out.push(algorithm as u8);
+  out.extend_from_slice(payload);
+  Ok(out)
+}
+
+/// Parse an envelope and check its payload length.
+pub fn open_envelope(bytes: &[u8]) -> Result<Envelope<'_>, EternityError> {
+  if bytes.len() < ENVELOPE_HEADER_BYTES || bytes[0] != ENVELOPE_VERSION {
+    return Err(EternityError::InvalidInput);
+  }
+  let algorithm =
+    AlgorithmId::from_u8(bytes[1]).ok_or(EternityError::UnsupportedAlgorithm)?;
+  let payload = &bytes[ENVELOPE_HEADER_BYTES..];
+  let kind = EnvelopeKind::from_payload_len(algorithm, payload.len())
+    .ok_or(EternityError::InvalidInput)?;
+
+  Ok(Envelope {
+    version: bytes[0],
+    algorithm,
+    kind,
+    payload,
+  })
+}
+
+/// Verify a raw signature with the scheme registered for `algorithm`.
+///
+/// Algorithms this build does not implement are reported as
+/// `ParameterMismatch` or `UnsupportedAlgorithm` rather than attempted; see
+/// [`resolve_scheme`].
+pub fn verify_with_algorithm(
+  algorithm: AlgorithmId,
+  signature: &[u8],
+  artifact: &[u8],
+  public_key: &[u8],
+) -> Result<(), EternityError> {
+  resolve_scheme(algorithm)?.verify(signature, artifact, public_key)
+}
+
+/// Verify an enveloped signature against an enveloped public key.
+///
+/// Both envelopes must name the same algorithm, so a key can never be used
+/// with a signature from a different scheme.
+pub fn verify_envelope(
+  signature: &[u8],
+  artifact: &[u8],
+  public_key: &[u8],
+) -> Result<(), EternityError> {
+  let signature = open_envelope(signature)?;
+  let public_key = open_envelope(public_key)?;
+  if signature.kind != EnvelopeKind::Signature
+    || public_key.kind != EnvelopeKind::PublicKey
+    || signature.algorithm != public_key.algorithm
+  {
+    return Err(EternityError::InvalidInput);
+  }
+  verify_with_algorithm(
+    signature.algorithm,
+    signature.payload,
+    artifact,
+    public_key.payload,
+  )
+}
@@ -0,0 +1,259 @@
+//! C ABI for the `cdylib`.
+//!
+//! Every fallible function returns one of the `EOE_*` status codes and
+//! writes its output through caller-provided buffers. Buffer lengths are
+//! passed explicitly and must equal the size reported by the matching
+//! `eoe_*_bytes` function for the parameter set of the build. Input pointers
+//! may be null only when their length is zero.
+//!
+//! `include/eoe.h` is generated from this module:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/eoe.h
+//! ```
+
+use core::{ffi::c_char, slice};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use zeroize::Zeroizing;
+
+use crate::{api::*, envelope::AlgorithmId, keys::*, params::*};
+
+/// The call succeeded.
+pub const EOE_OK: i32 = 0;
+
+/// A required pointer was null.
+pub const EOE_ERR_NULL_POINTER: i32 = -1;
+
+/// A buffer has the wrong length or malformed contents.
+pub const EOE_ERR_INVALID_INPUT: i32 = -2;
+
+/// The signature does not verify.
+pub const EOE_ERR_VERIFICATION_FAILED: i32 = -3;
+
+/// The input is sized for a different parameter set than the build.
+pub const EOE_ERR_PARAMETER_MISMATCH: i32 = -4;
+
+/// The algorithm is not implemented by the build.
+pub const EOE_ERR_UNSUPPORTED_ALGORITHM: i32 = -5;
+
+/// The operating system could not provide randomness.
+pub const EOE_ERR_RANDOMNESS: i32 = -6;
+
+/// The library hit an internal error; this is a bug.
+pub const EOE_ERR_INTERNAL: i32 = -7;
+
+fn error_code(error: EternityError) -> i32 {
+  match error {
+    EternityError::InvalidInput => EOE_ERR_INVALID_INPUT,
+    EternityError::VerificationFailed => EOE_ERR_VERIFICATION_FAILED,
+    EternityError::ParameterMismatch => EOE_ERR_PARAMETER_MISMATCH,
+    EternityError::UnsupportedAlgorithm => EOE_ERR_UNSUPPORTED_ALGORITHM,
+  }
+}
+
+/// Run `f`, keeping panics from unwinding into C.
+fn guard(f: impl FnOnce() -> Result<(), i32>) -> i32 {
+  match catch_unwind(AssertUnwindSafe(f)) {
+    Ok(Ok(())) => EOE_OK,
+    Ok(Err(code)) => code,
+    Err(_) => EOE_ERR_INTERNAL,
+  }
+}
+
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], i32> {
+  if len == 0 {
+    Ok(&[])
+  } else if ptr.is_null() {
+    Err(EOE_ERR_NULL_POINTER)
+  } else {
+    Ok(slice::from_raw_parts(ptr, len))
+  }
+}
+
+unsafe fn output<'a>(
+  ptr: *mut u8,
+  len: usize,
+  expected: usize,
+) -> Result<&'a mut [u8], i32> {
+  if ptr.is_null() {
+    Err(EOE_ERR_NULL_POINTER)
+  } else if len != expected {
+    Err(EOE_ERR_INVALID_INPUT)
+  } else {
+    Ok(slice::from_raw_parts_mut(ptr, len))
+  }
+}
+
+#[cfg(feature = "getrandom")]
+fn fill_random(buf: &mut [u8]) -> bool {
+  getrandom::getrandom(buf).is_ok()
+}
+
+#[cfg(not(feature = "getrandom"))]
+fn fill_random(_: &mut [u8]) -> bool {
+  false
+}
+
+/// Security level of the parameter set the library was built for: 2, 3 or 5.
+#[no_mangle]
+pub extern "C" fn eoe_parameter_set() -> u32 {
+  ParameterSet::CURRENT.level()
+}
+
+/// Envelope algorithm identifier of the library's algorithm.
+#[no_mangle]
+pub extern "C" fn eoe_algorithm_id() -> u8 {
+  AlgorithmId::CURRENT as u8
+}
+
+/// Size of a public key for a parameter set, or 0 if the level is unknown.
+#[no_mangle]
+pub extern "C" fn eoe_public_key_bytes(parameter_set: u32) -> usize {
+  ParameterSet::from_level(parameter_set)
+    .map_or(0, ParameterSet::artifact_key_bytes)
+}
+
+/// Size of a secret key for a parameter set, or 0 if the level is unknown.
+#[no_mangle]
+pub extern "C" fn eoe_secret_key_bytes(parameter_set: u32) -> usize {
+  ParameterSet::from_level(parameter_set)
+    .map_or(0, ParameterSet::secret_key_bytes)
+}
+
+/// Size of a signature for a parameter set, or 0 if the level is unknown.
+#[no_mangle]
+pub extern "C" fn eoe_signature_bytes(parameter_set: u32) -> usize {
+  ParameterSet::from_level(parameter_set)
+    .map_or(0, ParameterSet::signature_bytes)
+}
+
+/// Size of a key generation seed.
+#[no_mangle]
+pub extern "C" fn eoe_seed_bytes() -> usize {
+  ESSENCEBYTES
+}
+
+/// Static, NUL-terminated description of a status code.
+#[no_mangle]
+pub extern "C" fn eoe_strerror(code: i32) -> *const c_char {
+  let message: &'static [u8] = match code {
+    EOE_OK => b"ok\0",
+    EOE_ERR_NULL_POINTER => b"null pointer\0",
+    EOE_ERR_INVALID_INPUT => b"invalid input\0",
+    EOE_ERR_VERIFICATION_FAILED => b"verification failed\0",
+    EOE_ERR_PARAMETER_MISMATCH => b"parameter set mismatch\0",
+    EOE_ERR_UNSUPPORTED_ALGORITHM => b"unsupported algorithm\0",
+    EOE_ERR_RANDOMNESS => b"randomness unavailable\0",
+    EOE_ERR_INTERNAL => b"internal error\0",
+    _ => b"unknown status code\0",
+  };
+  message.as_ptr().cast()
+}
+
+/// Derive a key pair deterministically from a seed.
+///
+/// # Safety
+///
+/// `public_key` and `secret_key` must be valid for writes of their lengths
+/// and `seed` valid for reads of `seed_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn eoe_keypair_from_seed(
+  public_key: *mut u8,
+  public_key_len: usize,
+  secret_key: *mut u8,
+  secret_key_len: usize,
+  seed: *const u8,
+  seed_len: usize,
+) -> i32 {
+  guard(|| {
+    let public_key = output(public_key, public_key_len, ARTIFACTKEYBYTES)?;
+    let secret_key = output(secret_key, secret_key_len, SECRETKEYBYTES)?;
+    let seed: &[u8; ESSENCEBYTES] = input(seed, seed_len)?
+      .try_into()
+      .map_err(|_| EOE_ERR_INVALID_INPUT)?;
+
+    let keys = KeyPair::from_seed(seed);
+    public_key.copy_from_slice(&keys.public);
+    secret_key.copy_from_slice(keys.expose_secret());
+    Ok(())
+  })
+}
+
+/// Generate a key pair from operating system randomness.
+///
+/// Returns `EOE_ERR_RANDOMNESS` if the library was built without the
+/// `getrandom` feature or the operating system has no randomness to give.
+///
+/// # Safety
+///
+/// `public_key` and `secret_key` must be valid for writes of their lengths.
+#[no_mangle]
+pub unsafe extern "C" fn eoe_keypair(
+  public_key: *mut u8,
+  public_key_len: usize,
+  secret_key: *mut u8,
+  secret_key_len: usize,
+) -> i32 {
+  let mut seed = Zeroizing::new([0u8; ESSENCEBYTES]);
+  if !fill_random(&mut seed[..]) {
+    return EOE_ERR_RANDOMNESS;
+  }
+  eoe_keypair_from_seed(
+    public_key,
+    public_key_len,
+    secret_key,
+    secret_key_len,
+    seed.as_ptr(),
+    seed.len(),
+  )
+}
+
+/// Sign a message.
+///
+/// # Safety
+///
+/// `signature` must be valid for writes of `signature_len` bytes, `message`
+/// and `secret_key` valid for reads of their lengths.
+#[no_mangle]
+pub unsafe extern "C" fn eoe_sign(
+  signature: *mut u8,
+  signature_len: usize,
+  message: *const u8,
+  message_len: usize,
+  secret_key: *const u8,
+  secret_key_len: usize,
+) -> i32 {
+  guard(|| {
+    let signature = output(signature, signature_len, SIGNATUREBYTES)?;
+    let message = input(message, message_len)?;
+    let secret_key = input(secret_key, secret_key_len)?;
+
+    let secret_key =
+      ArtifactSecretKey::from_bytes(secret_key).map_err(error_code)?;
+    signature.copy_from_slice(secret_key.sign(message).as_bytes());
+    Ok(())
+  })
+}
+
+/// Verify a signature, returning `EOE_OK` only if it is valid.
+///
+/// # Safety
+///
+/// Each pointer must be valid for reads of its length.
+#[no_mangle]
+pub unsafe extern "C" fn eoe_verify(
+  signature: *const u8,
+  signature_len: usize,
+  message: *const u8,
+  message_len: usize,
+  public_key: *const u8,
+  public_key_len: usize,
+) -> i32 {
+  guard(|| {
+    let signature = input(signature, signature_len)?;
+    let message = input(message, message_len)?;
+    let public_key = input(public_key, public_key_len)?;
+    verify_transformation(signature, message, public_key).map_err(error_code)
+  })
+}
@@ -1,11 +1,17 @@
 #![allow(clippy::needless_range_loop)]
 
+use zeroize::Zeroize;
+
 pub const ARTIFACT_128_RATE: usize = 168;
 pub const ARTIFACT_256_RATE: usize = 136;
 
 const CYCLES: usize = 24;
 
-#[derive(Copy, Clone)]
+/// Keccak sponge state.
+///
+/// Not `Copy`: after absorbing a seed or key the state holds secrets, and it
+/// is wiped when dropped.
+#[derive(Clone)]
 pub struct EternityState {
     pub s: [u64; 25],
     pub pos: usize,
@@ -21,6 +27,13 @@ impl Default for EternityState {
     }
 }
 
+impl Drop for EternityState {
+    fn drop(&mut self) {
+        self.s.zeroize();
+        self.pos.zeroize();
+    }
+}
+
 impl EternityState {
     pub fn initialize(&mut self) {
         self.s.fill(0);
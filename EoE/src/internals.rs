@@ -0,0 +1,54 @@
+//! Unstable access to the arithmetic behind the public API.
+//!
+//! Exists for the benchmarks in `benches/`, the fuzz targets in
+//! `fuzz/`, the property tests in `tests/arithmetic.rs` and other
+//! out-of-crate audits. Nothing here is covered by semver; enable the
+//! `internals` feature only in tooling.
+
+/// Montgomery and Barrett style reductions.
+pub mod reduce {
+  pub use crate::reduce::*;
+}
+
+/// Forward and inverse number-theoretic transforms.
+pub mod ntt {
+  pub use crate::ntt::*;
+}
+
+/// Element decomposition, rounding and hints.
+pub mod rounding {
+  pub use crate::rounding::*;
+}
+
+/// Polynomial arithmetic, samplers and norm checks.
+pub mod poly {
+  pub use crate::poly::*;
+}
+
+/// Vectors and matrices of artifacts.
+pub mod polyvec {
+  pub use crate::polyvec::*;
+}
+
+/// Claims for artifacts built outside the crate, needed when the
+/// `artifact-bounds` feature is on as well.
+pub mod bounds {
+  pub use crate::bounds::claim;
+}
+
+/// SHAKE-based streams and hashes.
+pub mod symmetric {
+  pub use crate::symmetric::*;
+}
+
+/// Byte encodings of keys and signatures.
+pub mod packing {
+  pub use crate::packing::*;
+}
+
+pub use crate::sign::{
+  artifact_keypair, artifact_sign, artifact_verify_stage1,
+  artifact_verify_stage2, artifact_verify_stage3, artifact_verify_stage4,
+  artifact_verify_stage5, artifact_verify_stage6, artifact_verify_stage7,
+  artifact_verify_stage8,
+};
@@ -0,0 +1,397 @@
+//! Validated key and signature types.
+//!
+//! The transformation API works on raw byte slices, so a truncated key or a
+//! key from a differently compiled build is only noticed deep inside
+//! verification. These wrappers check length and parameter set when they are
+//! built, and provide hex, base64 and (with the `serde` feature) serde
+//! encodings: hex in human-readable formats, raw bytes in binary ones.
+
+use core::fmt;
+use std::sync::OnceLock;
+
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+  api::*,
+  encoding::*,
+  envelope::*,
+  params::*,
+  sign::{artifact_sign, artifact_signature_stats},
+};
+
+/// Shared constructors and encodings of the fixed-size byte wrappers.
+macro_rules! artifact_bytes {
+  ($name:ident, $len:expr, $expected:path) => {
+    impl $name {
+      /// Size of the encoding for the compiled parameter set.
+      pub const BYTES: usize = $len;
+
+      /// The parameter set every value of this type belongs to.
+      pub const PARAMETER_SET: ParameterSet = ParameterSet::CURRENT;
+
+      /// The parameter set whose encodings of this kind are `len` bytes
+      /// long, if any. Tells a key or signature from a differently built
+      /// peer apart from a corrupt one.
+      pub fn parameter_set_for_len(len: usize) -> Option<ParameterSet> {
+        ParameterSet::ALL
+          .into_iter()
+          .find(|&set| $expected(set) == len)
+      }
+
+      /// Validate and copy a raw encoding.
+      pub fn from_bytes(bytes: &[u8]) -> Result<Self, EternityError> {
+        check_length(bytes.len(), $expected)?;
+        let mut inner = Box::new([0u8; $len]);
+        inner.copy_from_slice(bytes);
+        Ok($name { bytes: inner })
+      }
+
+      /// Copy an encoding already known to have the right size.
+      ///
+      /// The bytes are copied straight into the heap allocation, never
+      /// through a temporary on the stack.
+      pub fn from_array(bytes: &[u8; $len]) -> Self {
+        let mut inner = Box::new([0u8; $len]);
+        inner.copy_from_slice(bytes);
+        $name { bytes: inner }
+      }
+
+      /// The raw encoding.
+      pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..]
+      }
+
+      /// Encode as lowercase hex.
+      pub fn to_hex(&self) -> String {
+        hex_encode(self.as_bytes())
+      }
+
+      /// Decode and validate a hex encoding.
+      pub fn from_hex(text: &str) -> Result<Self, EternityError> {
+        let bytes = Zeroizing::new(
+          hex_decode(text.trim()).ok_or(EternityError::InvalidInput)?,
+        );
+        Self::from_bytes(&bytes)
+      }
+
+      /// Encode as padded standard base64.
+      pub fn to_base64(&self) -> String {
+        base64_encode(self.as_bytes())
+      }
+
+      /// Decode and validate a base64 encoding.
+      pub fn from_base64(text: &str) -> Result<Self, EternityError> {
+        let bytes = Zeroizing::new(
+          base64_decode(text.trim()).ok_or(EternityError::InvalidInput)?,
+        );
+        Self::from_bytes(&bytes)
+      }
+
+      /// Wrap in a self-describing envelope naming the current algorithm.
+      pub fn to_envelope(&self) -> Vec<u8> {
+        seal_envelope(AlgorithmId::CURRENT, self.as_bytes())
+          .expect("encoding length matches the current algorithm")
+      }
+
+      /// Unwrap an envelope produced for the current algorithm.
+      pub fn from_envelope(bytes: &[u8]) -> Result<Self, EternityError> {
+        let envelope = open_envelope(bytes)?;
+        if envelope.algorithm != AlgorithmId::CURRENT {
+          return Err(EternityError::UnsupportedAlgorithm);
+        }
+        Self::from_bytes(envelope.payload)
+      }
+    }
+
+    impl TryFrom<&[u8]> for $name {
+      type Error = EternityError;
+
+      fn try_from(bytes: &[u8]) -> Result<Self, EternityError> {
+        $name::from_bytes(bytes)
+      }
+    }
+
+    impl AsRef<[u8]> for $name {
+      fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+      }
+    }
+
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for $name {
+      fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+      ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+          serializer.serialize_str(&self.to_hex())
+        } else {
+          serializer.serialize_bytes(self.as_bytes())
+        }
+      }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de> serde::Deserialize<'de> for $name {
+      fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+      ) -> Result<Self, D::Error> {
+        let bytes = Zeroizing::new(if deserializer.is_human_readable() {
+          deserializer.deserialize_str(EncodingVisitor)?
+        } else {
+          deserializer.deserialize_bytes(EncodingVisitor)?
+        });
+        $name::from_bytes(&bytes).map_err(serde::de::Error::custom)
+      }
+    }
+  };
+}
+
+/// An artifact (public) key of the compiled parameter set.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ArtifactPublicKey {
+  bytes: Box<[u8; ARTIFACTKEYBYTES]>,
+}
+
+artifact_bytes!(
+  ArtifactPublicKey,
+  ARTIFACTKEYBYTES,
+  ParameterSet::artifact_key_bytes
+);
+
+impl ArtifactPublicKey {
+  /// Verify `signature` over `message` with this key.
+  pub fn verify(
+    &self,
+    message: &[u8],
+    signature: &ArtifactSignature,
+  ) -> Result<(), EternityError> {
+    verify_transformation(signature.as_bytes(), message, self.as_bytes())
+  }
+}
+
+impl fmt::Debug for ArtifactPublicKey {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "ArtifactPublicKey({}..)", hex_encode(&self.bytes[..8]))
+  }
+}
+
+/// An artifact secret key of the compiled parameter set.
+///
+/// Deliberately not comparable, and its `Debug` output is redacted.
+pub struct ArtifactSecretKey {
+  bytes: Box<[u8; SECRETKEYBYTES]>,
+}
+
+artifact_bytes!(
+  ArtifactSecretKey,
+  SECRETKEYBYTES,
+  ParameterSet::secret_key_bytes
+);
+
+impl ArtifactSecretKey {
+  /// Sign `message` deterministically with this key.
+  pub fn sign(&self, message: &[u8]) -> ArtifactSignature {
+    let mut signature = Box::new([0u8; SIGNATUREBYTES]);
+    artifact_sign(&mut signature, message, &self.bytes);
+    ArtifactSignature { bytes: signature }
+  }
+}
+
+impl Clone for ArtifactSecretKey {
+  fn clone(&self) -> Self {
+    ArtifactSecretKey::from_array(&self.bytes)
+  }
+}
+
+impl Drop for ArtifactSecretKey {
+  fn drop(&mut self) {
+    self.bytes.zeroize();
+  }
+}
+
+impl fmt::Debug for ArtifactSecretKey {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("ArtifactSecretKey(..)")
+  }
+}
+
+/// A secret key stored as its 32-byte key-generation seed.
+///
+/// FIPS 204 allows the seed `xi` to stand in for the expanded key. The
+/// expanded key and the matching public key are derived on first use and
+/// cached until [`ArtifactSeed::clear_cache`]; since both always come from
+/// the seed, they cannot disagree with it.
+#[derive(Clone)]
+pub struct ArtifactSeed {
+  seed: Box<[u8; ESSENCEBYTES]>,
+  expanded: OnceLock<(ArtifactPublicKey, ArtifactSecretKey)>,
+}
+
+impl ArtifactSeed {
+  /// Size of the encoding.
+  pub const BYTES: usize = ESSENCEBYTES;
+
+  /// Decode a 32-byte seed.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, EternityError> {
+    let seed = bytes.try_into().map_err(|_| EternityError::InvalidInput)?;
+    Ok(ArtifactSeed::from_array(seed))
+  }
+
+  /// Wrap a seed.
+  pub fn from_array(seed: &[u8; ESSENCEBYTES]) -> Self {
+    let mut boxed = Box::new([0u8; ESSENCEBYTES]);
+    boxed.copy_from_slice(seed);
+    ArtifactSeed {
+      seed: boxed,
+      expanded: OnceLock::new(),
+    }
+  }
+
+  /// Draw a fresh seed from operating system randomness.
+  ///
+  /// Panics if the operating system cannot provide randomness.
+  #[cfg(feature = "getrandom")]
+  pub fn generate() -> Self {
+    let mut seed = Zeroizing::new([0u8; ESSENCEBYTES]);
+    getrandom::getrandom(&mut seed[..]).expect("OS randomness unavailable");
+    ArtifactSeed::from_array(&seed)
+  }
+
+  /// The raw seed.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.seed[..]
+  }
+
+  pub(crate) fn as_array(&self) -> &[u8; ESSENCEBYTES] {
+    &self.seed
+  }
+
+  /// The expanded secret key, derived now if not cached.
+  pub fn secret_key(&self) -> &ArtifactSecretKey {
+    &self.expand().1
+  }
+
+  /// The public key, derived now if not cached.
+  pub fn public_key(&self) -> &ArtifactPublicKey {
+    &self.expand().0
+  }
+
+  /// Sign `message` deterministically with the expanded key.
+  pub fn sign(&self, message: &[u8]) -> ArtifactSignature {
+    self.secret_key().sign(message)
+  }
+
+  /// Whether the expanded form is currently cached.
+  pub fn is_expanded(&self) -> bool {
+    self.expanded.get().is_some()
+  }
+
+  /// Drop the cached expanded form, keeping only the seed in memory.
+  pub fn clear_cache(&mut self) {
+    self.expanded.take();
+  }
+
+  fn expand(&self) -> &(ArtifactPublicKey, ArtifactSecretKey) {
+    self.expanded.get_or_init(|| {
+      let keys = KeyPair::from_seed(&self.seed);
+      (keys.public_key(), keys.secret_key())
+    })
+  }
+}
+
+impl Drop for ArtifactSeed {
+  fn drop(&mut self) {
+    self.seed.zeroize();
+  }
+}
+
+impl fmt::Debug for ArtifactSeed {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("ArtifactSeed(..)")
+  }
+}
+
+/// A detached artifact signature of the compiled parameter set.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ArtifactSignature {
+  bytes: Box<[u8; SIGNATUREBYTES]>,
+}
+
+artifact_bytes!(
+  ArtifactSignature,
+  SIGNATUREBYTES,
+  ParameterSet::signature_bytes
+);
+
+impl ArtifactSignature {
+  /// Decode the hint and fragment statistics, for diagnostics.
+  pub fn stats(&self) -> Result<SignatureStats, EternityError> {
+    let (hint_weight, z_norm) = artifact_signature_stats(self.as_bytes())
+      .map_err(|_| EternityError::InvalidInput)?;
+    Ok(SignatureStats {
+      hint_weight,
+      max_hint_weight: OMEGA,
+      z_norm: z_norm as u32,
+      z_bound: GAMMA1_MINUS_THRESHOLD as u32,
+    })
+  }
+}
+
+/// How close a signature is to the bounds verification enforces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignatureStats {
+  /// Number of hint bits set.
+  pub hint_weight: usize,
+  /// Largest hint weight a valid signature may have, `OMEGA`.
+  pub max_hint_weight: usize,
+  /// Infinity norm of the response `z`.
+  pub z_norm: u32,
+  /// Exclusive bound on `z_norm`, `GAMMA1 - BETA`.
+  pub z_bound: u32,
+}
+
+impl fmt::Debug for ArtifactSignature {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "ArtifactSignature({}..)", hex_encode(&self.bytes[..8]))
+  }
+}
+
+/// Accepts hex strings, byte strings and byte sequences.
+#[cfg(feature = "serde")]
+struct EncodingVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for EncodingVisitor {
+  type Value = Vec<u8>;
+
+  fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("a hex string or a byte string")
+  }
+
+  fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+    hex_decode(v).ok_or_else(|| E::custom("invalid hex encoding"))
+  }
+
+  fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+    Ok(v.to_vec())
+  }
+
+  fn visit_byte_buf<E: serde::de::Error>(
+    self,
+    v: Vec<u8>,
+  ) -> Result<Vec<u8>, E> {
+    Ok(v)
+  }
+
+  fn visit_seq<A: serde::de::SeqAccess<'de>>(
+    self,
+    mut seq: A,
+  ) -> Result<Vec<u8>, A::Error> {
+    let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+    while let Some(b) = seq.next_element()? {
+      bytes.push(b);
+    }
+    Ok(bytes)
+  }
+}
@@ -0,0 +1,493 @@
+//! Passphrase-encrypted keystore for artifact key seeds.
+//!
+//! Each entry stores a key-generation seed, see [`ArtifactSeed`], encrypted
+//! with ChaCha20-Poly1305 under a key stretched from the passphrase with
+//! scrypt. Everything but the seed is kept in the clear so that entries can
+//! be listed without a passphrase. A keystore file is
+//!
+//! ```text
+//! magic "EoEKS" || version (1 byte) || entry count (2 bytes) || entries
+//! ```
+//!
+//! and each entry, with integers big-endian,
+//!
+//! ```text
+//! algorithm id        1 byte
+//! created             8 bytes, Unix seconds
+//! label length        2 bytes, then the UTF-8 label
+//! public key length   2 bytes, then the raw public key
+//! scrypt log2(N)      1 byte
+//! scrypt r, p         4 bytes each
+//! salt               16 bytes
+//! nonce              12 bytes
+//! ciphertext         32 bytes of seed, then the 16-byte tag
+//! ```
+//!
+//! The file header and every entry byte before the ciphertext are the
+//! associated data, so tampering with the label, algorithm or public key
+//! fails decryption. Entries are authenticated one by one: removing or
+//! reordering whole entries is not detected. scrypt costs above the
+//! `KEYSTORE_MAX_*` limits are rejected while parsing, before any key
+//! derivation runs.
+
+use core::fmt;
+use std::{
+  fs, io,
+  path::Path,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use chacha20poly1305::{
+  aead::{AeadInPlace, KeyInit},
+  ChaCha20Poly1305, Key, Nonce, Tag,
+};
+
+use zeroize::Zeroizing;
+
+use crate::{
+  api::*,
+  envelope::AlgorithmId,
+  keys::{ArtifactPublicKey, ArtifactSeed},
+  params::*,
+};
+
+/// Magic bytes at the start of a keystore file.
+pub const KEYSTORE_MAGIC: &[u8; 5] = b"EoEKS";
+
+/// Current keystore format version.
+pub const KEYSTORE_VERSION: u8 = 1;
+
+/// Largest scrypt cost accepted when opening an entry, `N = 2^20`.
+pub const KEYSTORE_MAX_LOG_N: u8 = 20;
+
+/// Largest scrypt block size accepted when opening an entry.
+pub const KEYSTORE_MAX_R: u32 = 32;
+
+/// Largest scrypt parallelism accepted when opening an entry.
+pub const KEYSTORE_MAX_P: u32 = 16;
+
+/// Largest scrypt memory use, `128 * N * r` bytes, accepted when opening an
+/// entry: 1 GiB.
+pub const KEYSTORE_MAX_MEMORY: u64 = 1 << 30;
+
+/// Largest scrypt work, `N * r * p`, accepted when opening an entry: eight
+/// times the default cost.
+pub const KEYSTORE_MAX_WORK: u64 = 1 << 23;
+
+const SALT_BYTES: usize = 16;
+const NONCE_BYTES: usize = 12;
+const TAG_BYTES: usize = 16;
+const KEY_BYTES: usize = 32;
+
+/// scrypt cost parameters of a keystore entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeystoreKdf {
+  /// log2 of the CPU/memory cost `N`.
+  pub log_n: u8,
+  /// Block size.
+  pub r: u32,
+  /// Parallelism.
+  pub p: u32,
+}
+
+impl Default for KeystoreKdf {
+  /// `N = 2^17, r = 8, p = 1`: 128 MiB and a fraction of a second per
+  /// unlock on current hardware.
+  fn default() -> Self {
+    KeystoreKdf {
+      log_n: 17,
+      r: 8,
+      p: 1,
+    }
+  }
+}
+
+impl KeystoreKdf {
+  /// Reject costs outside the `KEYSTORE_MAX_*` limits, so that a crafted
+  /// file cannot make opening it allocate or spin without bound.
+  fn check(&self) -> Result<(), EternityError> {
+    if self.log_n == 0
+      || self.log_n > KEYSTORE_MAX_LOG_N
+      || !(1..=KEYSTORE_MAX_R).contains(&self.r)
+      || !(1..=KEYSTORE_MAX_P).contains(&self.p)
+    {
+      return Err(EternityError::InvalidInput);
+    }
+    // Cannot overflow: N <= 2^20, r <= 2^5 and p <= 2^4.
+    let blocks = (1u64 << self.log_n) * u64::from(self.r);
+    if 128 * blocks > KEYSTORE_MAX_MEMORY
+      || blocks * u64::from(self.p) > KEYSTORE_MAX_WORK
+    {
+      return Err(EternityError::InvalidInput);
+    }
+    Ok(())
+  }
+
+  fn derive_key(
+    &self,
+    passphrase: &str,
+    salt: &[u8; SALT_BYTES],
+  ) -> Result<Zeroizing<[u8; KEY_BYTES]>, EternityError> {
+    self.check()?;
+    let params = scrypt::Params::new(self.log_n, self.r, self.p, KEY_BYTES)
+      .map_err(|_| EternityError::InvalidInput)?;
+    let mut key = Zeroizing::new([0u8; KEY_BYTES]);
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key[..])
+      .map_err(|_| EternityError::InvalidInput)?;
+    Ok(key)
+  }
+}
+
+/// One encrypted seed and its public metadata.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeystoreEntry {
+  algorithm: AlgorithmId,
+  created: u64,
+  label: String,
+  public_key: Vec<u8>,
+  kdf: KeystoreKdf,
+  salt: [u8; SALT_BYTES],
+  nonce: [u8; NONCE_BYTES],
+  ciphertext: [u8; ESSENCEBYTES + TAG_BYTES],
+}
+
+impl KeystoreEntry {
+  /// Encrypt `seed` under `passphrase`.
+  fn seal(
+    label: &str,
+    seed: &ArtifactSeed,
+    passphrase: &str,
+    kdf: KeystoreKdf,
+  ) -> Result<Self, EternityError> {
+    if label.is_empty() || label.len() > u16::MAX as usize {
+      return Err(EternityError::InvalidInput);
+    }
+    let mut salt = [0u8; SALT_BYTES];
+    let mut nonce = [0u8; NONCE_BYTES];
+    getrandom::getrandom(&mut salt).expect("OS randomness unavailable");
+    getrandom::getrandom(&mut nonce).expect("OS randomness unavailable");
+    let created = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map_or(0, |elapsed| elapsed.as_secs());
+
+    let mut entry = KeystoreEntry {
+      algorithm: AlgorithmId::CURRENT,
+      created,
+      label: label.to_owned(),
+      public_key: seed.public_key().as_bytes().to_vec(),
+      kdf,
+      salt,
+      nonce,
+      ciphertext: [0u8; ESSENCEBYTES + TAG_BYTES],
+    };
+    let key = kdf.derive_key(passphrase, &salt)?;
+    let associated_data = entry.associated_data();
+    let (body, tag) = entry.ciphertext.split_at_mut(ESSENCEBYTES);
+    body.copy_from_slice(seed.as_bytes());
+    let computed = ChaCha20Poly1305::new(Key::from_slice(&key[..]))
+      .encrypt_in_place_detached(
+        Nonce::from_slice(&nonce),
+        &associated_data,
+        body,
+      )
+      .map_err(|_| EternityError::InvalidInput)?;
+    tag.copy_from_slice(&computed);
+    Ok(entry)
+  }
+
+  /// Decrypt the seed.
+  ///
+  /// A wrong passphrase or a modified entry fails with `VerificationFailed`;
+  /// an entry of another parameter set with `ParameterMismatch`.
+  pub fn decrypt(
+    &self,
+    passphrase: &str,
+  ) -> Result<ArtifactSeed, EternityError> {
+    if self.algorithm != AlgorithmId::CURRENT {
+      return Err(if self.algorithm.is_composite() {
+        EternityError::UnsupportedAlgorithm
+      } else {
+        EternityError::ParameterMismatch
+      });
+    }
+    let key = self.kdf.derive_key(passphrase, &self.salt)?;
+    let mut seed = Zeroizing::new([0u8; ESSENCEBYTES]);
+    seed.copy_from_slice(&self.ciphertext[..ESSENCEBYTES]);
+    ChaCha20Poly1305::new(Key::from_slice(&key[..]))
+      .decrypt_in_place_detached(
+        Nonce::from_slice(&self.nonce),
+        &self.associated_data(),
+        &mut seed[..],
+        Tag::from_slice(&self.ciphertext[ESSENCEBYTES..]),
+      )
+      .map_err(|_| EternityError::VerificationFailed)?;
+    Ok(ArtifactSeed::from_array(&seed))
+  }
+
+  /// The entry's label, unique within its keystore.
+  pub fn label(&self) -> &str {
+    &self.label
+  }
+
+  /// When the entry was added.
+  pub fn created(&self) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(self.created)
+  }
+
+  /// The algorithm of the encrypted key.
+  pub fn algorithm(&self) -> AlgorithmId {
+    self.algorithm
+  }
+
+  /// The parameter set of the encrypted key.
+  pub fn parameter_set(&self) -> ParameterSet {
+    self.algorithm.parameter_set()
+  }
+
+  /// The scrypt cost of unlocking the entry.
+  pub fn kdf(&self) -> KeystoreKdf {
+    self.kdf
+  }
+
+  /// The raw public key matching the encrypted seed.
+  pub fn public_key_bytes(&self) -> &[u8] {
+    &self.public_key
+  }
+
+  /// The public key, if it belongs to the compiled parameter set.
+  pub fn public_key(&self) -> Result<ArtifactPublicKey, EternityError> {
+    ArtifactPublicKey::from_bytes(&self.public_key)
+  }
+
+  /// Everything in front of the ciphertext.
+  fn header(&self) -> Vec<u8> {
+    let mut out = vec![self.algorithm as u8];
+    out.extend_from_slice(&self.created.to_be_bytes());
+    out.extend_from_slice(&(self.label.len() as u16).to_be_bytes());
+    out.extend_from_slice(self.label.as_bytes());
+    out.extend_from_slice(&(self.public_key.len() as u16).to_be_bytes());
+    out.extend_from_slice(&self.public_key);
+    out.push(self.kdf.log_n);
+    out.extend_from_slice(&self.kdf.r.to_be_bytes());
+    out.extend_from_slice(&self.kdf.p.to_be_bytes());
+    out.extend_from_slice(&self.salt);
+    out.extend_from_slice(&self.nonce);
+    out
+  }
+
+  fn associated_data(&self) -> Vec<u8> {
+    let mut out = file_header().to_vec();
+    out.extend_from_slice(&self.header());
+    out
+  }
+
+  fn write(&self, out: &mut Vec<u8>) {
+    out.extend_from_slice(&self.header());
+    out.extend_from_slice(&self.ciphertext);
+  }
+
+  fn read(reader: &mut Reader<'_>) -> Result<Self, EternityError> {
+    let algorithm = AlgorithmId::from_u8(reader.array::<1>()?[0])
+      .ok_or(EternityError::UnsupportedAlgorithm)?;
+    let created = u64::from_be_bytes(reader.array()?);
+    let label_len = u16::from_be_bytes(reader.array()?) as usize;
+    let label = core::str::from_utf8(reader.take(label_len)?)
+      .map_err(|_| EternityError::InvalidInput)?;
+    if label.is_empty() {
+      return Err(EternityError::InvalidInput);
+    }
+    let public_key_len = u16::from_be_bytes(reader.array()?) as usize;
+    if public_key_len != algorithm.public_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    let public_key = reader.take(public_key_len)?.to_vec();
+    let kdf = KeystoreKdf {
+      log_n: reader.array::<1>()?[0],
+      r: u32::from_be_bytes(reader.array()?),
+      p: u32::from_be_bytes(reader.array()?),
+    };
+    kdf.check()?;
+    Ok(KeystoreEntry {
+      algorithm,
+      created,
+      label: label.to_owned(),
+      public_key,
+      kdf,
+      salt: reader.array()?,
+      nonce: reader.array()?,
+      ciphertext: reader.array()?,
+    })
+  }
+}
+
+impl fmt::Debug for KeystoreEntry {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("KeystoreEntry")
+      .field("label", &self.label)
+      .field("algorithm", &self.algorithm)
+      .field("created", &self.created)
+      .field("kdf", &self.kdf)
+      .finish_non_exhaustive()
+  }
+}
+
+/// A set of labelled, individually encrypted key seeds.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Keystore {
+  entries: Vec<KeystoreEntry>,
+}
+
+impl Keystore {
+  /// An empty keystore.
+  pub fn new() -> Self {
+    Keystore::default()
+  }
+
+  /// Parse a keystore file.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, EternityError> {
+    let mut reader = Reader { bytes };
+    if reader.take(KEYSTORE_MAGIC.len())? != KEYSTORE_MAGIC {
+      return Err(EternityError::InvalidInput);
+    }
+    if reader.array::<1>()?[0] != KEYSTORE_VERSION {
+      return Err(EternityError::InvalidInput);
+    }
+    let count = u16::from_be_bytes(reader.array()?);
+    let mut keystore = Keystore::new();
+    for _ in 0..count {
+      let entry = KeystoreEntry::read(&mut reader)?;
+      if keystore.get(&entry.label).is_some() {
+        return Err(EternityError::InvalidInput);
+      }
+      keystore.entries.push(entry);
+    }
+    if !reader.bytes.is_empty() {
+      return Err(EternityError::InvalidInput);
+    }
+    Ok(keystore)
+  }
+
+  /// Encode the keystore file.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let mut out = file_header().to_vec();
+    out.extend_from_slice(&(self.entries.len() as u16).to_be_bytes());
+    for entry in &self.entries {
+      entry.write(&mut out);
+    }
+    out
+  }
+
+  /// Read a keystore file from disk.
+  ///
+  /// A malformed file is reported as `InvalidData` wrapping the
+  /// [`EternityError`].
+  pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+    let bytes = fs::read(path)?;
+    Keystore::from_bytes(&bytes)
+      .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+  }
+
+  /// Write the keystore to disk, replacing any existing file.
+  ///
+  /// The file is written next to `path` and renamed into place, and on Unix
+  /// is readable by its owner only.
+  pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    io::Write::write_all(&mut options.open(&temporary)?, &self.to_bytes())?;
+    fs::rename(&temporary, path)
+  }
+
+  /// All entries, in insertion order.
+  pub fn entries(&self) -> &[KeystoreEntry] {
+    &self.entries
+  }
+
+  /// The entry labelled `label`.
+  pub fn get(&self, label: &str) -> Option<&KeystoreEntry> {
+    self.entries.iter().find(|entry| entry.label == label)
+  }
+
+  /// Encrypt `seed` under `passphrase` with the default scrypt cost.
+  ///
+  /// Fails with `InvalidInput` if the label is empty, longer than 65535
+  /// bytes or already taken, or the keystore is full.
+  pub fn insert(
+    &mut self,
+    label: &str,
+    seed: &ArtifactSeed,
+    passphrase: &str,
+  ) -> Result<&KeystoreEntry, EternityError> {
+    self.insert_with_kdf(label, seed, passphrase, KeystoreKdf::default())
+  }
+
+  /// Encrypt `seed` under `passphrase` with a chosen scrypt cost.
+  pub fn insert_with_kdf(
+    &mut self,
+    label: &str,
+    seed: &ArtifactSeed,
+    passphrase: &str,
+    kdf: KeystoreKdf,
+  ) -> Result<&KeystoreEntry, EternityError> {
+    if self.get(label).is_some() || self.entries.len() == u16::MAX as usize {
+      return Err(EternityError::InvalidInput);
+    }
+    let entry = KeystoreEntry::seal(label, seed, passphrase, kdf)?;
+    self.entries.push(entry);
+    Ok(&self.entries[self.entries.len() - 1])
+  }
+
+  /// Remove and return the entry labelled `label`.
+  pub fn remove(&mut self, label: &str) -> Option<KeystoreEntry> {
+    let index = self.entries.iter().position(|entry| entry.label == label)?;
+    Some(self.entries.remove(index))
+  }
+
+  /// Decrypt the seed labelled `label`.
+  ///
+  /// An unknown label fails with `InvalidInput`, otherwise see
+  /// [`KeystoreEntry::decrypt`].
+  pub fn unlock(
+    &self,
+    label: &str,
+    passphrase: &str,
+  ) -> Result<ArtifactSeed, EternityError> {
+    self
+      .get(label)
+      .ok_or(EternityError::InvalidInput)?
+      .decrypt(passphrase)
+  }
+}
+
+fn file_header() -> [u8; 6] {
+  let mut header = [0u8; 6];
+  header[..5].copy_from_slice(KEYSTORE_MAGIC);
+  header[5] = KEYSTORE_VERSION;
+  header
+}
+
+struct Reader<'a> {
+  bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+  fn take(&mut self, len: usize) -> Result<&'a [u8], EternityError> {
+    if self.bytes.len() < len {
+      return Err(EternityError::InvalidInput);
+    }
+    let (head, tail) = self.bytes.split_at(len);
+    self.bytes = tail;
+    Ok(head)
+  }
+
+  fn array<const N: usize>(&mut self) -> Result<[u8; N], EternityError> {
+    let mut out = [0u8; N];
+    out.copy_from_slice(self.take(N)?);
+    Ok(out)
+  }
+}
@@ -1,15 +1,66 @@
 mod api;
+#[cfg(feature = "parallel")]
+mod batch;
+mod bounds;
+#[cfg(feature = "composite")]
+mod composite;
+mod der;
+mod derivation;
+mod encoding;
+mod envelope;
+mod ffi;
 mod fips202;
+#[cfg(feature = "internals")]
+#[doc(hidden)]
+pub mod internals;
+mod keys;
+#[cfg(feature = "keystore")]
+mod keystore;
+#[cfg(feature = "mlock")]
+mod locked;
+#[cfg(feature = "mnemonic")]
+mod mnemonic;
 mod ntt;
 mod packing;
 mod params;
+mod pkcs8;
 mod poly;
 mod polyvec;
 mod reduce;
 mod rounding;
+#[cfg(feature = "signature")]
+mod rustcrypto;
+mod scheme;
 mod sign;
 mod symmetric;
+#[cfg(feature = "artifact-trace")]
+mod trace;
+#[cfg(feature = "mnemonic")]
+mod wordlist;
+mod x509;
 pub use params::*;
 
 pub use api::*;
+#[cfg(feature = "parallel")]
+pub use batch::*;
+#[cfg(feature = "composite")]
+pub use composite::*;
+pub use derivation::*;
+pub use envelope::*;
+#[cfg(feature = "keystore")]
+pub use keystore::*;
+#[cfg(feature = "mlock")]
+pub use locked::*;
+#[cfg(feature = "mnemonic")]
+pub use mnemonic::*;
+pub use pkcs8::*;
+pub use scheme::*;
+pub use encoding::{base64_decode, base64_encode, hex_decode, hex_encode};
+pub use keys::{
+  ArtifactPublicKey, ArtifactSecretKey, ArtifactSeed, ArtifactSignature,
+  SignatureStats,
+};
+pub use x509::*;
+#[cfg(feature = "artifact-trace")]
+pub use trace::{ArtifactTrace, TraceEntry, TraceValue};
 
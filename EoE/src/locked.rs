@@ -0,0 +1,183 @@
+//! Page-locked storage for long-lived secrets.
+//!
+//! [`LockedBox`] puts its value on pages of its own and asks the kernel with
+//! `mlock` to keep them out of swap. Locking is best effort: it fails when
+//! the process exceeds `RLIMIT_MEMLOCK`, and is not attempted off Unix, in
+//! which case the value still lives on dedicated pages and is wiped on drop.
+//! [`LockedBox::is_locked`] tells the two apart.
+
+use core::{
+  fmt,
+  ops::{Deref, DerefMut},
+  ptr::NonNull,
+};
+use std::alloc::{self, Layout};
+
+use zeroize::Zeroize;
+
+use crate::{
+  keys::{ArtifactSecretKey, ArtifactSeed, ArtifactSignature},
+  params::*,
+  sign::{artifact_keypair, artifact_sign},
+};
+
+/// A heap value on dedicated, `mlock`ed pages, wiped before it is freed.
+pub struct LockedBox<T: Zeroize> {
+  ptr: NonNull<T>,
+  layout: Layout,
+  locked: bool,
+}
+
+// The box owns its value like `Box<T>` does.
+unsafe impl<T: Zeroize + Send> Send for LockedBox<T> {}
+unsafe impl<T: Zeroize + Sync> Sync for LockedBox<T> {}
+
+impl<T: Zeroize> LockedBox<T> {
+  /// Move `value` onto locked pages.
+  ///
+  /// Moving leaves the caller's copy of `value` behind; to keep a secret off
+  /// the stack, box a zeroed value and fill it in through `DerefMut`.
+  pub fn new(value: T) -> Self {
+    let page = page_size();
+    let size = core::mem::size_of::<T>().max(1).next_multiple_of(page);
+    let align = core::mem::align_of::<T>().max(page);
+    let layout = Layout::from_size_align(size, align)
+      .expect("locked allocation too large");
+    // SAFETY: `layout` has a non-zero size.
+    let ptr = unsafe { alloc::alloc_zeroed(layout) }.cast::<T>();
+    let ptr =
+      NonNull::new(ptr).unwrap_or_else(|| alloc::handle_alloc_error(layout));
+    let locked = lock(ptr.as_ptr().cast(), size);
+    // SAFETY: `ptr` is valid and aligned for a `T`.
+    unsafe { ptr.as_ptr().write(value) };
+    LockedBox {
+      ptr,
+      layout,
+      locked,
+    }
+  }
+
+  /// Whether the pages are locked in memory.
+  pub fn is_locked(&self) -> bool {
+    self.locked
+  }
+}
+
+impl<T: Zeroize> Deref for LockedBox<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    // SAFETY: the value is initialized for the lifetime of the box.
+    unsafe { self.ptr.as_ref() }
+  }
+}
+
+impl<T: Zeroize> DerefMut for LockedBox<T> {
+  fn deref_mut(&mut self) -> &mut T {
+    // SAFETY: the value is initialized and uniquely borrowed through `self`.
+    unsafe { self.ptr.as_mut() }
+  }
+}
+
+impl<T: Zeroize> Drop for LockedBox<T> {
+  fn drop(&mut self) {
+    // SAFETY: the value is initialized and never used again; the pages were
+    // allocated with `self.layout`.
+    unsafe {
+      self.ptr.as_mut().zeroize();
+      core::ptr::drop_in_place(self.ptr.as_ptr());
+      if self.locked {
+        unlock(self.ptr.as_ptr().cast(), self.layout.size());
+      }
+      alloc::dealloc(self.ptr.as_ptr().cast(), self.layout);
+    }
+  }
+}
+
+impl<T: Zeroize> fmt::Debug for LockedBox<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("LockedBox")
+      .field("locked", &self.locked)
+      .finish_non_exhaustive()
+  }
+}
+
+#[cfg(unix)]
+fn page_size() -> usize {
+  // SAFETY: `sysconf` has no preconditions.
+  match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+    size if size > 0 => size as usize,
+    _ => 4096,
+  }
+}
+
+#[cfg(not(unix))]
+fn page_size() -> usize {
+  4096
+}
+
+#[cfg(unix)]
+fn lock(ptr: *const u8, len: usize) -> bool {
+  // SAFETY: the range is a live allocation of `len` bytes.
+  unsafe { libc::mlock(ptr.cast(), len) == 0 }
+}
+
+#[cfg(not(unix))]
+fn lock(_: *const u8, _: usize) -> bool {
+  false
+}
+
+#[cfg(unix)]
+unsafe fn unlock(ptr: *const u8, len: usize) {
+  libc::munlock(ptr.cast(), len);
+}
+
+#[cfg(not(unix))]
+unsafe fn unlock(_: *const u8, _: usize) {}
+
+/// An expanded secret key kept on locked pages for its whole life.
+pub struct LockedSecretKey {
+  bytes: LockedBox<[u8; SECRETKEYBYTES]>,
+}
+
+impl LockedSecretKey {
+  /// Expand a seed directly onto locked pages.
+  pub fn from_seed(seed: &ArtifactSeed) -> Self {
+    let mut public_key = [0u8; ARTIFACTKEYBYTES];
+    let mut bytes = LockedBox::new([0u8; SECRETKEYBYTES]);
+    artifact_keypair(&mut public_key, &mut bytes, seed.as_array());
+    LockedSecretKey { bytes }
+  }
+
+  /// Copy a secret key onto locked pages.
+  pub fn from_secret_key(secret_key: &ArtifactSecretKey) -> Self {
+    let mut bytes = LockedBox::new([0u8; SECRETKEYBYTES]);
+    bytes.copy_from_slice(secret_key.as_bytes());
+    LockedSecretKey { bytes }
+  }
+
+  /// Sign `message` deterministically with this key.
+  pub fn sign(&self, message: &[u8]) -> ArtifactSignature {
+    let mut signature = [0u8; SIGNATUREBYTES];
+    artifact_sign(&mut signature, message, &self.bytes);
+    ArtifactSignature::from_array(&signature)
+  }
+
+  /// The raw secret key bytes.
+  pub fn expose_secret(&self) -> &[u8] {
+    &self.bytes[..]
+  }
+
+  /// Whether the key's pages are locked in memory.
+  pub fn is_locked(&self) -> bool {
+    self.bytes.is_locked()
+  }
+}
+
+impl fmt::Debug for LockedSecretKey {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("LockedSecretKey")
+      .field("locked", &self.is_locked())
+      .finish_non_exhaustive()
+  }
+}
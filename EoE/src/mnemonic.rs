@@ -0,0 +1,156 @@
+//! Mnemonic backup of key-generation seeds.
+//!
+//! A 32-byte seed is written as 24 words of the BIP39 English wordlist: the
+//! 256 bits followed by the first 8 bits of their SHA-256, cut into 11-bit
+//! word indices, exactly as BIP39 encodes 256 bits of entropy. Unlike BIP39
+//! the words hold the seed itself rather than the input of a key stretch, so
+//! restoring them gives back the exact [`KeyPair::from_seed`] input.
+//!
+//! An optional passphrase is normalized to NFKD, stretched with scrypt
+//! (`N = 2^15, r = 8, p = 1`, salt `"EoE-mnemonic-v1"`) and XORed into the
+//! seed. An empty passphrase leaves the seed unchanged. As in BIP39 a wrong
+//! passphrase is not detected: it restores a different, valid seed.
+
+use core::{fmt, str::FromStr};
+
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{api::*, params::ESSENCEBYTES, wordlist::ENGLISH};
+
+/// Number of words in a mnemonic.
+pub const MNEMONIC_WORDS: usize = 24;
+
+const PASSPHRASE_SALT: &[u8] = b"EoE-mnemonic-v1";
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// A checksummed 24-word encoding of a (passphrase-masked) seed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Mnemonic {
+  entropy: [u8; ESSENCEBYTES],
+}
+
+impl Mnemonic {
+  /// Encode a seed, masked with `passphrase` unless it is empty.
+  pub fn from_seed(seed: &[u8; ESSENCEBYTES], passphrase: &str) -> Self {
+    let mut entropy = passphrase_pad(passphrase);
+    for (e, s) in entropy.iter_mut().zip(seed) {
+      *e ^= s;
+    }
+    Mnemonic { entropy }
+  }
+
+  /// Parse a phrase, checking the wordlist and the checksum.
+  ///
+  /// Words may be separated by any whitespace and are matched
+  /// case-insensitively.
+  pub fn parse(phrase: &str) -> Result<Self, EternityError> {
+    let mut indices = [0u16; MNEMONIC_WORDS];
+    let mut count = 0;
+    for word in phrase.split_whitespace() {
+      if count == MNEMONIC_WORDS {
+        return Err(EternityError::InvalidInput);
+      }
+      let word = word.to_lowercase();
+      let index = ENGLISH
+        .binary_search(&word.as_str())
+        .map_err(|_| EternityError::InvalidInput)?;
+      indices[count] = index as u16;
+      count += 1;
+    }
+    if count != MNEMONIC_WORDS {
+      return Err(EternityError::InvalidInput);
+    }
+
+    // 24 * 11 = 264 bits: the entropy followed by an 8-bit checksum.
+    let mut bits = [0u8; ESSENCEBYTES + 1];
+    for (i, &index) in indices.iter().enumerate() {
+      for bit in 0..11 {
+        if index >> (10 - bit) & 1 == 1 {
+          let position = 11 * i + bit;
+          bits[position / 8] |= 0x80 >> (position % 8);
+        }
+      }
+    }
+    let mut entropy = [0u8; ESSENCEBYTES];
+    entropy.copy_from_slice(&bits[..ESSENCEBYTES]);
+    if checksum(&entropy) != bits[ESSENCEBYTES] {
+      return Err(EternityError::InvalidInput);
+    }
+    Ok(Mnemonic { entropy })
+  }
+
+  /// The words, separated by single spaces.
+  pub fn phrase(&self) -> String {
+    let mut bits = [0u8; ESSENCEBYTES + 1];
+    bits[..ESSENCEBYTES].copy_from_slice(&self.entropy);
+    bits[ESSENCEBYTES] = checksum(&self.entropy);
+
+    let words: Vec<&str> = (0..MNEMONIC_WORDS)
+      .map(|i| {
+        let index = (0..11).fold(0usize, |index, bit| {
+          let position = 11 * i + bit;
+          let set = bits[position / 8] & (0x80 >> (position % 8)) != 0;
+          index << 1 | set as usize
+        });
+        ENGLISH[index]
+      })
+      .collect();
+    words.join(" ")
+  }
+
+  /// Restore the seed, unmasking it with `passphrase`.
+  pub fn to_seed(&self, passphrase: &str) -> [u8; ESSENCEBYTES] {
+    let mut seed = passphrase_pad(passphrase);
+    for (s, e) in seed.iter_mut().zip(&self.entropy) {
+      *s ^= e;
+    }
+    seed
+  }
+
+  /// Restore the key pair generated from the seed.
+  pub fn to_keypair(&self, passphrase: &str) -> KeyPair {
+    KeyPair::from_seed(&Zeroizing::new(self.to_seed(passphrase)))
+  }
+}
+
+impl FromStr for Mnemonic {
+  type Err = EternityError;
+
+  fn from_str(phrase: &str) -> Result<Self, EternityError> {
+    Mnemonic::parse(phrase)
+  }
+}
+
+impl Drop for Mnemonic {
+  fn drop(&mut self) {
+    self.entropy.zeroize();
+  }
+}
+
+impl fmt::Debug for Mnemonic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("Mnemonic(..)")
+  }
+}
+
+fn checksum(entropy: &[u8; ESSENCEBYTES]) -> u8 {
+  Sha256::digest(entropy)[0]
+}
+
+fn passphrase_pad(passphrase: &str) -> [u8; ESSENCEBYTES] {
+  let mut pad = [0u8; ESSENCEBYTES];
+  if passphrase.is_empty() {
+    return pad;
+  }
+  let passphrase = Zeroizing::new(passphrase.nfkd().collect::<String>());
+  let params =
+    scrypt::Params::new(SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P, ESSENCEBYTES)
+      .expect("valid scrypt parameters");
+  scrypt::scrypt(passphrase.as_bytes(), PASSPHRASE_SALT, &params, &mut pad)
+    .expect("valid scrypt output length");
+  pad
+}
@@ -1,27 +1,42 @@
 use crate::{params::*, poly::*, polyvec::*, TransformationError};
 
 /// Unpack an artifact key `artifact_key = (essence, elements)`.
+///
+/// Fails unless `artifact_key` is exactly `ARTIFACTKEYBYTES` long.
 pub fn unpack_artifact_key(
     essence: &mut [u8],
-    elements: &mut ArtifactVector,
+    elements: &mut ArtifactArrayK,
     artifact_key: &[u8],
-) {
+) -> Result<(), TransformationError> {
+    if artifact_key.len() != ARTIFACTKEYBYTES {
+        return Err(TransformationError::InvalidInput);
+    }
+
     essence[..SEEDBYTES].copy_from_slice(&artifact_key[..SEEDBYTES]);
     for i in 0..K {
         artifact_element_unpack(
-            &mut elements.vec[i],
-            &artifact_key[SEEDBYTES + i * ELEMENT_PACKEDBYTES..],
+            &mut elements.elements[i],
+            &artifact_key[SEEDBYTES + i * ELEMENTT1_PACKEDBYTES..],
         );
     }
+
+    Ok(())
 }
 
 /// Unpack an artifact signature `artifact_signature = (glyph, shards, seal)`.
+///
+/// Fails unless `artifact_signature` is exactly `SIGNATUREBYTES` long and
+/// its glyph encoding is canonical. `glyph` must start out zeroed.
 pub fn unpack_artifact_signature(
     seal: &mut [u8],
-    shards: &mut ArtifactShards,
-    glyph: &mut ArtifactVector,
+    shards: &mut ArtifactArrayL,
+    glyph: &mut ArtifactArrayK,
     artifact_signature: &[u8],
 ) -> Result<(), TransformationError> {
+    if artifact_signature.len() != SIGNATUREBYTES {
+        return Err(TransformationError::InvalidInput);
+    }
+
     let mut idx = 0usize;
 
     // Extract the seal (e.g., cryptographic identifier)
@@ -31,7 +46,7 @@ pub fn unpack_artifact_signature(
     // Extract the shards (transformed components)
     for i in 0..L {
         shard_unpack(
-            &mut shards.vec[i],
+            &mut shards.elements[i],
             &artifact_signature[idx + i * SHARD_PACKEDBYTES..],
         );
     }
@@ -50,7 +65,7 @@ pub fn unpack_artifact_signature(
             if j > k && artifact_signature[idx + j] <= artifact_signature[idx + j - 1] {
                 return Err(TransformationError::InvalidInput);
             }
-            glyph.vec[i].coeffs[artifact_signature[idx + j] as usize] = 1;
+            glyph.elements[i].elements[artifact_signature[idx + j] as usize] = 1;
         }
         k = artifact_signature[idx + OMEGA + i] as usize;
     }
@@ -65,3 +80,138 @@ pub fn unpack_artifact_signature(
     Ok(())
 }
 
+
+/// Pack an artifact key `artifact_key = (essence, elements)`.
+pub fn pack_artifact_key(
+    artifact_key: &mut [u8],
+    essence: &[u8],
+    elements: &ArtifactArrayK,
+) {
+    artifact_key[..SEEDBYTES].copy_from_slice(&essence[..SEEDBYTES]);
+    for i in 0..K {
+        artifact_element_pack(
+            &mut artifact_key[SEEDBYTES + i * ELEMENTT1_PACKEDBYTES..],
+            &elements.elements[i],
+        );
+    }
+}
+
+/// Pack a secret key `secret_key = (essence, key, tr, s1, s2, t0)`.
+pub fn pack_artifact_secret(
+    secret_key: &mut [u8],
+    essence: &[u8],
+    tr: &[u8],
+    key: &[u8],
+    t0: &ArtifactArrayK,
+    s1: &ArtifactArrayL,
+    s2: &ArtifactArrayK,
+) {
+    let mut idx = 0usize;
+
+    secret_key[idx..idx + SEEDBYTES].copy_from_slice(&essence[..SEEDBYTES]);
+    idx += SEEDBYTES;
+    secret_key[idx..idx + SEEDBYTES].copy_from_slice(&key[..SEEDBYTES]);
+    idx += SEEDBYTES;
+    secret_key[idx..idx + SEEDBYTES].copy_from_slice(&tr[..SEEDBYTES]);
+    idx += SEEDBYTES;
+
+    for i in 0..L {
+        artifact_eta_pack(
+            &mut secret_key[idx + i * ELEMENTETA_PACKEDBYTES..],
+            &s1.elements[i],
+        );
+    }
+    idx += L * ELEMENTETA_PACKEDBYTES;
+
+    for i in 0..K {
+        artifact_eta_pack(
+            &mut secret_key[idx + i * ELEMENTETA_PACKEDBYTES..],
+            &s2.elements[i],
+        );
+    }
+    idx += K * ELEMENTETA_PACKEDBYTES;
+
+    for i in 0..K {
+        artifact_t0_pack(
+            &mut secret_key[idx + i * ELEMENTT0_PACKEDBYTES..],
+            &t0.elements[i],
+        );
+    }
+}
+
+/// Unpack a secret key `secret_key = (essence, key, tr, s1, s2, t0)`.
+pub fn unpack_artifact_secret(
+    essence: &mut [u8],
+    tr: &mut [u8],
+    key: &mut [u8],
+    t0: &mut ArtifactArrayK,
+    s1: &mut ArtifactArrayL,
+    s2: &mut ArtifactArrayK,
+    secret_key: &[u8],
+) {
+    let mut idx = 0usize;
+
+    essence[..SEEDBYTES].copy_from_slice(&secret_key[idx..idx + SEEDBYTES]);
+    idx += SEEDBYTES;
+    key[..SEEDBYTES].copy_from_slice(&secret_key[idx..idx + SEEDBYTES]);
+    idx += SEEDBYTES;
+    tr[..SEEDBYTES].copy_from_slice(&secret_key[idx..idx + SEEDBYTES]);
+    idx += SEEDBYTES;
+
+    for i in 0..L {
+        artifact_eta_unpack(
+            &mut s1.elements[i],
+            &secret_key[idx + i * ELEMENTETA_PACKEDBYTES..],
+        );
+    }
+    idx += L * ELEMENTETA_PACKEDBYTES;
+
+    for i in 0..K {
+        artifact_eta_unpack(
+            &mut s2.elements[i],
+            &secret_key[idx + i * ELEMENTETA_PACKEDBYTES..],
+        );
+    }
+    idx += K * ELEMENTETA_PACKEDBYTES;
+
+    for i in 0..K {
+        artifact_t0_unpack(
+            &mut t0.elements[i],
+            &secret_key[idx + i * ELEMENTT0_PACKEDBYTES..],
+        );
+    }
+}
+
+/// Pack an artifact signature `artifact_signature = (seal, shards, glyph)`.
+pub fn pack_artifact_signature(
+    artifact_signature: &mut [u8],
+    seal: &[u8],
+    shards: &ArtifactArrayL,
+    glyph: &ArtifactArrayK,
+) {
+    let mut idx = 0usize;
+
+    artifact_signature[..SEEDBYTES].copy_from_slice(&seal[..SEEDBYTES]);
+    idx += SEEDBYTES;
+
+    for i in 0..L {
+        shard_pack(
+            &mut artifact_signature[idx + i * SHARD_PACKEDBYTES..],
+            &shards.elements[i],
+        );
+    }
+    idx += L * SHARD_PACKEDBYTES;
+
+    // Encode glyph positions, followed by the running count per artifact
+    artifact_signature[idx..idx + OMEGA + K].fill(0);
+    let mut k = 0usize;
+    for i in 0..K {
+        for j in 0..ELEMENTS {
+            if glyph.elements[i].elements[j] != 0 {
+                artifact_signature[idx + k] = j as u8;
+                k += 1;
+            }
+        }
+        artifact_signature[idx + OMEGA + i] = k as u8;
+    }
+}
@@ -1,3 +1,5 @@
+use crate::api::EternityError;
+
 #[cfg(feature = "artifact_mode2")]
 mod transformation_mode_2;
 #[cfg(not(any(feature = "artifact_mode2", feature = "artifact_mode5")))]
@@ -58,3 +60,110 @@ pub const SECRETKEYBYTES: usize = 3 * ESSENCEBYTES
 pub const SIGNATUREBYTES: usize =
   ESSENCEBYTES + L * SHARD_PACKEDBYTES + ELEMENTH_PACKEDBYTES;
 
+
+/// Parameter sets an artifact key or signature can belong to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParameterSet {
+  Mode2,
+  Mode3,
+  Mode5,
+}
+
+impl ParameterSet {
+  /// Every parameter set, from smallest to largest.
+  pub const ALL: [ParameterSet; 3] =
+    [ParameterSet::Mode2, ParameterSet::Mode3, ParameterSet::Mode5];
+
+  /// The parameter set this build was compiled for.
+  pub const CURRENT: ParameterSet = if cfg!(feature = "artifact_mode2") {
+    ParameterSet::Mode2
+  } else if cfg!(feature = "artifact_mode5") {
+    ParameterSet::Mode5
+  } else {
+    ParameterSet::Mode3
+  };
+
+  /// Short lowercase name, matching the cargo feature naming.
+  pub const fn name(self) -> &'static str {
+    match self {
+      ParameterSet::Mode2 => "mode2",
+      ParameterSet::Mode3 => "mode3",
+      ParameterSet::Mode5 => "mode5",
+    }
+  }
+
+  /// NIST security level, the digit in the mode name.
+  pub const fn level(self) -> u32 {
+    match self {
+      ParameterSet::Mode2 => 2,
+      ParameterSet::Mode3 => 3,
+      ParameterSet::Mode5 => 5,
+    }
+  }
+
+  /// Look up a parameter set by its security level.
+  pub fn from_level(level: u32) -> Option<ParameterSet> {
+    ParameterSet::ALL.into_iter().find(|set| set.level() == level)
+  }
+
+  /// Matrix dimensions `(K, L)`.
+  pub const fn dimensions(self) -> (usize, usize) {
+    match self {
+      ParameterSet::Mode2 => (4, 4),
+      ParameterSet::Mode3 => (6, 5),
+      ParameterSet::Mode5 => (8, 7),
+    }
+  }
+
+  /// Hint weight bound `OMEGA`.
+  pub const fn omega(self) -> usize {
+    match self {
+      ParameterSet::Mode2 => 80,
+      ParameterSet::Mode3 => 55,
+      ParameterSet::Mode5 => 75,
+    }
+  }
+
+  /// Size of a packed artifact key.
+  pub const fn artifact_key_bytes(self) -> usize {
+    let (k, _) = self.dimensions();
+    ESSENCEBYTES + k * ELEMENTT1_PACKEDBYTES
+  }
+
+  /// Size of a packed secret key.
+  pub const fn secret_key_bytes(self) -> usize {
+    let (k, l) = self.dimensions();
+    let eta_packed = match self {
+      ParameterSet::Mode3 => 128,
+      _ => 96,
+    };
+    3 * ESSENCEBYTES + (l + k) * eta_packed + k * ELEMENTT0_PACKEDBYTES
+  }
+
+  /// Size of a packed signature.
+  pub const fn signature_bytes(self) -> usize {
+    let (k, l) = self.dimensions();
+    let shard_packed = match self {
+      ParameterSet::Mode2 => 576,
+      _ => 640,
+    };
+    ESSENCEBYTES + l * shard_packed + self.omega() + k
+  }
+}
+
+/// Check an encoding length against the compiled parameter set.
+///
+/// Lengths that belong to another parameter set are reported as
+/// `ParameterMismatch` so a wrongly built peer can be told apart from garbage.
+pub(crate) fn check_length(
+  len: usize,
+  expected: fn(ParameterSet) -> usize,
+) -> Result<(), EternityError> {
+  if len == expected(ParameterSet::CURRENT) {
+    Ok(())
+  } else if ParameterSet::ALL.iter().any(|&set| expected(set) == len) {
+    Err(EternityError::ParameterMismatch)
+  } else {
+    Err(EternityError::InvalidInput)
+  }
+}
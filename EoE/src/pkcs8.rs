@@ -0,0 +1,413 @@
+//! SubjectPublicKeyInfo and PKCS#8 (OneAsymmetricKey) encodings.
+//!
+//! Public keys are wrapped in an X.509 SubjectPublicKeyInfo, private keys in
+//! an RFC 5958 OneAsymmetricKey whose `privateKey` holds the LAMPS
+//! `ML-DSA-PrivateKey` choice:
+//!
+//! ```text
+//! ML-DSA-PrivateKey ::= CHOICE {
+//!   seed        [0] IMPLICIT OCTET STRING (SIZE (32)),
+//!   expandedKey OCTET STRING,
+//!   both        SEQUENCE { seed OCTET STRING, expandedKey OCTET STRING } }
+//! ```
+//!
+//! FIPS 204 algorithms use the NIST object identifiers assigned for the LAMPS
+//! drafts. Round 3 Dilithium, which is what this build implements, has no
+//! NIST identifier and uses the ones registered for the round 3 submission.
+//! The algorithm identifier never carries parameters.
+
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+  api::EternityError, der::*, encoding::*, envelope::AlgorithmId, keys::*,
+  params::*,
+};
+
+/// PEM label of a SubjectPublicKeyInfo.
+pub const PEM_PUBLIC_KEY: &str = "PUBLIC KEY";
+
+/// PEM label of a OneAsymmetricKey.
+pub const PEM_PRIVATE_KEY: &str = "PRIVATE KEY";
+
+impl AlgorithmId {
+  /// Object identifier arcs of the algorithm.
+  ///
+  /// Composites over round 3 Dilithium have no assigned identifier.
+  pub const fn oid(self) -> Option<&'static [u32]> {
+    let arcs: &'static [u32] = match self {
+      AlgorithmId::Dilithium2 => &[1, 3, 6, 1, 4, 1, 2, 267, 7, 4, 4],
+      AlgorithmId::Dilithium3 => &[1, 3, 6, 1, 4, 1, 2, 267, 7, 6, 5],
+      AlgorithmId::Dilithium5 => &[1, 3, 6, 1, 4, 1, 2, 267, 7, 8, 7],
+      AlgorithmId::MlDsa44 => &[2, 16, 840, 1, 101, 3, 4, 3, 17],
+      AlgorithmId::MlDsa65 => &[2, 16, 840, 1, 101, 3, 4, 3, 18],
+      AlgorithmId::MlDsa87 => &[2, 16, 840, 1, 101, 3, 4, 3, 19],
+      AlgorithmId::HashMlDsa44Sha512 => &[2, 16, 840, 1, 101, 3, 4, 3, 32],
+      AlgorithmId::HashMlDsa65Sha512 => &[2, 16, 840, 1, 101, 3, 4, 3, 33],
+      AlgorithmId::HashMlDsa87Sha512 => &[2, 16, 840, 1, 101, 3, 4, 3, 34],
+      AlgorithmId::MlDsa44Ed25519 => &[1, 3, 6, 1, 5, 5, 7, 6, 39],
+      AlgorithmId::MlDsa65Ed25519 => &[1, 3, 6, 1, 5, 5, 7, 6, 48],
+      AlgorithmId::Dilithium2Ed25519
+      | AlgorithmId::Dilithium3Ed25519
+      | AlgorithmId::Dilithium5Ed25519 => return None,
+    };
+    Some(arcs)
+  }
+
+  /// Look up an algorithm by the contents of an encoded OBJECT IDENTIFIER.
+  pub fn from_oid_contents(contents: &[u8]) -> Option<AlgorithmId> {
+    AlgorithmId::ALL.into_iter().find(|alg| {
+      alg.oid().map(oid_contents).as_deref() == Some(contents)
+    })
+  }
+}
+
+/// Encode `SEQUENCE { algorithm OBJECT IDENTIFIER }`.
+pub fn encode_algorithm_identifier(
+  algorithm: AlgorithmId,
+) -> Result<Vec<u8>, EternityError> {
+  let oid = algorithm.oid().ok_or(EternityError::UnsupportedAlgorithm)?;
+  Ok(encode_constructed(TAG_SEQUENCE, &[&encode_oid(oid)]))
+}
+
+/// Decode an AlgorithmIdentifier that carries no parameters.
+pub fn decode_algorithm_identifier(
+  contents: &[u8],
+) -> Result<AlgorithmId, EternityError> {
+  let mut reader = DerReader::new(contents);
+  let oid = reader.read(TAG_OID)?;
+  reader.finish()?;
+  AlgorithmId::from_oid_contents(oid).ok_or(EternityError::UnsupportedAlgorithm)
+}
+
+/// Encode a public key as a DER SubjectPublicKeyInfo.
+pub fn encode_public_key_der(
+  algorithm: AlgorithmId,
+  public_key: &[u8],
+) -> Result<Vec<u8>, EternityError> {
+  if public_key.len() != algorithm.public_key_bytes() {
+    return Err(EternityError::InvalidInput);
+  }
+  Ok(encode_constructed(
+    TAG_SEQUENCE,
+    &[
+      &encode_algorithm_identifier(algorithm)?,
+      &encode_bit_string(public_key),
+    ],
+  ))
+}
+
+/// Decode a DER SubjectPublicKeyInfo into its algorithm and raw key.
+pub fn decode_public_key_der(
+  der: &[u8],
+) -> Result<(AlgorithmId, &[u8]), EternityError> {
+  let spki = read_single(der, TAG_SEQUENCE)?;
+  decode_public_key_info(spki.contents)
+}
+
+/// Decode the contents of a SubjectPublicKeyInfo SEQUENCE.
+pub fn decode_public_key_info(
+  contents: &[u8],
+) -> Result<(AlgorithmId, &[u8]), EternityError> {
+  let mut reader = DerReader::new(contents);
+  let algorithm = decode_algorithm_identifier(reader.read(TAG_SEQUENCE)?)?;
+  let public_key = reader.read_bit_string()?;
+  reader.finish()?;
+
+  if public_key.len() != algorithm.public_key_bytes() {
+    return Err(EternityError::InvalidInput);
+  }
+  Ok((algorithm, public_key))
+}
+
+/// A decoded or to-be-encoded private key.
+///
+/// At least one of `seed` and `expanded` must be present. Both present selects
+/// the `both` form, otherwise the one present is used.
+#[derive(Clone)]
+pub struct PrivateKeyDocument {
+  pub algorithm: AlgorithmId,
+  /// The 32-byte key-generation seed.
+  pub seed: Option<[u8; ESSENCEBYTES]>,
+  /// The expanded secret key encoding.
+  pub expanded: Option<Vec<u8>>,
+  /// The matching public key, written to the optional `publicKey` field.
+  pub public_key: Option<Vec<u8>>,
+}
+
+impl core::fmt::Debug for PrivateKeyDocument {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("PrivateKeyDocument")
+      .field("algorithm", &self.algorithm)
+      .field("seed", &self.seed.map(|_| ".."))
+      .field("expanded", &self.expanded.as_ref().map(|_| ".."))
+      .field("public_key", &self.public_key.as_ref().map(|_| ".."))
+      .finish()
+  }
+}
+
+impl Drop for PrivateKeyDocument {
+  fn drop(&mut self) {
+    self.seed.zeroize();
+    self.expanded.zeroize();
+  }
+}
+
+/// Encode a private key as a DER OneAsymmetricKey.
+///
+/// Writes version 2 when the public key is included and version 1 otherwise.
+pub fn encode_private_key_der(
+  document: &PrivateKeyDocument,
+) -> Result<Vec<u8>, EternityError> {
+  let algorithm = document.algorithm;
+  if let Some(expanded) = &document.expanded {
+    if expanded.len() != algorithm.secret_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+  }
+
+  let private_key = match (&document.seed, &document.expanded) {
+    (Some(seed), None) => encode(tag_context_primitive(0), seed),
+    (None, Some(expanded)) => encode(TAG_OCTET_STRING, expanded),
+    (Some(seed), Some(expanded)) => {
+      let seed = Zeroizing::new(encode(TAG_OCTET_STRING, seed));
+      let expanded = Zeroizing::new(encode(TAG_OCTET_STRING, expanded));
+      encode_constructed(TAG_SEQUENCE, &[&seed[..], &expanded[..]])
+    }
+    (None, None) => return Err(EternityError::InvalidInput),
+  };
+  let private_key = Zeroizing::new(private_key);
+
+  let mut fields = Zeroizing::new(vec![
+    encode_small_uint(document.public_key.is_some() as u64),
+    encode_algorithm_identifier(algorithm)?,
+    encode(TAG_OCTET_STRING, &private_key),
+  ]);
+  if let Some(public_key) = &document.public_key {
+    if public_key.len() != algorithm.public_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+    let mut contents = Vec::with_capacity(public_key.len() + 1);
+    contents.push(0);
+    contents.extend_from_slice(public_key);
+    fields.push(encode(tag_context_primitive(1), &contents));
+  }
+
+  let fields: Vec<&[u8]> = fields.iter().map(Vec::as_slice).collect();
+  Ok(encode_constructed(TAG_SEQUENCE, &fields))
+}
+
+/// Decode a DER OneAsymmetricKey (or PKCS#8 v1 PrivateKeyInfo).
+pub fn decode_private_key_der(
+  der: &[u8],
+) -> Result<PrivateKeyDocument, EternityError> {
+  let info = read_single(der, TAG_SEQUENCE)?;
+  let mut reader = DerReader::new(info.contents);
+
+  let version = reader.read_small_uint()?;
+  if version > 1 {
+    return Err(EternityError::InvalidInput);
+  }
+  let algorithm = decode_algorithm_identifier(reader.read(TAG_SEQUENCE)?)?;
+  let private_key = reader.read(TAG_OCTET_STRING)?;
+  reader.read_optional(tag_context(0))?;
+  let public_key = match reader.read_optional(tag_context_primitive(1))? {
+    Some(_) if version == 0 => return Err(EternityError::InvalidInput),
+    Some(contents) => Some(bit_string_bytes(contents)?.to_vec()),
+    None => None,
+  };
+  reader.finish()?;
+
+  let mut document = PrivateKeyDocument {
+    algorithm,
+    seed: None,
+    expanded: None,
+    public_key,
+  };
+
+  let choice = DerReader::new(private_key).read_any()?;
+  if choice.raw.len() != private_key.len() {
+    return Err(EternityError::InvalidInput);
+  }
+  match choice.tag {
+    tag if tag == tag_context_primitive(0) => {
+      document.seed = Some(seed_bytes(choice.contents)?);
+    }
+    TAG_OCTET_STRING => document.expanded = Some(choice.contents.to_vec()),
+    TAG_SEQUENCE => {
+      let mut both = DerReader::new(choice.contents);
+      document.seed = Some(seed_bytes(both.read(TAG_OCTET_STRING)?)?);
+      document.expanded = Some(both.read(TAG_OCTET_STRING)?.to_vec());
+      both.finish()?;
+    }
+    _ => return Err(EternityError::InvalidInput),
+  }
+
+  if let Some(expanded) = &document.expanded {
+    if expanded.len() != algorithm.secret_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+  }
+  if let Some(public_key) = &document.public_key {
+    if public_key.len() != algorithm.public_key_bytes() {
+      return Err(EternityError::InvalidInput);
+    }
+  }
+  Ok(document)
+}
+
+fn seed_bytes(contents: &[u8]) -> Result<[u8; ESSENCEBYTES], EternityError> {
+  contents.try_into().map_err(|_| EternityError::InvalidInput)
+}
+
+/// Wrap DER in a PEM block with 64-column base64 lines.
+pub fn pem_encode(label: &str, der: &[u8]) -> String {
+  let body = base64_encode(der);
+  let mut out = String::with_capacity(body.len() + body.len() / 64 + 64);
+  out.push_str("-----BEGIN ");
+  out.push_str(label);
+  out.push_str("-----\n");
+  for line in body.as_bytes().chunks(64) {
+    out.push_str(core::str::from_utf8(line).unwrap_or_default());
+    out.push('\n');
+  }
+  out.push_str("-----END ");
+  out.push_str(label);
+  out.push_str("-----\n");
+  out
+}
+
+/// Extract the DER of the first PEM block labelled `label`.
+///
+/// Text around the block is ignored, as are line breaks inside it.
+pub fn pem_decode(text: &str, label: &str) -> Result<Vec<u8>, EternityError> {
+  let begin = format!("-----BEGIN {}-----", label);
+  let end = format!("-----END {}-----", label);
+  let start =
+    text.find(&begin).ok_or(EternityError::InvalidInput)? + begin.len();
+  let stop = text[start..].find(&end).ok_or(EternityError::InvalidInput)?;
+  let body: String = text[start..start + stop]
+    .chars()
+    .filter(|c| !c.is_ascii_whitespace())
+    .collect();
+  base64_decode(&body).ok_or(EternityError::InvalidInput)
+}
+
+impl ArtifactPublicKey {
+  /// Encode as a DER SubjectPublicKeyInfo.
+  pub fn to_public_key_der(&self) -> Vec<u8> {
+    encode_public_key_der(AlgorithmId::CURRENT, self.as_bytes())
+      .unwrap_or_default()
+  }
+
+  /// Encode as a PEM `PUBLIC KEY` block.
+  pub fn to_public_key_pem(&self) -> String {
+    pem_encode(PEM_PUBLIC_KEY, &self.to_public_key_der())
+  }
+
+  /// Decode a DER SubjectPublicKeyInfo for the current algorithm.
+  pub fn from_public_key_der(der: &[u8]) -> Result<Self, EternityError> {
+    let (algorithm, public_key) = decode_public_key_der(der)?;
+    if algorithm != AlgorithmId::CURRENT {
+      return Err(EternityError::UnsupportedAlgorithm);
+    }
+    ArtifactPublicKey::from_bytes(public_key)
+  }
+
+  /// Decode a PEM `PUBLIC KEY` block for the current algorithm.
+  pub fn from_public_key_pem(pem: &str) -> Result<Self, EternityError> {
+    ArtifactPublicKey::from_public_key_der(&pem_decode(pem, PEM_PUBLIC_KEY)?)
+  }
+}
+
+impl ArtifactSecretKey {
+  /// Encode as a DER OneAsymmetricKey in the expanded form.
+  pub fn to_pkcs8_der(&self) -> Vec<u8> {
+    let document = PrivateKeyDocument {
+      algorithm: AlgorithmId::CURRENT,
+      seed: None,
+      expanded: Some(self.as_bytes().to_vec()),
+      public_key: None,
+    };
+    encode_private_key_der(&document).unwrap_or_default()
+  }
+
+  /// Encode as a PEM `PRIVATE KEY` block in the expanded form.
+  pub fn to_pkcs8_pem(&self) -> String {
+    pem_encode(PEM_PRIVATE_KEY, &self.to_pkcs8_der())
+  }
+
+  /// Decode a DER OneAsymmetricKey for the current algorithm.
+  ///
+  /// Documents carrying the seed are expanded from it, after checking any
+  /// expanded key and public key stored next to it.
+  pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, EternityError> {
+    let document = decode_private_key_der(der)?;
+    if document.seed.is_some() {
+      return Ok(ArtifactSeed::from_document(&document)?.secret_key().clone());
+    }
+    if document.algorithm != AlgorithmId::CURRENT {
+      return Err(EternityError::UnsupportedAlgorithm);
+    }
+    let expanded =
+      document.expanded.as_deref().ok_or(EternityError::InvalidInput)?;
+    ArtifactSecretKey::from_bytes(expanded)
+  }
+
+  /// Decode a PEM `PRIVATE KEY` block for the current algorithm.
+  pub fn from_pkcs8_pem(pem: &str) -> Result<Self, EternityError> {
+    ArtifactSecretKey::from_pkcs8_der(&pem_decode(pem, PEM_PRIVATE_KEY)?)
+  }
+}
+
+impl ArtifactSeed {
+  /// Encode as a DER OneAsymmetricKey in the seed-only form.
+  pub fn to_pkcs8_der(&self) -> Vec<u8> {
+    let document = PrivateKeyDocument {
+      algorithm: AlgorithmId::CURRENT,
+      seed: Some(*self.as_array()),
+      expanded: None,
+      public_key: None,
+    };
+    encode_private_key_der(&document).unwrap_or_default()
+  }
+
+  /// Encode as a PEM `PRIVATE KEY` block in the seed-only form.
+  pub fn to_pkcs8_pem(&self) -> String {
+    pem_encode(PEM_PRIVATE_KEY, &self.to_pkcs8_der())
+  }
+
+  /// Decode a DER OneAsymmetricKey that carries the seed.
+  pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, EternityError> {
+    ArtifactSeed::from_document(&decode_private_key_der(der)?)
+  }
+
+  /// Decode a PEM `PRIVATE KEY` block that carries the seed.
+  pub fn from_pkcs8_pem(pem: &str) -> Result<Self, EternityError> {
+    ArtifactSeed::from_pkcs8_der(&pem_decode(pem, PEM_PRIVATE_KEY)?)
+  }
+
+  /// Take the seed of a document, rejecting expanded or public keys stored
+  /// alongside it that do not match it.
+  fn from_document(
+    document: &PrivateKeyDocument,
+  ) -> Result<Self, EternityError> {
+    if document.algorithm != AlgorithmId::CURRENT {
+      return Err(EternityError::UnsupportedAlgorithm);
+    }
+    let seed = document.seed.as_ref().ok_or(EternityError::InvalidInput)?;
+    let seed = ArtifactSeed::from_array(seed);
+    if let Some(expanded) = &document.expanded {
+      // Compared in constant time: both sides are secret.
+      if !bool::from(expanded[..].ct_eq(seed.secret_key().as_bytes())) {
+        return Err(EternityError::InvalidInput);
+      }
+    }
+    if let Some(public_key) = &document.public_key {
+      if public_key[..] != *seed.public_key().as_bytes() {
+        return Err(EternityError::InvalidInput);
+      }
+    }
+    Ok(seed)
+  }
+}
@@ -1,16 +1,26 @@
+use zeroize::Zeroize;
+
 use crate::{
-    fips202::*, ntt::*, params::*, reduce::*, rounding::*, symmetric::*,
+    bounds::{self, I32_BOUND},
+    fips202::*, ntt::*, params::*, reduce::*, rounding::{self, *}, symmetric::*,
 };
 
+const Q: i64 = QUANTA as i64;
+
 #[derive(Clone)]
 pub struct Artifact {
     pub elements: Box<[i32]>,
+    /// Claimed bound on |element|, checked at every operation.
+    #[cfg(feature = "artifact-bounds")]
+    pub bound: i64,
 }
 
 impl Artifact {
     pub fn new(size: usize) -> Self {
         Artifact {
             elements: vec![0; size].into_boxed_slice(),
+            #[cfg(feature = "artifact-bounds")]
+            bound: 0,
         }
     }
 }
@@ -21,74 +31,120 @@ impl Default for Artifact {
     }
 }
 
-/// In-place reduction of all artifact elements to a range [0, 2*QUANTA].
+// Artifacts hold s1, s2, t0 and y as well as public values; wipe them all
+// rather than track which is which.
+impl Drop for Artifact {
+    fn drop(&mut self) {
+        self.elements.zeroize();
+    }
+}
+
+/// In-place reduction of all artifact elements to representatives in
+/// [-6283009, 6283008].
 pub fn artifact_reduce(a: &mut Artifact) {
+    bounds::require(a, I32_BOUND - (1 << 22), "artifact_reduce");
     for i in 0..a.elements.len() {
         a.elements[i] = reduce32(a.elements[i]);
     }
+    bounds::claim(a, 6283009, "artifact_reduce");
 }
 
 /// Adjust all artifact elements by adding QUANTA if the element is negative.
 pub fn artifact_caddq(a: &mut Artifact) {
+    bounds::require(a, I32_BOUND, "artifact_caddq");
     for i in 0..a.elements.len() {
         a.elements[i] = caddq(a.elements[i]);
     }
+    let bound = bounds::claimed(a).max(Q - 1);
+    bounds::claim(a, bound, "artifact_caddq");
 }
 
 /// Add two artifacts. No modular reduction is performed.
 pub fn artifact_add(c: &mut Artifact, b: &Artifact) {
+    bounds::require(b, I32_BOUND, "artifact_add");
+    let bound = bounds::claimed(c) + bounds::claimed(b);
+    bounds::fits_i32(bound, "artifact_add");
     for i in 0..c.elements.len() {
         c.elements[i] = c.elements[i] + b.elements[i];
     }
+    bounds::claim(c, bound, "artifact_add");
 }
 
 /// Subtract one artifact from another. Assumes elements of the second input
 /// artifact are less than 2*QUANTA. No modular reduction is performed.
 pub fn artifact_sub(c: &mut Artifact, b: &Artifact) {
+    bounds::require(b, 2 * Q - 1, "artifact_sub");
+    let bound = bounds::claimed(c) + bounds::claimed(b);
+    bounds::fits_i32(bound, "artifact_sub");
     for i in 0..c.elements.len() {
         c.elements[i] = c.elements[i] - b.elements[i];
     }
+    bounds::claim(c, bound, "artifact_sub");
 }
 
 /// Multiply artifact elements by 2^DEPTH without modular reduction.
 /// Assumes input elements are less than 2^{32-DEPTH}.
 pub fn artifact_shiftl(a: &mut Artifact) {
+    bounds::require(a, I32_BOUND >> DEPTH, "artifact_shiftl");
     for i in 0..a.elements.len() {
         a.elements[i] <<= DEPTH;
     }
+    let bound = bounds::claimed(a) << DEPTH;
+    bounds::claim(a, bound, "artifact_shiftl");
 }
 
 /// In-place forward transformation in the NTT domain.
-/// Output elements can be up to 16*QUANTA larger than input elements.
+/// Output elements can be up to 8*(QUANTA - 1) larger than input elements:
+/// each of the 8 levels adds a Montgomery product below QUANTA.
 pub fn artifact_ntt(a: &mut Artifact) {
+    bounds::require(a, I32_BOUND - 8 * (Q - 1), "artifact_ntt");
+    let bound = bounds::claimed(a) + 8 * (Q - 1);
     ntt(&mut a.elements);
+    bounds::claim(a, bound, "artifact_ntt");
 }
 
 /// In-place inverse NTT and scaling by 2^{32}.
-/// Input elements need to be less than 2*QUANTA.
-/// Output elements are less than 2*QUANTA.
+/// Input elements need to be less than QUANTA in absolute value: the
+/// butterflies double them at each of the 8 levels without reduction.
+/// Output elements are again less than QUANTA.
 pub fn artifact_invntt_tomont(a: &mut Artifact) {
+    bounds::require(a, Q - 1, "artifact_invntt_tomont");
     invntt_tomont(&mut a.elements);
+    bounds::claim(a, Q - 1, "artifact_invntt_tomont");
 }
 
 /// Pointwise multiplication of artifacts in the NTT domain,
-/// followed by scaling by 2^{-32}. Output elements are less than 2*QUANTA
-/// if input elements are less than 22*QUANTA.
+/// followed by scaling by 2^{-32}. Input elements need to be less than
+/// 22*QUANTA, and their products must stay within the range of
+/// `montgomery_reduce`, below QUANTA*2^31 in absolute value. Output elements
+/// are less than QUANTA.
 pub fn artifact_pointwise_montgomery(
     c: &mut Artifact,
     a: &Artifact,
     b: &Artifact,
 ) {
+    bounds::require(a, 22 * Q - 1, "artifact_pointwise_montgomery");
+    bounds::require(b, 22 * Q - 1, "artifact_pointwise_montgomery");
+    bounds::require_product(a, b, (Q << 31) - 1, "artifact_pointwise_montgomery");
     for i in 0..ELEMENTS {
-        c.elements[i] = montgomery_reduce((a.elements[i] as i64) * b.elements[i] as i64);
+        let product = (a.elements[i] as i64) * b.elements[i] as i64;
+        #[cfg(feature = "artifact-bounds")]
+        assert!(
+            -(1i64 << 31) * Q <= product && product < Q << 31,
+            "artifact_pointwise_montgomery: product {i} out of range"
+        );
+        c.elements[i] = montgomery_reduce(product);
     }
+    bounds::claim(c, Q - 1, "artifact_pointwise_montgomery");
 }
 
 /// Use a hint artifact to correct the high bits of another artifact.
 pub fn artifact_use_hint(corrected: &mut Artifact, hint: &Artifact) {
+    bounds::require_standard(corrected, "artifact_use_hint");
     for i in 0..ELEMENTS {
         corrected.elements[i] = use_hint(corrected.elements[i], hint.elements[i] as u8);
     }
+    bounds::claim(corrected, HIGH_FRAGMENT_BOUND, "artifact_use_hint");
 }
 
 /// Check if the infinity norm of an artifact is within the given bound.
@@ -107,6 +163,17 @@ pub fn artifact_chknorm(a: &Artifact, bound: i32) -> u8 {
     0
 }
 
+/// Infinity norm of a reduced artifact, the largest centered |element|.
+pub fn artifact_norm(a: &Artifact) -> i32 {
+    let mut norm = 0;
+    for i in 0..ELEMENTS {
+        let mut t = a.elements[i] >> 31;
+        t = a.elements[i] - (t & 2 * a.elements[i]);
+        norm = norm.max(t);
+    }
+    norm
+}
+
 /// Sample uniformly random artifact elements in the range [0, QUANTA-1].
 pub fn artifact_sample_uniform(
     elements: &mut [i32],
@@ -149,5 +216,292 @@ pub fn artifact_uniform(a: &mut Artifact, seed: &[u8], nonce: u16) {
         stream128_squeezeblocks(&mut buf[offset..], 1, &mut state);
         count += artifact_sample_uniform(&mut a.elements[(count as usize)..], ELEMENTS_U32 - count, &buf, buflen);
     }
+    bounds::claim(a, Q - 1, "artifact_uniform");
 }
 
+
+/// Number of stream blocks sampled up front for a short secret artifact.
+const POLY_UNIFORM_ETA_NBLOCKS: usize = if ETA == 2 {
+    (136 + STREAM256_BLOCKBYTES - 1) / STREAM256_BLOCKBYTES
+} else {
+    (227 + STREAM256_BLOCKBYTES - 1) / STREAM256_BLOCKBYTES
+};
+
+/// Number of stream blocks needed for a masking artifact.
+const POLY_UNIFORM_GAMMA1_NBLOCKS: usize =
+    (SHARD_PACKEDBYTES + STREAM256_BLOCKBYTES - 1) / STREAM256_BLOCKBYTES;
+
+/// Largest high fragment from `artifact_decompose` and `artifact_use_hint`.
+const HIGH_FRAGMENT_BOUND: i64 = (Q - 1) / (2 * GAMMA2 as i64) - 1;
+
+/// Split all artifact elements at 2^DEPTH, in place.
+/// `a` holds the input and receives the high fragments; `low` receives the
+/// low fragments. Assumes standard representatives.
+pub fn artifact_power2round(a: &mut Artifact, low: &mut Artifact) {
+    bounds::require_standard(a, "artifact_power2round");
+    for i in 0..ELEMENTS {
+        a.elements[i] =
+            rounding::artifact_power2round(&mut low.elements[i], a.elements[i]);
+    }
+    bounds::claim(a, (Q - 1 + (1 << (DEPTH - 1)) - 1) >> DEPTH, "artifact_power2round");
+    bounds::claim(low, 1 << (DEPTH - 1), "artifact_power2round");
+}
+
+/// Decompose all artifact elements into high and low fragments, in place.
+/// `a` holds the input and receives the high fragments; `low` receives the
+/// low fragments. Assumes standard representatives.
+pub fn artifact_decompose(a: &mut Artifact, low: &mut Artifact) {
+    bounds::require_standard(a, "artifact_decompose");
+    for i in 0..ELEMENTS {
+        a.elements[i] =
+            rounding::artifact_decompose(&mut low.elements[i], a.elements[i]);
+    }
+    bounds::claim(a, HIGH_FRAGMENT_BOUND, "artifact_decompose");
+    bounds::claim(low, GAMMA2 as i64, "artifact_decompose");
+}
+
+/// Compute the hint artifact for the given low and high fragments.
+/// Returns the number of elements set to `1`.
+pub fn artifact_make_hint(
+    hint: &mut Artifact,
+    low: &Artifact,
+    high: &Artifact,
+) -> u32 {
+    let mut count = 0u32;
+    for i in 0..ELEMENTS {
+        let h = rounding::artifact_make_hint(low.elements[i], high.elements[i]);
+        hint.elements[i] = h as i32;
+        count += h as u32;
+    }
+    bounds::claim(hint, 1, "artifact_make_hint");
+    count
+}
+
+/// Sample short artifact elements in [-ETA, ETA] by rejection sampling on
+/// half-bytes of `buf`. Returns the number of elements written.
+pub fn artifact_rej_eta(
+    elements: &mut [i32],
+    len: u32,
+    buf: &[u8],
+    buflen: usize,
+) -> u32 {
+    let (mut count, mut pos) = (0usize, 0usize);
+    while count < len as usize && pos < buflen {
+        let mut t0 = (buf[pos] & 0x0F) as u32;
+        let mut t1 = (buf[pos] >> 4) as u32;
+        pos += 1;
+
+        if ETA == 2 {
+            if t0 < 15 {
+                t0 -= ((205 * t0) >> 10) * 5;
+                elements[count] = 2 - t0 as i32;
+                count += 1;
+            }
+            if t1 < 15 && count < len as usize {
+                t1 -= ((205 * t1) >> 10) * 5;
+                elements[count] = 2 - t1 as i32;
+                count += 1;
+            }
+        } else {
+            if t0 < 9 {
+                elements[count] = 4 - t0 as i32;
+                count += 1;
+            }
+            if t1 < 9 && count < len as usize {
+                elements[count] = 4 - t1 as i32;
+                count += 1;
+            }
+        }
+    }
+    count as u32
+}
+
+/// Generate a short secret artifact with elements in [-ETA, ETA] from a
+/// CRHBYTES seed and a nonce.
+pub fn artifact_uniform_eta(a: &mut Artifact, seed: &[u8], nonce: u16) {
+    let buflen = POLY_UNIFORM_ETA_NBLOCKS * STREAM256_BLOCKBYTES;
+    let mut buf = [0u8; POLY_UNIFORM_ETA_NBLOCKS * STREAM256_BLOCKBYTES];
+    let mut state = EternityStream256State::default();
+    eternity_stream256_init(&mut state, seed, nonce);
+    eternity_stream256_squeeze_blocks(
+        &mut buf,
+        POLY_UNIFORM_ETA_NBLOCKS as u64,
+        &mut state,
+    );
+    let mut count = artifact_rej_eta(&mut a.elements, ELEMENTS_U32, &buf, buflen);
+    while count < ELEMENTS_U32 {
+        eternity_stream256_squeeze_blocks(&mut buf, 1, &mut state);
+        count += artifact_rej_eta(
+            &mut a.elements[(count as usize)..],
+            ELEMENTS_U32 - count,
+            &buf,
+            STREAM256_BLOCKBYTES,
+        );
+    }
+    buf.zeroize();
+    bounds::claim(a, ETA as i64, "artifact_uniform_eta");
+}
+
+/// Generate a masking artifact with elements in [-(GAMMA1 - 1), GAMMA1] from
+/// a CRHBYTES seed and a nonce.
+pub fn artifact_uniform_gamma1(a: &mut Artifact, seed: &[u8], nonce: u16) {
+    let mut buf = [0u8; POLY_UNIFORM_GAMMA1_NBLOCKS * STREAM256_BLOCKBYTES];
+    let mut state = EternityStream256State::default();
+    eternity_stream256_init(&mut state, seed, nonce);
+    eternity_stream256_squeeze_blocks(
+        &mut buf,
+        POLY_UNIFORM_GAMMA1_NBLOCKS as u64,
+        &mut state,
+    );
+    shard_unpack(a, &buf);
+    buf.zeroize();
+    bounds::claim(a, GAMMA1 as i64, "artifact_uniform_gamma1");
+}
+
+/// Pack a short secret artifact with elements in [-ETA, ETA].
+pub fn artifact_eta_pack(r: &mut [u8], a: &Artifact) {
+    let mut t = [0u8; 8];
+    if ETA == 2 {
+        for i in 0..ELEMENTS / 8 {
+            for j in 0..8 {
+                t[j] = (ETA_I32 - a.elements[8 * i + j]) as u8;
+            }
+            r[3 * i] = t[0] | (t[1] << 3) | (t[2] << 6);
+            r[3 * i + 1] = (t[2] >> 2) | (t[3] << 1) | (t[4] << 4) | (t[5] << 7);
+            r[3 * i + 2] = (t[5] >> 1) | (t[6] << 2) | (t[7] << 5);
+        }
+    } else {
+        for i in 0..ELEMENTS / 2 {
+            t[0] = (ETA_I32 - a.elements[2 * i]) as u8;
+            t[1] = (ETA_I32 - a.elements[2 * i + 1]) as u8;
+            r[i] = t[0] | (t[1] << 4);
+        }
+    }
+    t.zeroize();
+}
+
+/// Unpack a short secret artifact with elements in [-ETA, ETA].
+pub fn artifact_eta_unpack(a: &mut Artifact, r: &[u8]) {
+    let mut b = [0i32; 3];
+    if ETA == 2 {
+        for i in 0..ELEMENTS / 8 {
+            b = [r[3 * i] as i32, r[3 * i + 1] as i32, r[3 * i + 2] as i32];
+            let e = &mut a.elements[8 * i..8 * i + 8];
+            e[0] = b[0] & 7;
+            e[1] = (b[0] >> 3) & 7;
+            e[2] = ((b[0] >> 6) | (b[1] << 2)) & 7;
+            e[3] = (b[1] >> 1) & 7;
+            e[4] = (b[1] >> 4) & 7;
+            e[5] = ((b[1] >> 7) | (b[2] << 1)) & 7;
+            e[6] = (b[2] >> 2) & 7;
+            e[7] = (b[2] >> 5) & 7;
+            for x in e.iter_mut() {
+                *x = ETA_I32 - *x;
+            }
+        }
+    } else {
+        for i in 0..ELEMENTS / 2 {
+            a.elements[2 * i] = ETA_I32 - (r[i] & 0x0F) as i32;
+            a.elements[2 * i + 1] = ETA_I32 - (r[i] >> 4) as i32;
+        }
+    }
+    b.zeroize();
+    // Malformed keys decode to anything the 3 or 4 bits allow.
+    let bound = if ETA == 2 { 7 - ETA as i64 } else { 15 - ETA as i64 };
+    bounds::claim(a, bound, "artifact_eta_unpack");
+}
+
+/// Pack the low fragments t0 with elements in [-2^{DEPTH-1}+1, 2^{DEPTH-1}].
+pub fn artifact_t0_pack(r: &mut [u8], a: &Artifact) {
+    let mut t = [0u32; 8];
+    for i in 0..ELEMENTS / 8 {
+        for j in 0..8 {
+            t[j] = ((1 << (DEPTH - 1)) - a.elements[8 * i + j]) as u32;
+        }
+        let r = &mut r[13 * i..13 * i + 13];
+        r[0] = t[0] as u8;
+        r[1] = ((t[0] >> 8) | (t[1] << 5)) as u8;
+        r[2] = (t[1] >> 3) as u8;
+        r[3] = ((t[1] >> 11) | (t[2] << 2)) as u8;
+        r[4] = ((t[2] >> 6) | (t[3] << 7)) as u8;
+        r[5] = (t[3] >> 1) as u8;
+        r[6] = ((t[3] >> 9) | (t[4] << 4)) as u8;
+        r[7] = (t[4] >> 4) as u8;
+        r[8] = ((t[4] >> 12) | (t[5] << 1)) as u8;
+        r[9] = ((t[5] >> 7) | (t[6] << 6)) as u8;
+        r[10] = (t[6] >> 2) as u8;
+        r[11] = ((t[6] >> 10) | (t[7] << 3)) as u8;
+        r[12] = (t[7] >> 5) as u8;
+    }
+    t.zeroize();
+}
+
+/// Unpack the low fragments t0.
+pub fn artifact_t0_unpack(a: &mut Artifact, r: &[u8]) {
+    let (mut b, mut t) = ([0u32; 13], [0u32; 8]);
+    for i in 0..ELEMENTS / 8 {
+        b = core::array::from_fn(|j| r[13 * i + j] as u32);
+        let e = &mut a.elements[8 * i..8 * i + 8];
+        t = [
+            b[0] | (b[1] << 8),
+            (b[1] >> 5) | (b[2] << 3) | (b[3] << 11),
+            (b[3] >> 2) | (b[4] << 6),
+            (b[4] >> 7) | (b[5] << 1) | (b[6] << 9),
+            (b[6] >> 4) | (b[7] << 4) | (b[8] << 12),
+            (b[8] >> 1) | (b[9] << 7),
+            (b[9] >> 6) | (b[10] << 2) | (b[11] << 10),
+            (b[11] >> 3) | (b[12] << 5),
+        ];
+        for j in 0..8 {
+            e[j] = (1 << (DEPTH - 1)) - (t[j] & 0x1FFF) as i32;
+        }
+    }
+    b.zeroize();
+    t.zeroize();
+    bounds::claim(a, 1 << (DEPTH - 1), "artifact_t0_unpack");
+}
+
+/// Pack the high fragments t1 with elements in [0, 1023].
+pub fn artifact_element_pack(r: &mut [u8], a: &Artifact) {
+    for i in 0..ELEMENTS / 4 {
+        let t = &a.elements[4 * i..4 * i + 4];
+        r[5 * i] = t[0] as u8;
+        r[5 * i + 1] = ((t[0] >> 8) | (t[1] << 2)) as u8;
+        r[5 * i + 2] = ((t[1] >> 6) | (t[2] << 4)) as u8;
+        r[5 * i + 3] = ((t[2] >> 4) | (t[3] << 6)) as u8;
+        r[5 * i + 4] = (t[3] >> 2) as u8;
+    }
+}
+
+/// Pack a signature shard with elements in [-(GAMMA1 - 1), GAMMA1].
+pub fn shard_pack(r: &mut [u8], a: &Artifact) {
+    if GAMMA1 == (1 << 17) {
+        for i in 0..ELEMENTS / 4 {
+            let mut t = [0u32; 4];
+            for j in 0..4 {
+                t[j] = (GAMMA1_I32 - a.elements[4 * i + j]) as u32;
+            }
+            let r = &mut r[9 * i..9 * i + 9];
+            r[0] = t[0] as u8;
+            r[1] = (t[0] >> 8) as u8;
+            r[2] = ((t[0] >> 16) | (t[1] << 2)) as u8;
+            r[3] = (t[1] >> 6) as u8;
+            r[4] = ((t[1] >> 14) | (t[2] << 4)) as u8;
+            r[5] = (t[2] >> 4) as u8;
+            r[6] = ((t[2] >> 12) | (t[3] << 6)) as u8;
+            r[7] = (t[3] >> 2) as u8;
+            r[8] = (t[3] >> 10) as u8;
+        }
+    } else {
+        for i in 0..ELEMENTS / 2 {
+            let t0 = (GAMMA1_I32 - a.elements[2 * i]) as u32;
+            let t1 = (GAMMA1_I32 - a.elements[2 * i + 1]) as u32;
+            let r = &mut r[5 * i..5 * i + 5];
+            r[0] = t0 as u8;
+            r[1] = (t0 >> 8) as u8;
+            r[2] = ((t0 >> 16) | (t1 << 4)) as u8;
+            r[3] = (t1 >> 4) as u8;
+            r[4] = (t1 >> 12) as u8;
+        }
+    }
+}
@@ -63,7 +63,7 @@ pub fn artifact_matrix_pointwise_montgomery(
 }
 
 /// Forward NTT transformation for all artifacts in a vector of length L.
-/// Output elements can be up to 16 * QUANTA larger than input elements.
+/// Output elements can be up to 8 * (QUANTA - 1) larger than input elements.
 pub fn artifact_array_l_ntt(vector: &mut ArtifactArrayL) {
     for i in 0..L {
         artifact_ntt(&mut vector.elements[i]);
@@ -96,9 +96,61 @@ pub fn artifact_array_l_chknorm(vector: &ArtifactArrayL, bound: i32) -> u8 {
     0
 }
 
+/// Sample a short secret vector of length L with elements in [-ETA, ETA],
+/// using consecutive nonces starting at `nonce`.
+pub fn artifact_array_l_uniform_eta(vector: &mut ArtifactArrayL, seed: &[u8], nonce: u16) {
+    for i in 0..L {
+        artifact_uniform_eta(&mut vector.elements[i], seed, nonce + i as u16);
+    }
+}
+
+/// Sample a masking vector of length L with elements in [-(GAMMA1 - 1), GAMMA1].
+/// Each call consumes L nonces, starting at `L * nonce`.
+pub fn artifact_array_l_uniform_gamma1(vector: &mut ArtifactArrayL, seed: &[u8], nonce: u16) {
+    for i in 0..L {
+        artifact_uniform_gamma1(
+            &mut vector.elements[i],
+            seed,
+            LEVEL_U16.wrapping_mul(nonce).wrapping_add(i as u16),
+        );
+    }
+}
+
+/// Reduce all artifact elements in a vector of length L to representatives in [-6283009, 6283008].
+pub fn artifact_array_l_reduce(vector: &mut ArtifactArrayL) {
+    for i in 0..L {
+        artifact_reduce(&mut vector.elements[i]);
+    }
+}
+
+/// Add two artifact vectors of length L. No modular reduction is performed.
+pub fn artifact_array_l_add(result: &mut ArtifactArrayL, vector: &ArtifactArrayL) {
+    for i in 0..L {
+        artifact_add(&mut result.elements[i], &vector.elements[i]);
+    }
+}
+
+/// Perform inverse NTT and 2^{32} scaling on all artifacts in a vector of length L.
+pub fn artifact_array_l_invntt_tomont(vector: &mut ArtifactArrayL) {
+    for i in 0..L {
+        artifact_invntt_tomont(&mut vector.elements[i]);
+    }
+}
+
+/// Perform pointwise multiplication of an artifact vector of length L by a scalar artifact.
+pub fn artifact_array_l_pointwise_poly_montgomery(
+    result: &mut ArtifactArrayL,
+    scalar: &Artifact,
+    vector: &ArtifactArrayL,
+) {
+    for i in 0..L {
+        artifact_pointwise_montgomery(&mut result.elements[i], scalar, &vector.elements[i]);
+    }
+}
+
 //************************ Artifact Arrays of Length K **************************
 
-/// Reduce all artifact elements in a vector of length K to representatives in [0, 2*QUANTA].
+/// Reduce all artifact elements in a vector of length K to representatives in [-6283009, 6283008].
 pub fn artifact_array_k_reduce(vector: &mut ArtifactArrayK) {
     for i in 0..K {
         artifact_reduce(&mut vector.elements[i]);
@@ -166,3 +218,59 @@ pub fn artifact_array_k_pack_w1(result: &mut [u8], vector: &ArtifactArrayK) {
     }
 }
 
+
+/// Sample a short secret vector of length K with elements in [-ETA, ETA],
+/// using consecutive nonces starting at `nonce`.
+pub fn artifact_array_k_uniform_eta(vector: &mut ArtifactArrayK, seed: &[u8], nonce: u16) {
+    for i in 0..K {
+        artifact_uniform_eta(&mut vector.elements[i], seed, nonce + i as u16);
+    }
+}
+
+/// Add two artifact vectors of length K. No modular reduction is performed.
+pub fn artifact_array_k_add(result: &mut ArtifactArrayK, vector: &ArtifactArrayK) {
+    for i in 0..K {
+        artifact_add(&mut result.elements[i], &vector.elements[i]);
+    }
+}
+
+/// Check the infinity norm of an artifact vector of length K.
+/// Returns `0` if the norm is strictly less than `bound`, otherwise `1`.
+pub fn artifact_array_k_chknorm(vector: &ArtifactArrayK, bound: i32) -> u8 {
+    for i in 0..K {
+        if artifact_chknorm(&vector.elements[i], bound) > 0 {
+            return 1;
+        }
+    }
+    0
+}
+
+/// Split all elements of an artifact vector of length K at 2^DEPTH, in place.
+/// `vector` receives the high fragments and `low` the low fragments.
+pub fn artifact_array_k_power2round(vector: &mut ArtifactArrayK, low: &mut ArtifactArrayK) {
+    for i in 0..K {
+        artifact_power2round(&mut vector.elements[i], &mut low.elements[i]);
+    }
+}
+
+/// Decompose all elements of an artifact vector of length K, in place.
+/// `vector` receives the high fragments and `low` the low fragments.
+pub fn artifact_array_k_decompose(vector: &mut ArtifactArrayK, low: &mut ArtifactArrayK) {
+    for i in 0..K {
+        artifact_decompose(&mut vector.elements[i], &mut low.elements[i]);
+    }
+}
+
+/// Compute the hint vector for the given low and high fragments.
+/// Returns the total number of elements set to `1`.
+pub fn artifact_array_k_make_hint(
+    hint: &mut ArtifactArrayK,
+    low: &ArtifactArrayK,
+    high: &ArtifactArrayK,
+) -> u32 {
+    let mut count = 0u32;
+    for i in 0..K {
+        count += artifact_make_hint(&mut hint.elements[i], &low.elements[i], &high.elements[i]);
+    }
+    count
+}
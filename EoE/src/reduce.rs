@@ -2,9 +2,11 @@ use crate::params::*;
 
 pub const QUANTA_INVERSE: i32 = 58728449; // QUANTA^(-1) mod 2^32
 
-/// For an artifact element `a` in the range -2^{31} * QUANTA <= a <= QUANTA * 2^31,
+/// For an artifact element `a` in the range -2^{31} * QUANTA <= a < QUANTA * 2^31,
 /// compute `r` ≡ a * 2^{-32} (mod QUANTA) such that -QUANTA < r < QUANTA.
 ///
+/// The upper bound is exclusive: `a = QUANTA * 2^31` gives `r = QUANTA`.
+///
 /// Returns the reduced artifact element `r`.
 pub fn artifact_montgomery_reduce(a: i64) -> i32 {
     let mut t = (a as i32).wrapping_mul(QUANTA_INVERSE) as i64;
@@ -13,7 +15,9 @@ pub fn artifact_montgomery_reduce(a: i64) -> i32 {
 }
 
 /// For an artifact element `a` in the range a <= 2^{31} - 2^{22} - 1,
-/// compute `r` ≡ a (mod QUANTA) such that -6283009 <= r <= 6283007.
+/// compute `r` ≡ a (mod QUANTA) such that -6283009 <= r <= 6283008.
+///
+/// The maximum is reached only at the top of the range, `a = 2^{31} - 2^{22} - 1`.
 ///
 /// Returns the reduced artifact element `r`.
 pub fn artifact_reduce32(a: i32) -> i32 {
@@ -29,3 +33,45 @@ pub fn artifact_caddq(a: i32) -> i32 {
     a + ((a >> 31) & QUANTA as i32)
 }
 
+/// Kani proofs of the ranges stated above, over every admissible input.
+///
+/// Run with `cargo kani`; the proofs use no unwinding bounds.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    const Q: i64 = QUANTA as i64;
+
+    #[kani::proof]
+    fn montgomery_reduce_range_and_congruence() {
+        let a: i64 = kani::any();
+        kani::assume(-(1i64 << 31) * Q <= a && a < Q * (1i64 << 31));
+        let r = artifact_montgomery_reduce(a) as i64;
+        assert!(-Q < r && r < Q);
+        // r * 2^32 ≡ a (mod QUANTA)
+        assert!(((r as i128) * (1i128 << 32) - a as i128) % Q as i128 == 0);
+    }
+
+    #[kani::proof]
+    fn reduce32_range_and_congruence() {
+        let a: i32 = kani::any();
+        kani::assume(a <= i32::MAX - (1 << 22));
+        let r = artifact_reduce32(a);
+        assert!((-6283009..=6283008).contains(&r));
+        assert!((a as i64 - r as i64) % Q == 0);
+    }
+
+    #[kani::proof]
+    fn caddq_adds_quanta_to_negatives() {
+        let a: i32 = kani::any();
+        let r = artifact_caddq(a);
+        if a < 0 {
+            assert!(r as i64 == a as i64 + Q);
+        } else {
+            assert!(r == a);
+        }
+        if -(Q as i32) <= a && a < Q as i32 {
+            assert!(0 <= r && r < Q as i32);
+        }
+    }
+}
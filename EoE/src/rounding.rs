@@ -61,3 +61,64 @@ pub fn artifact_use_hint(a: i32, hint: u8) -> i32 {
     }
 }
 
+
+/// Split an artifact element into high and low bits at 2^DEPTH.
+///
+/// Computes `high_fragment` and `low_fragment` such that
+/// `a = high_fragment * 2^DEPTH + low_fragment` with
+/// `-2^{DEPTH-1} < low_fragment <= 2^{DEPTH-1}`.
+///
+/// Assumes `a` is a standard artifact representative.
+///
+/// Returns `high_fragment`.
+pub fn artifact_power2round(low_fragment: &mut i32, a: i32) -> i32 {
+    let high_fragment = (a + (1 << (DEPTH - 1)) - 1) >> DEPTH;
+    *low_fragment = a - (high_fragment << DEPTH);
+    high_fragment
+}
+
+/// Compute the hint bit telling whether the low fragment overflows into the
+/// high fragment.
+///
+/// Returns `1` if the high bits of `a` differ from the high bits of
+/// `a + low_fragment`, `0` otherwise.
+pub fn artifact_make_hint(low_fragment: i32, high_fragment: i32) -> u8 {
+    if low_fragment > GAMMA2_I32
+        || low_fragment < -GAMMA2_I32
+        || (low_fragment == -GAMMA2_I32 && high_fragment != 0)
+    {
+        return 1;
+    }
+    0
+}
+
+/// Kani proof of the decomposition contract above for every standard
+/// representative.
+///
+/// Run with `cargo kani`, once per parameter set: `GAMMA2` differs between
+/// mode 2 and modes 3 and 5.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    #[kani::proof]
+    fn decompose_contract() {
+        let a: i32 = kani::any();
+        kani::assume(0 <= a && a < QUANTA_I32);
+        let mut low_fragment = 0i32;
+        let high_fragment = artifact_decompose(&mut low_fragment, a);
+        let alpha = 2 * GAMMA2_I32;
+
+        assert!(0 <= high_fragment && high_fragment < (QUANTA_I32 - 1) / alpha);
+        assert!(-GAMMA2_I32 <= low_fragment && low_fragment <= GAMMA2_I32);
+        let recomposed =
+            high_fragment as i64 * alpha as i64 + low_fragment as i64;
+        if recomposed == a as i64 {
+            assert!(low_fragment > -GAMMA2_I32);
+        } else {
+            // The wrap-around case: a - low_fragment = QUANTA - 1
+            assert!(recomposed == a as i64 - QUANTA_I32 as i64);
+            assert!(high_fragment == 0 && low_fragment < 0);
+        }
+    }
+}
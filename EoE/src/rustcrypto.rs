@@ -0,0 +1,87 @@
+//! RustCrypto `signature` trait implementations.
+//!
+//! Lets the artifact keys slot in wherever code is generic over
+//! `signature::{Signer, Verifier}`, next to ECDSA and Ed25519. The digest
+//! traits sign and verify the finalized digest bytes as the message.
+
+use signature::{
+  digest::Digest, DigestSigner, DigestVerifier, Error, Keypair, Signer,
+  SignatureEncoding, Verifier,
+};
+
+use crate::{api::*, keys::*};
+
+impl SignatureEncoding for ArtifactSignature {
+  type Repr = Vec<u8>;
+
+  fn to_bytes(&self) -> Vec<u8> {
+    self.as_bytes().to_vec()
+  }
+
+  fn encoded_len(&self) -> usize {
+    ArtifactSignature::BYTES
+  }
+}
+
+impl From<ArtifactSignature> for Vec<u8> {
+  fn from(signature: ArtifactSignature) -> Vec<u8> {
+    signature.as_bytes().to_vec()
+  }
+}
+
+impl Verifier<ArtifactSignature> for ArtifactPublicKey {
+  fn verify(
+    &self,
+    message: &[u8],
+    signature: &ArtifactSignature,
+  ) -> Result<(), Error> {
+    ArtifactPublicKey::verify(self, message, signature)
+      .map_err(Error::from_source)
+  }
+}
+
+impl<D: Digest> DigestVerifier<D, ArtifactSignature> for ArtifactPublicKey {
+  fn verify_digest(
+    &self,
+    digest: D,
+    signature: &ArtifactSignature,
+  ) -> Result<(), Error> {
+    ArtifactPublicKey::verify(self, &digest.finalize(), signature)
+      .map_err(Error::from_source)
+  }
+}
+
+impl Signer<ArtifactSignature> for ArtifactSecretKey {
+  fn try_sign(&self, message: &[u8]) -> Result<ArtifactSignature, Error> {
+    Ok(self.sign(message))
+  }
+}
+
+impl<D: Digest> DigestSigner<D, ArtifactSignature> for ArtifactSecretKey {
+  fn try_sign_digest(&self, digest: D) -> Result<ArtifactSignature, Error> {
+    Ok(self.sign(&digest.finalize()))
+  }
+}
+
+impl Signer<ArtifactSignature> for KeyPair {
+  fn try_sign(&self, message: &[u8]) -> Result<ArtifactSignature, Error> {
+    Ok(ArtifactSignature::from_array(&KeyPair::sign(self, message)))
+  }
+}
+
+impl<D: Digest> DigestSigner<D, ArtifactSignature> for KeyPair {
+  fn try_sign_digest(&self, digest: D) -> Result<ArtifactSignature, Error> {
+    Ok(ArtifactSignature::from_array(&KeyPair::sign(
+      self,
+      &digest.finalize(),
+    )))
+  }
+}
+
+impl Keypair for KeyPair {
+  type VerifyingKey = ArtifactPublicKey;
+
+  fn verifying_key(&self) -> ArtifactPublicKey {
+    self.public_key()
+  }
+}
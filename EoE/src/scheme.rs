@@ -0,0 +1,157 @@
+//! Algorithm agility.
+//!
+//! Every signature algorithm this build implements is a [`SignatureScheme`]
+//! registered under its [`AlgorithmId`]. Verification, envelopes and
+//! composites look schemes up by identifier, so supporting another algorithm
+//! is a new implementation plus a registry entry in [`scheme`].
+
+use zeroize::Zeroizing;
+
+use crate::{api::*, envelope::*, keys::ArtifactSecretKey, params::*};
+
+/// A signature algorithm over raw key and signature encodings.
+///
+/// The trait is object safe; schemes are handed out as
+/// `&'static dyn SignatureScheme`.
+pub trait SignatureScheme: Send + Sync {
+  /// Identifier the scheme is registered under.
+  fn algorithm(&self) -> AlgorithmId;
+
+  /// Size of the seed taken by [`SignatureScheme::keypair_from_seed`].
+  fn seed_bytes(&self) -> usize;
+
+  /// Derive `(public_key, secret_key)` deterministically from a seed.
+  ///
+  /// The secret key is wiped when dropped.
+  fn keypair_from_seed(
+    &self,
+    seed: &[u8],
+  ) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), EternityError>;
+
+  /// Sign `message` with an encoded secret key.
+  fn sign(
+    &self,
+    secret_key: &[u8],
+    message: &[u8],
+  ) -> Result<Vec<u8>, EternityError>;
+
+  /// Verify `signature` over `message` with an encoded public key.
+  fn verify(
+    &self,
+    signature: &[u8],
+    message: &[u8],
+    public_key: &[u8],
+  ) -> Result<(), EternityError>;
+
+  /// Wrap a raw key or signature of this scheme in an envelope.
+  fn encode(&self, payload: &[u8]) -> Result<Vec<u8>, EternityError> {
+    seal_envelope(self.algorithm(), payload)
+  }
+
+  /// Open an envelope, rejecting those of other algorithms.
+  fn decode<'a>(
+    &self,
+    bytes: &'a [u8],
+  ) -> Result<Envelope<'a>, EternityError> {
+    let envelope = open_envelope(bytes)?;
+    if envelope.algorithm != self.algorithm() {
+      return Err(EternityError::InvalidInput);
+    }
+    Ok(envelope)
+  }
+}
+
+/// The lattice transformation compiled into this build.
+struct ArtifactScheme;
+
+impl SignatureScheme for ArtifactScheme {
+  fn algorithm(&self) -> AlgorithmId {
+    AlgorithmId::CURRENT
+  }
+
+  fn seed_bytes(&self) -> usize {
+    ESSENCEBYTES
+  }
+
+  fn keypair_from_seed(
+    &self,
+    seed: &[u8],
+  ) -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), EternityError> {
+    let seed = seed.try_into().map_err(|_| EternityError::InvalidInput)?;
+    let keys = KeyPair::from_seed(seed);
+    let secret_key = Zeroizing::new(keys.expose_secret().to_vec());
+    Ok((keys.public.to_vec(), secret_key))
+  }
+
+  fn sign(
+    &self,
+    secret_key: &[u8],
+    message: &[u8],
+  ) -> Result<Vec<u8>, EternityError> {
+    let secret_key = ArtifactSecretKey::from_bytes(secret_key)?;
+    Ok(secret_key.sign(message).as_bytes().to_vec())
+  }
+
+  fn verify(
+    &self,
+    signature: &[u8],
+    message: &[u8],
+    public_key: &[u8],
+  ) -> Result<(), EternityError> {
+    verify_artifact(signature, message, public_key)
+  }
+}
+
+/// Resolve the scheme for any known algorithm.
+///
+/// Every identifier has a fixed outcome:
+///
+/// - [`AlgorithmId::CURRENT`], and [`AlgorithmId::CURRENT_COMPOSITE`] with
+///   the `composite` feature, resolve to their scheme.
+/// - Round 3 Dilithium and its composites at another security level fail
+///   with `ParameterMismatch`: the crate implements them, but the parameter
+///   set is fixed when it is compiled (`artifact_mode2`, `artifact_mode5`).
+/// - FIPS 204 algorithms fail with `UnsupportedAlgorithm`. ML-DSA hashes the
+///   key to a 64-byte `tr`, derives the challenge from a longer seed and
+///   binds a context string, none of which this transformation does.
+/// - Composites fail with `UnsupportedAlgorithm` without the `composite`
+///   feature.
+pub fn resolve_scheme(
+  algorithm: AlgorithmId,
+) -> Result<&'static dyn SignatureScheme, EternityError> {
+  if algorithm.is_fips204()
+    || (algorithm.is_composite() && !cfg!(feature = "composite"))
+  {
+    return Err(EternityError::UnsupportedAlgorithm);
+  }
+  match algorithm {
+    AlgorithmId::CURRENT => Ok(&ArtifactScheme),
+    #[cfg(feature = "composite")]
+    AlgorithmId::CURRENT_COMPOSITE => Ok(&crate::composite::CompositeScheme),
+    _ => Err(EternityError::ParameterMismatch),
+  }
+}
+
+/// Look up the scheme for an algorithm.
+///
+/// Returns `None` for algorithms this build knows the identifier of but does
+/// not implement; [`resolve_scheme`] tells why.
+pub fn scheme(algorithm: AlgorithmId) -> Option<&'static dyn SignatureScheme> {
+  resolve_scheme(algorithm).ok()
+}
+
+/// Every algorithm this build implements, in identifier order.
+pub fn supported_algorithms() -> Vec<AlgorithmId> {
+  AlgorithmId::ALL
+    .into_iter()
+    .filter(|&algorithm| scheme(algorithm).is_some())
+    .collect()
+}
+
+/// Pick the first algorithm of a peer's preference list this build supports.
+pub fn negotiate(offered: &[AlgorithmId]) -> Option<AlgorithmId> {
+  offered
+    .iter()
+    .copied()
+    .find(|&algorithm| scheme(algorithm).is_some())
+}
@@ -1,6 +1,11 @@
+use zeroize::Zeroizing;
+
 use crate::{
-    fips202::*, packing::*, params::*, poly::*, polyvec::*, ValidationError,
+    api::EternityError, fips202::*, packing::*, params::*, poly::*, polyvec::*,
+    ValidationError,
 };
+#[cfg(feature = "artifact-trace")]
+use crate::trace;
 
 /// **Stage 1**: Unpack the artifact's key and prepare essential parameters.
 pub fn artifact_verify_stage1(
@@ -14,7 +19,7 @@ pub fn artifact_verify_stage1(
     let mut essence = Box::new([0u8; SEEDBYTES]);
     let mut elements = Box::new(ArtifactArrayK::default());
 
-    unpack_pk(&mut *essence, &mut *elements, artifact_key);
+    unpack_pk(&mut *essence, &mut *elements, artifact_key)?;
 
     Ok((essence, elements))
 }
@@ -36,20 +41,79 @@ pub fn artifact_verify_stage2(
     Ok((seal, fragments, glyphs))
 }
 
+/// **Pre-validation**: Run only the cheap structural checks on a signature.
+///
+/// Checks the signature and key lengths (the key length fixes the parameter
+/// set), the canonical hint encoding and the norm bound on the fragments.
+/// Skips matrix expansion and every NTT, so malformed signatures can be
+/// dropped before paying for a full verification. Passing this check does not
+/// mean the signature verifies.
+pub fn artifact_prevalidate(
+    signature: &[u8],
+    artifact_key: &[u8],
+) -> Result<(), EternityError> {
+    check_length(artifact_key.len(), ParameterSet::artifact_key_bytes)?;
+    check_length(signature.len(), ParameterSet::signature_bytes)?;
+
+    artifact_verify_stage2(signature).map_err(|_| EternityError::InvalidInput)?;
+
+    Ok(())
+}
+
+/// **Inspection**: Hint weight and fragment norm of a signature.
+///
+/// Decodes the signature like stage 2 but reports the norm of the fragments
+/// instead of rejecting on it, so diagnostics can show how close a signature
+/// is to the bounds. Returns `(hint_weight, norm)`.
+pub fn artifact_signature_stats(
+    signature: &[u8],
+) -> Result<(usize, i32), ValidationError> {
+    if signature.len() != SIGNBYTES {
+        return Err(ValidationError::Input);
+    }
+
+    let mut seal = Box::new([0u8; SEEDBYTES]);
+    let mut fragments = Box::new(ArtifactArrayL::default());
+    let mut glyphs = Box::new(ArtifactArrayK::default());
+
+    unpack_sig(&mut *seal, &mut *fragments, &mut *glyphs, signature)?;
+
+    let weight = glyphs
+        .elements
+        .iter()
+        .map(|glyph| glyph.elements.iter().filter(|&&h| h != 0).count())
+        .sum();
+    let norm = fragments.elements.iter().map(artifact_norm).max().unwrap_or(0);
+
+    Ok((weight, norm))
+}
+
 /// **Stage 3**: Compute the compressed representation of the artifact's essence.
 pub fn artifact_verify_stage3(
     artifact_key: &[u8],
     message: &[u8],
+) -> Box<[u8; CRHBYTES]> {
+    let mut essence_digest = [0u8; SEEDBYTES];
+    shake256(&mut essence_digest, SEEDBYTES, artifact_key, PUBLICKEYBYTES);
+    message_digest(&essence_digest, message)
+}
+
+/// Hash a message under the digest of its artifact key.
+fn message_digest(
+    essence_digest: &[u8; SEEDBYTES],
+    message: &[u8],
 ) -> Box<[u8; CRHBYTES]> {
     let mut digest = Box::new([0u8; CRHBYTES]);
     let mut state = Box::new(KeccakState::default());
 
-    shake256(&mut *digest, SEEDBYTES, artifact_key, PUBLICKEYBYTES);
-    shake256_absorb(&mut *state, &*digest, SEEDBYTES);
+    shake256_absorb(&mut *state, essence_digest, SEEDBYTES);
     shake256_absorb(&mut *state, message, message.len());
     shake256_finalize(&mut *state);
     shake256_squeeze(&mut *digest, CRHBYTES, &mut *state);
 
+    #[cfg(feature = "artifact-trace")]
+    trace::record_bytes("mu", &*digest);
+
     digest
 }
 
@@ -59,50 +123,68 @@ pub fn artifact_verify_stage4(
 ) -> Box<Artifact> {
     let mut challenge = Box::new(Artifact::default());
     artifact_challenge(&mut *challenge, seal);
+
+    #[cfg(feature = "artifact-trace")]
+    {
+        trace::record_bytes("c_tilde", &*seal);
+        trace::record_artifact("c", &challenge);
+    }
+
     challenge
 }
 
 /// **Stage 5**: Expand the artifact matrix.
 pub fn artifact_verify_stage5(
     essence: &mut [u8; SEEDBYTES],
-) -> Box<[ArtifactArrayL; 4]> {
-    let mut matrix = Box::new([
-        ArtifactArrayL::default(),
-        ArtifactArrayL::default(),
-        ArtifactArrayL::default(),
-        ArtifactArrayL::default(),
-    ]);
-
-    artifact_matrix_expand(&mut *matrix, essence);
+) -> Box<[ArtifactArrayL]> {
+    let mut matrix = vec![ArtifactArrayL::default(); K].into_boxed_slice();
+    artifact_matrix_expand(&mut matrix, essence);
     matrix
 }
 
 /// **Stage 6**: Compute the transformed artifact array.
+///
+/// Returns `A*z` and `c*t1*2^DEPTH`, both in the NTT domain, for stage 7 to
+/// subtract.
 pub fn artifact_verify_stage6(
     challenge: Box<Artifact>,
-    matrix: Box<[ArtifactArrayL; 4]>,
+    matrix: Box<[ArtifactArrayL]>,
     fragments: Box<ArtifactArrayL>,
-    elements: Box<ArtifactArrayK>,
-) -> Result<Box<ArtifactArrayK>, ValidationError> {
-    let mut transformed = Box::new(ArtifactArrayK::default());
-    artifact_array_l_ntt(&mut *fragments);
-    artifact_matrix_pointwise_montgomery(&mut *transformed, &*matrix, &*fragments);
-    artifact_ntt(&mut *challenge);
+    mut elements: Box<ArtifactArrayK>,
+) -> Result<(Box<ArtifactArrayK>, ArtifactArrayK), ValidationError> {
     artifact_array_k_shiftl(&mut *elements);
     artifact_array_k_ntt(&mut *elements);
 
-    let cloned_elements = Box::new((*elements).clone());
-    artifact_array_k_pointwise_poly_montgomery(&mut *elements, &*challenge, &*cloned_elements);
+    Ok(transform_commitment(challenge, &matrix, fragments, &elements))
+}
 
-    Ok(transformed)
+/// The products of stage 6, with `t1*2^DEPTH` already in the NTT domain.
+fn transform_commitment(
+    mut challenge: Box<Artifact>,
+    matrix: &[ArtifactArrayL],
+    mut fragments: Box<ArtifactArrayL>,
+    elements: &ArtifactArrayK,
+) -> (Box<ArtifactArrayK>, ArtifactArrayK) {
+    let mut transformed = Box::new(ArtifactArrayK::default());
+    artifact_array_l_ntt(&mut *fragments);
+    artifact_matrix_pointwise_montgomery(&mut *transformed, matrix, &*fragments);
+
+    artifact_ntt(&mut *challenge);
+    let mut scaled = ArtifactArrayK::default();
+    artifact_array_k_pointwise_poly_montgomery(&mut scaled, &*challenge, elements);
+
+    (transformed, scaled)
 }
 
 /// **Stage 7**: Refine the transformed artifact array.
+///
+/// Subtracts the second product of stage 6 from the first and returns
+/// `w' = A*z - c*t1*2^DEPTH` in normal representation.
 pub fn artifact_verify_stage7(
     mut transformed: Box<ArtifactArrayK>,
-    elements: ArtifactArrayK,
+    scaled: ArtifactArrayK,
 ) -> Result<Box<ArtifactArrayK>, ValidationError> {
-    artifact_array_k_sub(&mut *transformed, &elements);
+    artifact_array_k_sub(&mut *transformed, &scaled);
     artifact_array_k_reduce(&mut *transformed);
     artifact_array_k_invntt_tomont(&mut *transformed);
 
@@ -119,8 +201,16 @@ pub fn artifact_verify_stage8(
     computed_seal: &mut [u8; SEEDBYTES],
 ) -> Result<(), ValidationError> {
     artifact_array_k_caddq(&mut *transformed);
+    #[cfg(feature = "artifact-trace")]
+    trace::record_array_k("w_approx", &transformed);
+
     artifact_array_k_use_hint(&mut *transformed, glyphs);
+    #[cfg(feature = "artifact-trace")]
+    trace::record_array_k("w1", &transformed);
+
     artifact_array_k_pack_w1(&mut *buffer, &*transformed);
+    #[cfg(feature = "artifact-trace")]
+    trace::record_bytes("w1_encoded", &*buffer);
 
     let mut state = Box::new(KeccakState::default());
     state.init();
@@ -129,6 +219,9 @@ pub fn artifact_verify_stage8(
     shake256_finalize(&mut *state);
     shake256_squeeze(computed_seal, SEEDBYTES, &mut *state);
 
+    #[cfg(feature = "artifact-trace")]
+    trace::record_bytes("c_tilde_computed", &*computed_seal);
+
     if &**seal != computed_seal {
         Err(ValidationError::Verify)
     } else {
@@ -142,37 +235,255 @@ pub fn artifact_verify(
     message: &[u8],
     artifact_key: &[u8],
 ) -> Result<(), ValidationError> {
-    let (essence, elements) = artifact_verify_stage1(signature, artifact_key)?;
-    let (mut seal, fragments, glyphs) = artifact_verify_stage2(signature)?;
-    let digest = artifact_verify_stage3(artifact_key, message);
+    let prepared = artifact_prepare_key(artifact_key)?;
+    artifact_verify_prepared(signature, message, &prepared)
+}
 
-    let transformed = validate_matrix(essence, &mut seal, fragments, &elements)?;
-    finalize_verification(transformed, elements, glyphs, digest, seal)
+/// The message-independent part of verification under one artifact key.
+///
+/// Holds the expanded matrix, `t1 * 2^DEPTH` in the NTT domain and the key
+/// digest, which together make up most of the cost of a single verification.
+#[derive(Clone)]
+pub struct ArtifactPreparedKey {
+    matrix: Box<[ArtifactArrayL]>,
+    elements: Box<ArtifactArrayK>,
+    essence_digest: [u8; SEEDBYTES],
 }
 
-fn validate_matrix(
-    essence: Box<[u8; SEEDBYTES]>,
-    seal: &mut Box<[u8; SEEDBYTES]>,
-    fragments: Box<ArtifactArrayL>,
-    elements: &Box<ArtifactArrayK>,
-) -> Result<Box<ArtifactArrayK>, ValidationError> {
-    let challenge = artifact_verify_stage4(&mut **seal);
-    let matrix = artifact_verify_stage5(&mut *essence);
-    artifact_verify_stage6(challenge, matrix, fragments, elements.clone())
+/// **Key Preparation**: Run the stages of verification that depend only on
+/// the artifact key, for reuse across any number of signatures.
+pub fn artifact_prepare_key(
+    artifact_key: &[u8],
+) -> Result<ArtifactPreparedKey, ValidationError> {
+    if artifact_key.len() != PUBLICKEYBYTES {
+        return Err(ValidationError::Input);
+    }
+
+    let mut essence = [0u8; SEEDBYTES];
+    let mut elements = Box::new(ArtifactArrayK::default());
+    unpack_pk(&mut essence, &mut *elements, artifact_key)?;
+    let matrix = artifact_verify_stage5(&mut essence);
+
+    artifact_array_k_shiftl(&mut *elements);
+    artifact_array_k_ntt(&mut *elements);
+
+    let mut essence_digest = [0u8; SEEDBYTES];
+    shake256(&mut essence_digest, SEEDBYTES, artifact_key, PUBLICKEYBYTES);
+
+    Ok(ArtifactPreparedKey {
+        matrix,
+        elements,
+        essence_digest,
+    })
 }
 
-fn finalize_verification(
-    transformed: Box<ArtifactArrayK>,
-    elements: Box<ArtifactArrayK>,
-    glyphs: Box<ArtifactArrayK>,
-    digest: Box<[u8; CRHBYTES]>,
-    seal: Box<[u8; SEEDBYTES]>,
+/// **Prepared Verification**: Verify a signature against a prepared key.
+///
+/// Equivalent to [`artifact_verify`] with the key the preparation was built
+/// from, without repeating the matrix expansion and the key's NTTs.
+pub fn artifact_verify_prepared(
+    signature: &[u8],
+    message: &[u8],
+    prepared: &ArtifactPreparedKey,
 ) -> Result<(), ValidationError> {
-    let refined = artifact_verify_stage7(transformed, *elements)?;
+    if signature.len() != SIGNBYTES {
+        return Err(ValidationError::Input);
+    }
+
+    let (mut seal, fragments, glyphs) = artifact_verify_stage2(signature)?;
+    let digest = message_digest(&prepared.essence_digest, message);
+    let challenge = artifact_verify_stage4(&mut *seal);
+    let (transformed, scaled) = transform_commitment(
+        challenge,
+        &prepared.matrix,
+        fragments,
+        &prepared.elements,
+    );
+
+    let refined = artifact_verify_stage7(transformed, scaled)?;
     let buffer = Box::new([0u8; K * POLYW1_PACKEDBYTES]);
-    let mut computed_seal = Box::new([0u8; SEEDBYTES]);
-    artifact_verify_stage8(buffer, refined, &glyphs, digest, &seal, &mut *computed_seal)?;
+    let mut computed_seal = [0u8; SEEDBYTES];
+    artifact_verify_stage8(buffer, refined, &glyphs, digest, &seal, &mut computed_seal)
+}
 
-    Ok(())
+/// **Key Generation**: Derive an artifact key pair from a 32-byte seed.
+///
+/// Expands the seed into the matrix essence, the secret vectors and the
+/// signing key, computes `t = A*s1 + s2` and splits it into the public high
+/// fragments and the secret low fragments.
+pub fn artifact_keypair(
+    artifact_key: &mut [u8; PUBLICKEYBYTES],
+    secret_key: &mut [u8; SECRETKEYBYTES],
+    seed: &[u8; SEEDBYTES],
+) {
+    // rho' and the signing key are secret; keep them off the heap and wipe
+    // them on return. s1, s2 and t0 wipe themselves.
+    let mut seedbuf = Zeroizing::new([0u8; 2 * SEEDBYTES + CRHBYTES]);
+    shake256(&mut seedbuf[..], 2 * SEEDBYTES + CRHBYTES, seed, SEEDBYTES);
+    let (essence, rest) = seedbuf.split_at(SEEDBYTES);
+    let (secret_essence, key) = rest.split_at(CRHBYTES);
+
+    let mut matrix = vec![ArtifactArrayL::default(); K].into_boxed_slice();
+    artifact_matrix_expand(&mut matrix, essence);
+
+    let mut s1 = Box::new(ArtifactArrayL::default());
+    let mut s2 = Box::new(ArtifactArrayK::default());
+    artifact_array_l_uniform_eta(&mut s1, secret_essence, 0);
+    artifact_array_k_uniform_eta(&mut s2, secret_essence, L as u16);
+
+    let mut s1_hat = s1.clone();
+    artifact_array_l_ntt(&mut s1_hat);
+    let mut elements = Box::new(ArtifactArrayK::default());
+    artifact_matrix_pointwise_montgomery(&mut elements, &matrix, &s1_hat);
+    artifact_array_k_reduce(&mut elements);
+    artifact_array_k_invntt_tomont(&mut elements);
+    artifact_array_k_add(&mut elements, &s2);
+    artifact_array_k_caddq(&mut elements);
+
+    let mut t0 = Box::new(ArtifactArrayK::default());
+    artifact_array_k_power2round(&mut elements, &mut t0);
+    pack_artifact_key(artifact_key, essence, &elements);
+
+    let mut tr = [0u8; SEEDBYTES];
+    shake256(&mut tr, SEEDBYTES, artifact_key, PUBLICKEYBYTES);
+
+    #[cfg(feature = "artifact-trace")]
+    {
+        trace::record_bytes("rho", essence);
+        trace::record_array_k("t1", &elements);
+        trace::record_bytes("tr", &tr);
+    }
+
+    pack_artifact_secret(secret_key, essence, &tr, key, &t0, &s1, &s2);
 }
 
+/// **Signing**: Produce a deterministic artifact signature over a message.
+///
+/// The masking randomness is derived from the signing key and the message
+/// digest, so signing the same message twice yields the same signature.
+pub fn artifact_sign(
+    signature: &mut [u8; SIGNBYTES],
+    message: &[u8],
+    secret_key: &[u8; SECRETKEYBYTES],
+) {
+    let mut essence = [0u8; SEEDBYTES];
+    let mut tr = [0u8; SEEDBYTES];
+    let mut key = Zeroizing::new([0u8; SEEDBYTES]);
+    let mut t0 = Box::new(ArtifactArrayK::default());
+    let mut s1 = Box::new(ArtifactArrayL::default());
+    let mut s2 = Box::new(ArtifactArrayK::default());
+    unpack_artifact_secret(
+        &mut essence,
+        &mut tr,
+        &mut key[..],
+        &mut t0,
+        &mut s1,
+        &mut s2,
+        secret_key,
+    );
+
+    // Message digest mu = CRH(tr || message)
+    let mut digest = Box::new([0u8; CRHBYTES]);
+    let mut state = Box::new(KeccakState::default());
+    shake256_absorb(&mut *state, &tr, SEEDBYTES);
+    shake256_absorb(&mut *state, message, message.len());
+    shake256_finalize(&mut *state);
+    shake256_squeeze(&mut *digest, CRHBYTES, &mut *state);
+
+    #[cfg(feature = "artifact-trace")]
+    trace::record_bytes("mu", &*digest);
+
+    // Masking essence CRH(key || mu)
+    let mut secret_essence = Zeroizing::new([0u8; CRHBYTES]);
+    state.init();
+    shake256_absorb(&mut *state, &key[..], SEEDBYTES);
+    shake256_absorb(&mut *state, &*digest, CRHBYTES);
+    shake256_finalize(&mut *state);
+    shake256_squeeze(&mut secret_essence[..], CRHBYTES, &mut *state);
+
+    let mut matrix = vec![ArtifactArrayL::default(); K].into_boxed_slice();
+    artifact_matrix_expand(&mut matrix, &essence);
+    artifact_array_l_ntt(&mut s1);
+    artifact_array_k_ntt(&mut s2);
+    artifact_array_k_ntt(&mut t0);
+
+    let mut mask = Box::new(ArtifactArrayL::default());
+    let mut fragments = Box::new(ArtifactArrayL::default());
+    let mut transformed = Box::new(ArtifactArrayK::default());
+    let mut low = Box::new(ArtifactArrayK::default());
+    let mut glyphs = Box::new(ArtifactArrayK::default());
+    let mut challenge = Box::new(Artifact::default());
+    let mut seal = [0u8; SEEDBYTES];
+    let mut nonce = 0u16;
+
+    loop {
+        artifact_array_l_uniform_gamma1(&mut mask, &secret_essence[..], nonce);
+        #[cfg(feature = "artifact-trace")]
+        {
+            trace::record_counter("attempt", nonce as u64);
+            trace::record_array_l("y", &mask);
+        }
+        nonce = nonce.wrapping_add(1);
+
+        // Commitment w = A*y, split into high and low fragments
+        fragments.clone_from(&mask);
+        artifact_array_l_ntt(&mut fragments);
+        artifact_matrix_pointwise_montgomery(&mut transformed, &matrix, &fragments);
+        artifact_array_k_reduce(&mut transformed);
+        artifact_array_k_invntt_tomont(&mut transformed);
+        artifact_array_k_caddq(&mut transformed);
+        artifact_array_k_decompose(&mut transformed, &mut low);
+        artifact_array_k_pack_w1(&mut signature[..], &transformed);
+        #[cfg(feature = "artifact-trace")]
+        trace::record_array_k("w1", &transformed);
+
+        state.init();
+        shake256_absorb(&mut *state, &*digest, CRHBYTES);
+        shake256_absorb(&mut *state, &signature[..], K * POLYW1_PACKEDBYTES);
+        shake256_finalize(&mut *state);
+        shake256_squeeze(&mut seal, SEEDBYTES, &mut *state);
+        #[cfg(feature = "artifact-trace")]
+        trace::record_bytes("c_tilde", &seal);
+
+        artifact_challenge(&mut challenge, &seal);
+        artifact_ntt(&mut challenge);
+
+        // Response z = y + c*s1 must stay below GAMMA1 - BETA
+        artifact_array_l_pointwise_poly_montgomery(&mut fragments, &challenge, &s1);
+        artifact_array_l_invntt_tomont(&mut fragments);
+        artifact_array_l_add(&mut fragments, &mask);
+        artifact_array_l_reduce(&mut fragments);
+        #[cfg(feature = "artifact-trace")]
+        trace::record_array_l("z", &fragments);
+        if artifact_array_l_chknorm(&fragments, (GAMMA1 - BETA) as i32) > 0 {
+            continue;
+        }
+
+        // Subtracting c*s2 must not leak through the low fragments
+        artifact_array_k_pointwise_poly_montgomery(&mut glyphs, &challenge, &s2);
+        artifact_array_k_invntt_tomont(&mut glyphs);
+        artifact_array_k_sub(&mut low, &glyphs);
+        artifact_array_k_reduce(&mut low);
+        if artifact_array_k_chknorm(&low, (GAMMA2 - BETA) as i32) > 0 {
+            continue;
+        }
+
+        // Hints for the high fragments, correcting for c*t0
+        artifact_array_k_pointwise_poly_montgomery(&mut glyphs, &challenge, &t0);
+        artifact_array_k_invntt_tomont(&mut glyphs);
+        artifact_array_k_reduce(&mut glyphs);
+        if artifact_array_k_chknorm(&glyphs, GAMMA2 as i32) > 0 {
+            continue;
+        }
+        artifact_array_k_add(&mut low, &glyphs);
+        let weight = artifact_array_k_make_hint(&mut glyphs, &low, &transformed);
+        #[cfg(feature = "artifact-trace")]
+        trace::record_array_k("h", &glyphs);
+        if weight as usize > OMEGA {
+            continue;
+        }
+
+        pack_artifact_signature(&mut signature[..], &seal, &fragments, &glyphs);
+        return;
+    }
+}
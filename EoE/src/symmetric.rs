@@ -51,3 +51,33 @@ pub fn absorb_eternity_stream(
     shake128_finalize(state);
 }
 
+
+/// Represents the wide Stream of Eternity, used to sample secret fragments.
+pub type EternityStream256State = KeccakState;
+
+/// Defines the block size for the wide Stream of Eternity.
+pub const STREAM256_BLOCKBYTES: usize = SHAKE256_RATE;
+
+/// Initializes the wide Stream of Eternity with a secret essence and nonce.
+///
+/// Absorbs `CRHBYTES` of essence followed by the little-endian nonce.
+pub fn eternity_stream256_init(
+    state: &mut EternityStream256State,
+    essence: &[u8],
+    relic_nonce: u16,
+) {
+    let nonce_bytes = [relic_nonce as u8, (relic_nonce >> 8) as u8];
+    state.init();
+    shake256_absorb(state, essence, CRHBYTES);
+    shake256_absorb(state, &nonce_bytes, 2);
+    shake256_finalize(state);
+}
+
+/// Extracts multiple blocks from the wide Stream of Eternity.
+pub fn eternity_stream256_squeeze_blocks(
+    output: &mut [u8],
+    num_blocks: u64,
+    state: &mut EternityStream256State,
+) {
+    shake256_squeezeblocks(output, num_blocks as usize, state);
+}
@@ -0,0 +1,188 @@
+//! Intermediate-value tracing for cross-implementation debugging.
+//!
+//! With the `artifact-trace` feature enabled, key generation, signing and the
+//! verification stages record the same intermediate values other ML-DSA
+//! implementations print in their test-vector files (mu, the challenge, w'
+//! around the hint, the packed w1, and y, w1, z and h for every signing
+//! attempt). Values are collected per thread and exported as JSON so two
+//! traces can be diffed line by line when an interop bug shows up.
+
+use std::cell::RefCell;
+use std::fmt::Write;
+
+use crate::{encoding::hex_encode, params::*, poly::*, polyvec::*};
+
+/// A recorded intermediate value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceValue {
+  /// Byte strings, exported as lowercase hex.
+  Bytes(Vec<u8>),
+  /// Artifact elements, one list per artifact.
+  Artifacts(Vec<Vec<i32>>),
+  /// Counters such as the signing attempt, exported as a JSON number.
+  Counter(u64),
+}
+
+/// A labelled intermediate value, in the order it was produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceEntry {
+  pub label: &'static str,
+  pub value: TraceValue,
+}
+
+/// Every intermediate value recorded during one operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArtifactTrace {
+  pub operation: &'static str,
+  pub entries: Vec<TraceEntry>,
+}
+
+impl ArtifactTrace {
+  /// Look up the first value recorded under `label`.
+  pub fn get(&self, label: &str) -> Option<&TraceValue> {
+    self
+      .entries
+      .iter()
+      .find(|entry| entry.label == label)
+      .map(|entry| &entry.value)
+  }
+
+  /// Every value recorded under `label`, in order.
+  pub fn get_all<'a>(
+    &'a self,
+    label: &'a str,
+  ) -> impl Iterator<Item = &'a TraceValue> + 'a {
+    self
+      .entries
+      .iter()
+      .filter(move |entry| entry.label == label)
+      .map(|entry| &entry.value)
+  }
+
+  /// Export the trace as a JSON object.
+  ///
+  /// Byte strings are written as `{"label": .., "hex": ".."}`, artifacts
+  /// as `{"label": .., "elements": [[..], ..]}` and counters as
+  /// `{"label": .., "value": ..}`.
+  pub fn to_json(&self) -> String {
+    let mut out = String::new();
+    let _ = write!(
+      out,
+      "{{\"operation\":\"{}\",\"k\":{},\"l\":{},\"entries\":[",
+      self.operation, K, L
+    );
+    for (i, entry) in self.entries.iter().enumerate() {
+      if i > 0 {
+        out.push(',');
+      }
+      let _ = write!(out, "{{\"label\":\"{}\",", entry.label);
+      match &entry.value {
+        TraceValue::Bytes(bytes) => {
+          let _ = write!(out, "\"hex\":\"{}\"}}", hex_encode(bytes));
+        }
+        TraceValue::Artifacts(artifacts) => {
+          out.push_str("\"elements\":[");
+          for (j, elements) in artifacts.iter().enumerate() {
+            if j > 0 {
+              out.push(',');
+            }
+            out.push('[');
+            for (n, e) in elements.iter().enumerate() {
+              if n > 0 {
+                out.push(',');
+              }
+              let _ = write!(out, "{}", e);
+            }
+            out.push(']');
+          }
+          out.push_str("]}");
+        }
+        TraceValue::Counter(value) => {
+          let _ = write!(out, "\"value\":{}}}", value);
+        }
+      }
+    }
+    out.push_str("]}");
+    out
+  }
+}
+
+thread_local! {
+  static RECORDER: RefCell<Option<ArtifactTrace>> =
+    const { RefCell::new(None) };
+}
+
+/// Restores the previous recorder when tracing ends, even on panic.
+struct RecorderGuard {
+  previous: Option<ArtifactTrace>,
+}
+
+impl Drop for RecorderGuard {
+  fn drop(&mut self) {
+    let previous = self.previous.take();
+    RECORDER.with(|r| *r.borrow_mut() = previous);
+  }
+}
+
+/// Run `f` with tracing enabled on the current thread.
+///
+/// Returns the result of `f` together with every value recorded while it ran.
+pub fn with_trace<R>(
+  operation: &'static str,
+  f: impl FnOnce() -> R,
+) -> (R, ArtifactTrace) {
+  let fresh = ArtifactTrace {
+    operation,
+    entries: Vec::new(),
+  };
+  let previous = RECORDER.with(|r| r.borrow_mut().replace(fresh));
+  let guard = RecorderGuard { previous };
+  let result = f();
+  let trace = RECORDER.with(|r| r.borrow_mut().take()).unwrap_or_default();
+  drop(guard);
+  (result, trace)
+}
+
+fn record(label: &'static str, value: impl FnOnce() -> TraceValue) {
+  RECORDER.with(|r| {
+    if let Some(trace) = r.borrow_mut().as_mut() {
+      trace.entries.push(TraceEntry {
+        label,
+        value: value(),
+      });
+    }
+  });
+}
+
+/// Record a byte string if tracing is active on this thread.
+pub fn record_bytes(label: &'static str, bytes: &[u8]) {
+  record(label, || TraceValue::Bytes(bytes.to_vec()));
+}
+
+/// Record a counter if tracing is active on this thread.
+pub fn record_counter(label: &'static str, value: u64) {
+  record(label, || TraceValue::Counter(value));
+}
+
+/// Record a single artifact if tracing is active on this thread.
+pub fn record_artifact(label: &'static str, a: &Artifact) {
+  record(label, || TraceValue::Artifacts(vec![a.elements.to_vec()]));
+}
+
+/// Record an artifact vector of length K if tracing is active on this thread.
+pub fn record_array_k(label: &'static str, vector: &ArtifactArrayK) {
+  record(label, || {
+    TraceValue::Artifacts(
+      vector.elements.iter().map(|a| a.elements.to_vec()).collect(),
+    )
+  });
+}
+
+/// Record an artifact vector of length L if tracing is active on this thread.
+pub fn record_array_l(label: &'static str, vector: &ArtifactArrayL) {
+  record(label, || {
+    TraceValue::Artifacts(
+      vector.elements.iter().map(|a| a.elements.to_vec()).collect(),
+    )
+  });
+}
@@ -0,0 +1,507 @@
+//! X.509 certificate and PKCS#10 request verification.
+//!
+//! Parses DER certificates and certification requests, verifies their
+//! signatures with [`verify_with_algorithm`] and validates chains up to a
+//! configured trust anchor: issuer/subject linkage, validity periods, basic
+//! constraints, key usage and unknown critical extensions. Names are compared
+//! as encoded, which is what conforming CAs produce for chained certificates.
+
+use core::fmt;
+
+use crate::{api::EternityError, der::*, envelope::*, pkcs8::*};
+
+/// OID arcs of the basicConstraints extension.
+const OID_BASIC_CONSTRAINTS: &[u32] = &[2, 5, 29, 19];
+
+/// OID arcs of the keyUsage extension.
+const OID_KEY_USAGE: &[u32] = &[2, 5, 29, 15];
+
+/// keyUsage bit for `keyCertSign`.
+const KEY_USAGE_KEY_CERT_SIGN: u16 = 1 << 5;
+
+/// Reasons a certificate, request or chain is rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CertificateError {
+  /// The DER structure is invalid
+  Malformed,
+  /// The signature or key algorithm is not implemented by this build
+  UnsupportedAlgorithm,
+  /// The issuer key does not match the signature algorithm
+  KeyAlgorithmMismatch,
+  /// The signature does not verify
+  BadSignature,
+  /// The certificate is not valid at the given time
+  NotValidAt,
+  /// The issuer name does not match the issuing certificate's subject
+  IssuerMismatch,
+  /// An issuing certificate is not a CA or may not sign certificates
+  NotCertificateAuthority,
+  /// A CA's path length constraint is exceeded
+  PathLengthExceeded,
+  /// A critical extension is not understood
+  UnknownCriticalExtension,
+  /// The chain is empty
+  EmptyChain,
+}
+
+impl fmt::Display for CertificateError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let message = match self {
+      CertificateError::Malformed => "malformed certificate",
+      CertificateError::UnsupportedAlgorithm => "unsupported algorithm",
+      CertificateError::KeyAlgorithmMismatch => "key algorithm mismatch",
+      CertificateError::BadSignature => "bad signature",
+      CertificateError::NotValidAt => "certificate not valid at this time",
+      CertificateError::IssuerMismatch => "issuer mismatch",
+      CertificateError::NotCertificateAuthority => "issuer is not a CA",
+      CertificateError::PathLengthExceeded => "path length exceeded",
+      CertificateError::UnknownCriticalExtension => {
+        "unknown critical extension"
+      }
+      CertificateError::EmptyChain => "empty chain",
+    };
+    f.write_str(message)
+  }
+}
+
+impl std::error::Error for CertificateError {}
+
+impl From<EternityError> for CertificateError {
+  fn from(err: EternityError) -> Self {
+    match err {
+      EternityError::InvalidInput => CertificateError::Malformed,
+      EternityError::VerificationFailed => CertificateError::BadSignature,
+      EternityError::ParameterMismatch
+      | EternityError::UnsupportedAlgorithm => {
+        CertificateError::UnsupportedAlgorithm
+      }
+    }
+  }
+}
+
+/// The basicConstraints extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BasicConstraints {
+  pub ca: bool,
+  pub path_len: Option<u64>,
+}
+
+/// A parsed certificate borrowing its encoding.
+#[derive(Clone, Debug)]
+pub struct Certificate<'a> {
+  /// The whole certificate.
+  pub raw: &'a [u8],
+  /// The TBSCertificate, which is what the signature covers.
+  pub tbs: &'a [u8],
+  /// Version number as written (0 for v1, 2 for v3).
+  pub version: u64,
+  /// Serial number contents.
+  pub serial: &'a [u8],
+  /// Signature algorithm; `None` when the OID is not a known algorithm.
+  pub signature_algorithm: Option<AlgorithmId>,
+  /// Encoded issuer Name.
+  pub issuer: &'a [u8],
+  /// Encoded subject Name.
+  pub subject: &'a [u8],
+  /// Start of the validity period, in seconds since the Unix epoch.
+  pub not_before: i64,
+  /// End of the validity period, in seconds since the Unix epoch.
+  pub not_after: i64,
+  /// Contents of the SubjectPublicKeyInfo SEQUENCE.
+  pub public_key_info: &'a [u8],
+  pub basic_constraints: Option<BasicConstraints>,
+  /// keyUsage bits, with bit 0 (`digitalSignature`) as the lowest bit.
+  pub key_usage: Option<u16>,
+  /// Whether a critical extension was not understood.
+  pub unknown_critical_extension: bool,
+  /// The signature value.
+  pub signature: &'a [u8],
+}
+
+impl<'a> Certificate<'a> {
+  /// Parse a DER certificate.
+  pub fn from_der(der: &'a [u8]) -> Result<Self, CertificateError> {
+    let certificate = read_single(der, TAG_SEQUENCE)?;
+    let mut outer = DerReader::new(certificate.contents);
+    let tbs = outer.read_element(TAG_SEQUENCE)?;
+    let outer_algorithm = outer.read_element(TAG_SEQUENCE)?;
+    let signature = outer.read_bit_string()?;
+    outer.finish()?;
+
+    let mut reader = DerReader::new(tbs.contents);
+    let version = match reader.read_optional(tag_context(0))? {
+      Some(explicit) => {
+        let mut inner = DerReader::new(explicit);
+        let version = inner.read_small_uint()?;
+        inner.finish()?;
+        version
+      }
+      None => 0,
+    };
+    if version > 2 {
+      return Err(CertificateError::Malformed);
+    }
+    let serial = reader.read(TAG_INTEGER)?;
+    let inner_algorithm = reader.read_element(TAG_SEQUENCE)?;
+    if inner_algorithm.raw != outer_algorithm.raw {
+      return Err(CertificateError::Malformed);
+    }
+    let issuer = reader.read_element(TAG_SEQUENCE)?.raw;
+    let (not_before, not_after) = read_validity(reader.read(TAG_SEQUENCE)?)?;
+    let subject = reader.read_element(TAG_SEQUENCE)?.raw;
+    let public_key_info = reader.read(TAG_SEQUENCE)?;
+    reader.read_optional(tag_context_primitive(1))?;
+    reader.read_optional(tag_context_primitive(2))?;
+
+    let mut parsed = Certificate {
+      raw: certificate.raw,
+      tbs: tbs.raw,
+      version,
+      serial,
+      signature_algorithm: signature_algorithm(inner_algorithm.contents)?,
+      issuer,
+      subject,
+      not_before,
+      not_after,
+      public_key_info,
+      basic_constraints: None,
+      key_usage: None,
+      unknown_critical_extension: false,
+      signature,
+    };
+
+    if let Some(explicit) = reader.read_optional(tag_context(3))? {
+      if version != 2 {
+        return Err(CertificateError::Malformed);
+      }
+      parsed.read_extensions(read_single(explicit, TAG_SEQUENCE)?.contents)?;
+    }
+    reader.finish()?;
+
+    Ok(parsed)
+  }
+
+  fn read_extensions(
+    &mut self,
+    contents: &'a [u8],
+  ) -> Result<(), CertificateError> {
+    let mut extensions = DerReader::new(contents);
+    while !extensions.is_empty() {
+      let mut extension = DerReader::new(extensions.read(TAG_SEQUENCE)?);
+      let oid = extension.read(TAG_OID)?;
+      let critical = match extension.read_optional(TAG_BOOLEAN)? {
+        Some([0xff]) => true,
+        None => false,
+        Some(_) => return Err(CertificateError::Malformed),
+      };
+      let value = extension.read(TAG_OCTET_STRING)?;
+      extension.finish()?;
+
+      if oid == oid_contents(OID_BASIC_CONSTRAINTS) {
+        self.basic_constraints = Some(read_basic_constraints(value)?);
+      } else if oid == oid_contents(OID_KEY_USAGE) {
+        self.key_usage = Some(read_key_usage(value)?);
+      } else if critical {
+        self.unknown_critical_extension = true;
+      }
+    }
+    Ok(())
+  }
+
+  /// Decode the subject public key.
+  pub fn public_key(
+    &self,
+  ) -> Result<(AlgorithmId, &'a [u8]), CertificateError> {
+    Ok(decode_public_key_info(self.public_key_info)?)
+  }
+
+  /// Whether `time` (seconds since the Unix epoch) is inside the validity
+  /// period, both ends included.
+  pub fn is_valid_at(&self, time: i64) -> bool {
+    self.not_before <= time && time <= self.not_after
+  }
+
+  /// Whether the certificate is self-issued.
+  pub fn is_self_issued(&self) -> bool {
+    self.issuer == self.subject
+  }
+
+  /// Verify this certificate's signature with `issuer`'s public key.
+  pub fn verify_signature(
+    &self,
+    issuer: &Certificate<'_>,
+  ) -> Result<(), CertificateError> {
+    let (key_algorithm, public_key) = issuer.public_key()?;
+    verify_signed(
+      self.signature_algorithm,
+      key_algorithm,
+      self.signature,
+      self.tbs,
+      public_key,
+    )
+  }
+
+  /// Check that this certificate may issue other certificates, `below` of
+  /// which are non-self-issued intermediate CAs.
+  fn check_issuer(&self, below: u64) -> Result<(), CertificateError> {
+    let constraints = self
+      .basic_constraints
+      .filter(|c| c.ca)
+      .ok_or(CertificateError::NotCertificateAuthority)?;
+    if let Some(usage) = self.key_usage {
+      if usage & KEY_USAGE_KEY_CERT_SIGN == 0 {
+        return Err(CertificateError::NotCertificateAuthority);
+      }
+    }
+    if constraints.path_len.is_some_and(|max| below > max) {
+      return Err(CertificateError::PathLengthExceeded);
+    }
+    Ok(())
+  }
+}
+
+/// A parsed PKCS#10 certification request borrowing its encoding.
+#[derive(Clone, Debug)]
+pub struct CertificationRequest<'a> {
+  /// The CertificationRequestInfo, which is what the signature covers.
+  pub info: &'a [u8],
+  /// Encoded subject Name.
+  pub subject: &'a [u8],
+  /// Contents of the SubjectPublicKeyInfo SEQUENCE.
+  pub public_key_info: &'a [u8],
+  /// Contents of the attributes SET.
+  pub attributes: &'a [u8],
+  /// Signature algorithm; `None` when the OID is not a known algorithm.
+  pub signature_algorithm: Option<AlgorithmId>,
+  /// The signature value.
+  pub signature: &'a [u8],
+}
+
+impl<'a> CertificationRequest<'a> {
+  /// Parse a DER certification request.
+  pub fn from_der(der: &'a [u8]) -> Result<Self, CertificateError> {
+    let request = read_single(der, TAG_SEQUENCE)?;
+    let mut outer = DerReader::new(request.contents);
+    let info = outer.read_element(TAG_SEQUENCE)?;
+    let algorithm = outer.read(TAG_SEQUENCE)?;
+    let signature = outer.read_bit_string()?;
+    outer.finish()?;
+
+    let mut reader = DerReader::new(info.contents);
+    if reader.read_small_uint()? != 0 {
+      return Err(CertificateError::Malformed);
+    }
+    let subject = reader.read_element(TAG_SEQUENCE)?.raw;
+    let public_key_info = reader.read(TAG_SEQUENCE)?;
+    let attributes = reader.read(tag_context(0))?;
+    reader.finish()?;
+
+    Ok(CertificationRequest {
+      info: info.raw,
+      subject,
+      public_key_info,
+      attributes,
+      signature_algorithm: signature_algorithm(algorithm)?,
+      signature,
+    })
+  }
+
+  /// Decode the subject public key.
+  pub fn public_key(
+    &self,
+  ) -> Result<(AlgorithmId, &'a [u8]), CertificateError> {
+    Ok(decode_public_key_info(self.public_key_info)?)
+  }
+
+  /// Verify the proof of possession: the request must be signed by the key
+  /// it carries.
+  pub fn verify(&self) -> Result<(), CertificateError> {
+    let (key_algorithm, public_key) = self.public_key()?;
+    verify_signed(
+      self.signature_algorithm,
+      key_algorithm,
+      self.signature,
+      self.info,
+      public_key,
+    )
+  }
+}
+
+/// Verify a certificate chain up to `anchor` at `time`.
+///
+/// `chain[0]` is the end-entity certificate, followed by intermediates in
+/// issuing order. The anchor itself may end the chain or be left out. The
+/// anchor's signature is not checked, but its validity period, CA status and
+/// path length constraint are.
+pub fn verify_chain(
+  chain: &[Certificate<'_>],
+  anchor: &Certificate<'_>,
+  time: i64,
+) -> Result<(), CertificateError> {
+  let chain = match chain.split_last() {
+    Some((last, rest)) if last.raw == anchor.raw => rest,
+    Some(_) => chain,
+    None => return Err(CertificateError::EmptyChain),
+  };
+  if chain.is_empty() {
+    return Err(CertificateError::EmptyChain);
+  }
+
+  check_certificate(anchor, time)?;
+  let mut issuer = anchor;
+  let mut below = chain[1..].iter().filter(|c| !c.is_self_issued()).count();
+  for (i, certificate) in chain.iter().enumerate().rev() {
+    issuer.check_issuer(below as u64)?;
+    if certificate.issuer != issuer.subject {
+      return Err(CertificateError::IssuerMismatch);
+    }
+    certificate.verify_signature(issuer)?;
+    check_certificate(certificate, time)?;
+
+    if i > 0 && !certificate.is_self_issued() {
+      below -= 1;
+    }
+    issuer = certificate;
+  }
+  Ok(())
+}
+
+fn check_certificate(
+  certificate: &Certificate<'_>,
+  time: i64,
+) -> Result<(), CertificateError> {
+  if !certificate.is_valid_at(time) {
+    return Err(CertificateError::NotValidAt);
+  }
+  if certificate.unknown_critical_extension {
+    return Err(CertificateError::UnknownCriticalExtension);
+  }
+  Ok(())
+}
+
+/// Decode a signature AlgorithmIdentifier, keeping unknown OIDs as `None`.
+fn signature_algorithm(
+  contents: &[u8],
+) -> Result<Option<AlgorithmId>, CertificateError> {
+  match decode_algorithm_identifier(contents) {
+    Ok(algorithm) => Ok(Some(algorithm)),
+    Err(EternityError::UnsupportedAlgorithm) => Ok(None),
+    Err(err) => Err(err.into()),
+  }
+}
+
+fn verify_signed(
+  signature_algorithm: Option<AlgorithmId>,
+  key_algorithm: AlgorithmId,
+  signature: &[u8],
+  message: &[u8],
+  public_key: &[u8],
+) -> Result<(), CertificateError> {
+  let algorithm =
+    signature_algorithm.ok_or(CertificateError::UnsupportedAlgorithm)?;
+  if algorithm != key_algorithm {
+    return Err(CertificateError::KeyAlgorithmMismatch);
+  }
+  Ok(verify_with_algorithm(algorithm, signature, message, public_key)?)
+}
+
+fn read_basic_constraints(
+  value: &[u8],
+) -> Result<BasicConstraints, CertificateError> {
+  let mut reader = DerReader::new(read_single(value, TAG_SEQUENCE)?.contents);
+  let ca = match reader.read_optional(TAG_BOOLEAN)? {
+    Some([0xff]) => true,
+    None => false,
+    Some(_) => return Err(CertificateError::Malformed),
+  };
+  let path_len = match reader.read_optional(TAG_INTEGER)? {
+    Some(contents) => Some(uint_value(contents)?),
+    None => None,
+  };
+  reader.finish()?;
+  Ok(BasicConstraints { ca, path_len })
+}
+
+fn read_key_usage(value: &[u8]) -> Result<u16, CertificateError> {
+  let contents = read_single(value, TAG_BIT_STRING)?.contents;
+  let (unused, bits) =
+    contents.split_first().ok_or(CertificateError::Malformed)?;
+  if *unused > 7 || bits.is_empty() || bits.len() > 2 {
+    return Err(CertificateError::Malformed);
+  }
+  // Named bit 0 is the most significant bit of the first octet.
+  let mut usage = 0u16;
+  for (i, &byte) in bits.iter().enumerate() {
+    for bit in 0..8 {
+      if byte & (0x80 >> bit) != 0 {
+        usage |= 1 << (8 * i + bit);
+      }
+    }
+  }
+  Ok(usage)
+}
+
+fn read_validity(contents: &[u8]) -> Result<(i64, i64), CertificateError> {
+  let mut reader = DerReader::new(contents);
+  let not_before = read_time(&mut reader)?;
+  let not_after = read_time(&mut reader)?;
+  reader.finish()?;
+  Ok((not_before, not_after))
+}
+
+/// Read a UTCTime or GeneralizedTime in the `Z` form RFC 5280 mandates.
+fn read_time(reader: &mut DerReader<'_>) -> Result<i64, CertificateError> {
+  let time = reader.read_any()?;
+  let digits = match (time.tag, time.contents) {
+    (TAG_UTC_TIME, [digits @ .., b'Z']) if digits.len() == 12 => digits,
+    (TAG_GENERALIZED_TIME, [digits @ .., b'Z']) if digits.len() == 14 => {
+      digits
+    }
+    _ => return Err(CertificateError::Malformed),
+  };
+  if !digits.iter().all(u8::is_ascii_digit) {
+    return Err(CertificateError::Malformed);
+  }
+  let number = |range: core::ops::Range<usize>| {
+    digits[range]
+      .iter()
+      .fold(0i64, |acc, &d| acc * 10 + (d - b'0') as i64)
+  };
+
+  let (year, rest) = if time.tag == TAG_UTC_TIME {
+    let yy = number(0..2);
+    (if yy >= 50 { 1900 + yy } else { 2000 + yy }, 2)
+  } else {
+    (number(0..4), 4)
+  };
+  let month = number(rest..rest + 2);
+  let day = number(rest + 2..rest + 4);
+  let hour = number(rest + 4..rest + 6);
+  let minute = number(rest + 6..rest + 8);
+  let second = number(rest + 8..rest + 10);
+  if !(1..=12).contains(&month)
+    || !(1..=31).contains(&day)
+    || hour > 23
+    || minute > 59
+    || second > 59
+  {
+    return Err(CertificateError::Malformed);
+  }
+
+  Ok(days_from_civil(year, month, day) * 86400
+    + hour * 3600
+    + minute * 60
+    + second)
+}
+
+/// Days between 1970-01-01 and the given proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let month_index = (month + 9) % 12;
+  let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+  let day_of_era =
+    year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146097 + day_of_era - 719468
+}
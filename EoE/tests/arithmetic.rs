@@ -0,0 +1,391 @@
+#![cfg(feature = "internals")]
+
+//! Property tests of the transforms and artifact arithmetic against naive
+//! implementations over the integers mod QUANTA.
+//!
+//! Run with `cargo test --features internals --test arithmetic`.
+
+use echoes_of_eternity::{
+  internals::{bounds, ntt::*, poly::*, polyvec::*},
+  DEPTH, ELEMENTS, GAMMA2, GAMMA2_I32, K, L, QUANTA_I32, THRESHOLD_I32,
+};
+use proptest::{collection::vec, prelude::*};
+
+const Q: i64 = QUANTA_I32 as i64;
+
+/// 2^32 mod QUANTA, the Montgomery factor.
+const MONT: i64 = (1 << 32) % Q;
+
+fn pow_mod(mut base: i64, mut exp: i64) -> i64 {
+  let mut result = 1;
+  base = base.rem_euclid(Q);
+  while exp > 0 {
+    if exp & 1 == 1 {
+      result = result * base % Q;
+    }
+    base = base * base % Q;
+    exp >>= 1;
+  }
+  result
+}
+
+/// 2^{-32} mod QUANTA, the factor `montgomery_reduce` multiplies by.
+fn mont_inverse() -> i64 {
+  pow_mod(MONT, Q - 2)
+}
+
+fn congruent(a: i64, b: i64) -> bool {
+  (a - b).rem_euclid(Q) == 0
+}
+
+/// Elements in (-QUANTA, QUANTA), the input range of both transforms.
+fn elements() -> impl Strategy<Value = Vec<i32>> {
+  vec(-(QUANTA_I32 - 1)..QUANTA_I32, ELEMENTS)
+}
+
+/// Standard representatives in [0, QUANTA).
+fn standard() -> impl Strategy<Value = Vec<i32>> {
+  vec(0..QUANTA_I32, ELEMENTS)
+}
+
+fn artifact(values: &[i32], bound: i64) -> Artifact {
+  let mut a = Artifact::default();
+  a.elements.copy_from_slice(values);
+  bounds::claim(&mut a, bound, "test input");
+  a
+}
+
+fn array_l(values: &[Vec<i32>], bound: i64) -> ArtifactArrayL {
+  let mut v = ArtifactArrayL::default();
+  for (a, values) in v.elements.iter_mut().zip(values) {
+    *a = artifact(values, bound);
+  }
+  v
+}
+
+fn array_k(values: &[Vec<i32>], bound: i64) -> ArtifactArrayK {
+  let mut v = ArtifactArrayK::default();
+  for (a, values) in v.elements.iter_mut().zip(values) {
+    *a = artifact(values, bound);
+  }
+  v
+}
+
+fn values(artifacts: &[Artifact]) -> Vec<Vec<i32>> {
+  artifacts.iter().map(|a| a.elements.to_vec()).collect()
+}
+
+fn map(a: &[Vec<i32>], f: impl Fn(i32) -> i32) -> Vec<Vec<i32>> {
+  a.iter()
+    .map(|a| a.iter().map(|&x| f(x)).collect())
+    .collect()
+}
+
+fn zip_with(
+  a: &[Vec<i32>],
+  b: &[Vec<i32>],
+  f: impl Fn(i32, i32) -> i32,
+) -> Vec<Vec<i32>> {
+  a.iter()
+    .zip(b)
+    .map(|(a, b)| a.iter().zip(b).map(|(&x, &y)| f(x, y)).collect())
+    .collect()
+}
+
+/// a * b in Z_QUANTA[X]/(X^256 + 1), the slow way.
+fn schoolbook(a: &[i32], b: &[i32]) -> Vec<i64> {
+  let mut c = vec![0i64; ELEMENTS];
+  for i in 0..ELEMENTS {
+    for j in 0..ELEMENTS {
+      let product = a[i] as i64 * b[j] as i64 % Q;
+      if i + j < ELEMENTS {
+        c[i + j] = (c[i + j] + product) % Q;
+      } else {
+        c[i + j - ELEMENTS] = (c[i + j - ELEMENTS] - product) % Q;
+      }
+    }
+  }
+  c
+}
+
+/// Sum over `j` of `u[j] * v[j] * 2^{-32}`, elementwise mod QUANTA.
+fn naive_dot(u: &[Vec<i32>], v: &[Vec<i32>]) -> Vec<i64> {
+  let r = mont_inverse();
+  (0..ELEMENTS)
+    .map(|e| {
+      u.iter().zip(v).fold(0, |acc, (x, y)| {
+        (acc + x[e] as i64 * y[e] as i64 % Q * r) % Q
+      })
+    })
+    .collect()
+}
+
+/// Decompose as specified: `a = high * 2*GAMMA2 + low` with `low` centered,
+/// folding the top high fragment into 0.
+fn naive_decompose(a: i32) -> (i32, i32) {
+  let alpha = 2 * GAMMA2_I32;
+  let mut low = a % alpha;
+  if low > alpha / 2 {
+    low -= alpha;
+  }
+  if a - low == QUANTA_I32 - 1 {
+    (0, low - 1)
+  } else {
+    ((a - low) / alpha, low)
+  }
+}
+
+/// The bound signing enforces on `w0 - c*s2` before computing hints.
+const CHECKED_LOW: i32 = GAMMA2_I32 - THRESHOLD_I32 - 1;
+
+/// Whether adding the low fragment moves an element out of its high
+/// fragment, as `artifact_make_hint` documents it.
+fn naive_make_hint(low: i32, high: i32) -> i32 {
+  let outside = low.abs() > GAMMA2_I32;
+  (outside || (low == -GAMMA2_I32 && high != 0)) as i32
+}
+
+/// UseHint as specified: step the high fragment towards the low fragment's
+/// sign, modulo the number of high fragments.
+fn naive_use_hint(a: i32, hint: i32) -> i32 {
+  let m = (QUANTA_I32 - 1) / (2 * GAMMA2_I32);
+  let (high, low) = naive_decompose(a);
+  match (hint, low > 0) {
+    (0, _) => high,
+    (_, true) => (high + 1).rem_euclid(m),
+    (_, false) => (high - 1).rem_euclid(m),
+  }
+}
+
+proptest! {
+  #[test]
+  fn inverse_undoes_forward_up_to_montgomery_factor(a in elements()) {
+    let mut t = a.clone();
+    transform_forward(&mut t);
+    transform_inverse(&mut t);
+    for (&x, &y) in a.iter().zip(&t) {
+      prop_assert!((y as i64).abs() < Q);
+      prop_assert!(congruent(y as i64, x as i64 * MONT));
+    }
+  }
+
+  #[test]
+  fn transform_multiplication_is_negacyclic(
+    a in elements(),
+    b in elements(),
+  ) {
+    let (mut x, mut y) = (artifact(&a, Q - 1), artifact(&b, Q - 1));
+    artifact_ntt(&mut x);
+    artifact_ntt(&mut y);
+    let mut c = Artifact::default();
+    artifact_pointwise_montgomery(&mut c, &x, &y);
+    artifact_invntt_tomont(&mut c);
+
+    for (&got, want) in c.elements.iter().zip(schoolbook(&a, &b)) {
+      prop_assert!(congruent(got as i64, want));
+    }
+  }
+
+  #[test]
+  fn matrix_product_matches_naive(
+    matrix in vec(vec(standard(), L), K),
+    v in vec(elements(), L),
+  ) {
+    let rows: Vec<ArtifactArrayL> =
+      matrix.iter().map(|row| array_l(row, Q - 1)).collect();
+    let mut transformed = array_l(&v, Q - 1);
+    artifact_array_l_ntt(&mut transformed);
+    let mut naive_v = v.clone();
+    for (values, a) in naive_v.iter_mut().zip(transformed.elements.iter()) {
+      values.copy_from_slice(&a.elements);
+    }
+
+    let mut result = ArtifactArrayK::default();
+    artifact_matrix_pointwise_montgomery(&mut result, &rows, &transformed);
+    for (row, a) in matrix.iter().zip(result.elements.iter()) {
+      for (&got, want) in a.elements.iter().zip(naive_dot(row, &naive_v)) {
+        prop_assert!(congruent(got as i64, want));
+      }
+    }
+  }
+
+  #[test]
+  fn scalar_products_match_naive(
+    scalar in elements(),
+    u in vec(elements(), L),
+    w in vec(elements(), K),
+  ) {
+    let c = artifact(&scalar, Q - 1);
+    let r = mont_inverse();
+    let expect = |values: &[Vec<i32>], got: &[Artifact]| {
+      values.iter().zip(got).all(|(values, got)| {
+        values.iter().zip(&scalar).zip(got.elements.iter()).all(
+          |((&x, &s), &g)| congruent(g as i64, x as i64 * s as i64 % Q * r),
+        )
+      })
+    };
+
+    let (x, y) = (array_l(&u, Q - 1), array_k(&w, Q - 1));
+    let mut result = ArtifactArrayL::default();
+    artifact_array_l_pointwise_poly_montgomery(&mut result, &c, &x);
+    prop_assert!(expect(&u, &result.elements));
+
+    let mut result = ArtifactArrayK::default();
+    artifact_array_k_pointwise_poly_montgomery(&mut result, &c, &y);
+    prop_assert!(expect(&w, &result.elements));
+  }
+
+  #[test]
+  fn accumulated_dot_product_matches_naive(
+    u in vec(elements(), L),
+    v in vec(elements(), L),
+  ) {
+    let mut result = Artifact::default();
+    artifact_array_l_pointwise_acc_montgomery(
+      &mut result,
+      &array_l(&u, Q - 1),
+      &array_l(&v, Q - 1),
+    );
+    for (&got, want) in result.elements.iter().zip(naive_dot(&u, &v)) {
+      prop_assert!(congruent(got as i64, want));
+    }
+  }
+
+  #[test]
+  fn addition_and_subtraction_are_elementwise(
+    a in vec(elements(), K),
+    b in vec(elements(), K),
+    c in vec(elements(), L),
+    d in vec(elements(), L),
+  ) {
+    let mut sum = array_k(&a, Q - 1);
+    artifact_array_k_add(&mut sum, &array_k(&b, Q - 1));
+    prop_assert_eq!(values(&sum.elements), zip_with(&a, &b, |x, y| x + y));
+
+    let mut difference = array_k(&a, Q - 1);
+    artifact_array_k_sub(&mut difference, &array_k(&b, Q - 1));
+    prop_assert_eq!(
+      values(&difference.elements),
+      zip_with(&a, &b, |x, y| x - y)
+    );
+
+    let mut sum = array_l(&c, Q - 1);
+    artifact_array_l_add(&mut sum, &array_l(&d, Q - 1));
+    prop_assert_eq!(values(&sum.elements), zip_with(&c, &d, |x, y| x + y));
+  }
+
+  #[test]
+  fn reductions_are_congruent_and_in_range(
+    a in vec(vec(-(1 << 30)..(1 << 30), ELEMENTS), K),
+    b in vec(vec(-(1 << 30)..(1 << 30), ELEMENTS), L),
+  ) {
+    let in_range = |x: i32| (-6283009..=6283008).contains(&x);
+
+    let mut reduced = array_k(&a, 1 << 30);
+    artifact_array_k_reduce(&mut reduced);
+    let reduced_k = values(&reduced.elements);
+    for (&x, &r) in a.iter().flatten().zip(reduced_k.iter().flatten()) {
+      prop_assert!(in_range(r) && congruent(r as i64, x as i64));
+    }
+
+    artifact_array_k_caddq(&mut reduced);
+    prop_assert_eq!(
+      values(&reduced.elements),
+      map(&a, |x| x.rem_euclid(QUANTA_I32))
+    );
+
+    let mut reduced = array_l(&b, 1 << 30);
+    artifact_array_l_reduce(&mut reduced);
+    let reduced_l = values(&reduced.elements);
+    for (&x, &r) in b.iter().flatten().zip(reduced_l.iter().flatten()) {
+      prop_assert!(in_range(r) && congruent(r as i64, x as i64));
+    }
+  }
+
+  #[test]
+  fn shift_multiplies_by_two_to_the_depth(
+    a in vec(vec(0..(1 << 10), ELEMENTS), K),
+  ) {
+    let mut shifted = array_k(&a, (1 << 10) - 1);
+    artifact_array_k_shiftl(&mut shifted);
+    prop_assert_eq!(values(&shifted.elements), map(&a, |x| x << DEPTH));
+  }
+
+  #[test]
+  fn norm_checks_match_naive(
+    a in vec(elements(), K),
+    b in vec(elements(), L),
+    bound in 1..(QUANTA_I32 - 1) / 8,
+  ) {
+    let exceeds = |values: &[Vec<i32>]| {
+      values.iter().flatten().any(|x| x.abs() >= bound)
+    };
+    prop_assert_eq!(
+      artifact_array_k_chknorm(&array_k(&a, Q - 1), bound) == 1,
+      exceeds(&a)
+    );
+    prop_assert_eq!(
+      artifact_array_l_chknorm(&array_l(&b, Q - 1), bound) == 1,
+      exceeds(&b)
+    );
+  }
+
+  #[test]
+  fn rounding_matches_naive(a in vec(standard(), K)) {
+    let mut high = array_k(&a, Q - 1);
+    let mut low = ArtifactArrayK::default();
+    artifact_array_k_power2round(&mut high, &mut low);
+    let (high_k, low_k) = (values(&high.elements), values(&low.elements));
+    let half = 1 << (DEPTH - 1);
+    for ((&x, &h), &l) in a
+      .iter()
+      .flatten()
+      .zip(high_k.iter().flatten())
+      .zip(low_k.iter().flatten())
+    {
+      prop_assert!(-half < l && l <= half);
+      prop_assert_eq!((h << DEPTH) + l, x);
+    }
+
+    let mut high = array_k(&a, Q - 1);
+    let mut low = ArtifactArrayK::default();
+    artifact_array_k_decompose(&mut high, &mut low);
+    prop_assert_eq!(
+      values(&high.elements),
+      map(&a, |x| naive_decompose(x).0)
+    );
+    prop_assert_eq!(values(&low.elements), map(&a, |x| naive_decompose(x).1));
+  }
+
+  #[test]
+  fn hints_match_naive_and_recover_high_fragments(
+    w in vec(standard(), K),
+    low in vec(vec(-CHECKED_LOW..=CHECKED_LOW, ELEMENTS), K),
+    shift in vec(vec(-(GAMMA2_I32 - 1)..GAMMA2_I32, ELEMENTS), K),
+  ) {
+    // As in signing: w = w1 * 2*GAMMA2 + w0 has its low fragment replaced
+    // by one that passed the norm check, then moves by a shift below
+    // GAMMA2. The hint must let verification recover w1 from the result.
+    let high = map(&w, |x| naive_decompose(x).0);
+    let moved_low = zip_with(&low, &shift, |l, s| l + s);
+    let moved = zip_with(&high, &moved_low, |h, l| {
+      (h * 2 * GAMMA2_I32 + l).rem_euclid(QUANTA_I32)
+    });
+
+    let mut hint = ArtifactArrayK::default();
+    let count = artifact_array_k_make_hint(
+      &mut hint,
+      &array_k(&moved_low, 2 * GAMMA2 as i64),
+      &array_k(&high, Q - 1),
+    );
+    let hints = values(&hint.elements);
+    prop_assert_eq!(&hints, &zip_with(&moved_low, &high, naive_make_hint));
+    prop_assert_eq!(count, hints.iter().flatten().sum::<i32>() as u32);
+
+    let mut corrected = array_k(&moved, Q - 1);
+    artifact_array_k_use_hint(&mut corrected, &hint);
+    let corrected = values(&corrected.elements);
+    prop_assert_eq!(&corrected, &zip_with(&moved, &hints, naive_use_hint));
+    prop_assert_eq!(&corrected, &high);
+  }
+}
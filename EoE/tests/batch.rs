@@ -0,0 +1,59 @@
+#![cfg(feature = "parallel")]
+
+use echoes_of_eternity::*;
+
+fn keys(count: u8) -> Vec<KeyPair> {
+  (0..count).map(|i| KeyPair::from_seed(&[i; ESSENCEBYTES])).collect()
+}
+
+#[test]
+fn batch_matches_single_verification_in_order() {
+  let keys = keys(3);
+  let mut items = Vec::new();
+  for i in 0..24u8 {
+    let pair = &keys[usize::from(i) % keys.len()];
+    let message = vec![i; usize::from(i)];
+    let mut signature = pair.sign(&message).to_vec();
+    match i % 4 {
+      1 => signature[0] ^= 1,
+      2 => signature.truncate(SIGNATUREBYTES - 1),
+      _ => {}
+    }
+    let public = if i % 8 == 3 {
+      pair.public[1..].to_vec()
+    } else {
+      pair.public.to_vec()
+    };
+    items.push((signature, message, public));
+  }
+
+  let results = verify_batch(&items);
+  assert_eq!(results.len(), items.len());
+  for ((signature, message, public), result) in items.iter().zip(&results) {
+    assert_eq!(
+      result.is_ok(),
+      verify_transformation(signature, message, public).is_ok()
+    );
+  }
+  assert!(results[0].is_ok());
+  assert_eq!(results[1], Err(EternityError::VerificationFailed));
+  assert_eq!(results[2], Err(EternityError::InvalidInput));
+  assert_eq!(results[3], Err(EternityError::InvalidInput));
+}
+
+#[test]
+fn prepared_key_is_reusable() {
+  let keys = KeyPair::from_seed(&[9; ESSENCEBYTES]);
+  let prepared = PreparedKey::new(&keys.public).expect("valid key");
+  for message in [&b""[..], b"first", b"second"] {
+    let signature = keys.sign(message);
+    assert!(prepared.verify(&signature, message).is_ok());
+    assert!(prepared.verify(&signature, b"other").is_err());
+  }
+}
+
+#[test]
+fn empty_batch() {
+  let items: [(&[u8], &[u8], &[u8]); 0] = [];
+  assert!(verify_batch(&items).is_empty());
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "artifact-bounds")]
+
+//! Bound tracking must stay silent on the real pipelines and catch
+//! reordered ones.
+//!
+//! Run with `cargo test --features artifact-bounds,internals --test bounds`;
+//! without `internals` only the clean runs are built.
+
+use echoes_of_eternity::*;
+
+#[test]
+fn keygen_sign_and_verify_run_clean() {
+  for n in 0..4u8 {
+    let keys = KeyPair::from_seed(&[n; ESSENCEBYTES]);
+    let message = [n; 40];
+    let signature = keys.sign(&message);
+    assert!(verify_transformation(&signature, &message, &keys.public).is_ok());
+    assert!(verify_transformation(&signature, b"other", &keys.public).is_err());
+    assert_eq!(prevalidate_transformation(&signature, &keys.public), Ok(()));
+  }
+}
+
+#[test]
+fn seeded_keys_run_clean() {
+  let seed = ArtifactSeed::from_array(&[9; ESSENCEBYTES]);
+  let signature = seed.sign(b"bounded");
+  assert!(seed.public_key().verify(b"bounded", &signature).is_ok());
+}
+
+#[cfg(feature = "internals")]
+mod reordered {
+  use echoes_of_eternity::{
+    internals::{
+      artifact_verify_stage1, artifact_verify_stage2, artifact_verify_stage4,
+      artifact_verify_stage5, artifact_verify_stage6, artifact_verify_stage7,
+      poly::*, polyvec::*,
+    },
+    KeyPair, ESSENCEBYTES,
+  };
+
+  /// Stage 6 products of a valid signature: `A*z` and `c*t1*2^DEPTH`.
+  fn stage6() -> (Box<ArtifactArrayK>, ArtifactArrayK) {
+    let keys = KeyPair::from_seed(&[6; ESSENCEBYTES]);
+    let signature = keys.sign(b"reordered");
+    let (mut essence, elements) =
+      artifact_verify_stage1(&signature, &keys.public).unwrap();
+    let (mut seal, fragments, _) = artifact_verify_stage2(&signature).unwrap();
+    let challenge = artifact_verify_stage4(&mut seal);
+    let matrix = artifact_verify_stage5(&mut essence);
+    artifact_verify_stage6(challenge, matrix, fragments, elements).unwrap()
+  }
+
+  #[test]
+  fn stage7_in_order_runs_clean() {
+    let (transformed, scaled) = stage6();
+    artifact_verify_stage7(transformed, scaled).unwrap();
+  }
+
+  #[test]
+  #[should_panic(expected = "artifact_invntt_tomont")]
+  fn stage7_without_reduce_panics() {
+    let (mut transformed, scaled) = stage6();
+    artifact_array_k_sub(&mut transformed, &scaled);
+    artifact_array_k_invntt_tomont(&mut transformed);
+  }
+
+  #[test]
+  #[should_panic(expected = "artifact_invntt_tomont")]
+  fn inverse_ntt_of_an_unreduced_sum_panics() {
+    let (transformed, scaled) = stage6();
+    let mut sum = transformed.elements[0].clone();
+    artifact_add(&mut sum, &scaled.elements[0]);
+    artifact_invntt_tomont(&mut sum);
+  }
+
+  #[test]
+  #[should_panic(expected = "artifact_shiftl")]
+  fn shift_after_ntt_panics() {
+    let keys = KeyPair::from_seed(&[6; ESSENCEBYTES]);
+    let signature = keys.sign(b"reordered");
+    let (_, mut elements) =
+      artifact_verify_stage1(&signature, &keys.public).unwrap();
+    artifact_array_k_ntt(&mut elements);
+    artifact_array_k_shiftl(&mut elements);
+  }
+}
@@ -0,0 +1,121 @@
+/*
+ * Links against the cdylib and exercises the C ABI end to end.
+ * Build and run with `make -C tests/c` from the crate root.
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "eoe.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                     \
+  do {                                                                  \
+    if (!(cond)) {                                                      \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+              #cond);                                                   \
+      failures++;                                                       \
+    }                                                                   \
+  } while (0)
+
+#define CHECK_STATUS(expr, expected)                                     \
+  do {                                                                   \
+    int32_t status_ = (expr);                                            \
+    if (status_ != (expected)) {                                         \
+      fprintf(stderr, "%s:%d: %s returned %d (%s), expected %d\n",       \
+              __FILE__, __LINE__, #expr, status_, eoe_strerror(status_), \
+              (expected));                                               \
+      failures++;                                                        \
+    }                                                                    \
+  } while (0)
+
+int main(void) {
+  uint32_t level = eoe_parameter_set();
+  CHECK(level == 2 || level == 3 || level == 5);
+  CHECK(eoe_public_key_bytes(4) == 0);
+
+  size_t pk_len = eoe_public_key_bytes(level);
+  size_t sk_len = eoe_secret_key_bytes(level);
+  size_t sig_len = eoe_signature_bytes(level);
+  CHECK(eoe_public_key_bytes(2) < eoe_public_key_bytes(5));
+
+  uint8_t *pk = malloc(pk_len);
+  uint8_t *pk2 = malloc(pk_len);
+  uint8_t *sk = malloc(sk_len);
+  uint8_t *sk2 = malloc(sk_len);
+  uint8_t *sig = malloc(sig_len);
+  uint8_t *sig2 = malloc(sig_len);
+  uint8_t seed[32];
+  const uint8_t message[] = "artifact";
+  CHECK(pk && pk2 && sk && sk2 && sig && sig2);
+  CHECK(eoe_seed_bytes() == sizeof seed);
+  memset(seed, 0x42, sizeof seed);
+
+  /* Key generation is deterministic in the seed. */
+  CHECK_STATUS(eoe_keypair_from_seed(pk, pk_len, sk, sk_len, seed, sizeof seed),
+               EOE_OK);
+  CHECK_STATUS(
+      eoe_keypair_from_seed(pk2, pk_len, sk2, sk_len, seed, sizeof seed),
+      EOE_OK);
+  CHECK(memcmp(pk, pk2, pk_len) == 0);
+  CHECK(memcmp(sk, sk2, sk_len) == 0);
+
+  /* Signing is deterministic and verifies. */
+  CHECK_STATUS(eoe_sign(sig, sig_len, message, sizeof message, sk, sk_len),
+               EOE_OK);
+  CHECK_STATUS(eoe_sign(sig2, sig_len, message, sizeof message, sk, sk_len),
+               EOE_OK);
+  CHECK(memcmp(sig, sig2, sig_len) == 0);
+  CHECK_STATUS(eoe_verify(sig, sig_len, message, sizeof message, pk, pk_len),
+               EOE_OK);
+
+  /* Empty messages may be passed as NULL. */
+  CHECK_STATUS(eoe_sign(sig2, sig_len, NULL, 0, sk, sk_len), EOE_OK);
+  CHECK_STATUS(eoe_verify(sig2, sig_len, NULL, 0, pk, pk_len), EOE_OK);
+
+  /* Tampering is detected. */
+  sig[sig_len / 2] ^= 1;
+  CHECK(eoe_verify(sig, sig_len, message, sizeof message, pk, pk_len) !=
+        EOE_OK);
+  sig[sig_len / 2] ^= 1;
+  CHECK_STATUS(eoe_verify(sig, sig_len, message, sizeof message - 1, pk,
+                          pk_len),
+               EOE_ERR_VERIFICATION_FAILED);
+
+  /* Malformed arguments are reported, not dereferenced. */
+  CHECK_STATUS(eoe_verify(sig, sig_len - 1, message, sizeof message, pk,
+                          pk_len),
+               EOE_ERR_INVALID_INPUT);
+  CHECK_STATUS(eoe_verify(NULL, sig_len, message, sizeof message, pk, pk_len),
+               EOE_ERR_NULL_POINTER);
+  CHECK_STATUS(eoe_sign(sig, sig_len + 1, message, sizeof message, sk, sk_len),
+               EOE_ERR_INVALID_INPUT);
+  CHECK_STATUS(eoe_keypair_from_seed(pk, pk_len, sk, sk_len, seed, 31),
+               EOE_ERR_INVALID_INPUT);
+
+  /* Fresh keys from the operating system sign and verify too. */
+  CHECK_STATUS(eoe_keypair(pk2, pk_len, sk2, sk_len), EOE_OK);
+  CHECK_STATUS(eoe_sign(sig2, sig_len, message, sizeof message, sk2, sk_len),
+               EOE_OK);
+  CHECK_STATUS(eoe_verify(sig2, sig_len, message, sizeof message, pk2, pk_len),
+               EOE_OK);
+  CHECK(eoe_verify(sig2, sig_len, message, sizeof message, pk, pk_len) !=
+        EOE_OK);
+
+  CHECK(strcmp(eoe_strerror(EOE_OK), "ok") == 0);
+  CHECK(strcmp(eoe_strerror(1234), "unknown status code") == 0);
+
+  free(pk);
+  free(pk2);
+  free(sk);
+  free(sk2);
+  free(sig);
+  free(sig2);
+
+  if (failures) {
+    fprintf(stderr, "%d check(s) failed\n", failures);
+    return 1;
+  }
+  printf("eoe C ABI: all checks passed (level %u)\n", level);
+  return 0;
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "composite")]
+
+use echoes_of_eternity::*;
+use zeroize::Zeroizing;
+
+const MESSAGE: &[u8] = b"composite artifact";
+
+struct Composite {
+  seed: Vec<u8>,
+  public_key: Vec<u8>,
+  secret_key: Zeroizing<Vec<u8>>,
+}
+
+fn scheme() -> &'static dyn SignatureScheme {
+  resolve_scheme(AlgorithmId::CURRENT_COMPOSITE).unwrap()
+}
+
+fn composite() -> Composite {
+  let seed: Vec<u8> = (0..scheme().seed_bytes() as u8).collect();
+  let (public_key, secret_key) = scheme().keypair_from_seed(&seed).unwrap();
+  Composite {
+    seed,
+    public_key,
+    secret_key,
+  }
+}
+
+fn sign(keys: &Composite, message: &[u8]) -> CompositeSignature {
+  let signature = scheme().sign(&keys.secret_key, message).unwrap();
+  CompositeSignature::from_bytes(CompositeAlgorithm::CURRENT, &signature)
+    .unwrap()
+}
+
+fn public_key(keys: &Composite) -> CompositePublicKey {
+  CompositePublicKey::from_bytes(CompositeAlgorithm::CURRENT, &keys.public_key)
+    .unwrap()
+}
+
+fn verify(keys: &Composite, signature: &CompositeSignature) -> bool {
+  public_key(keys).verify(MESSAGE, &[], signature).is_ok()
+}
+
+#[test]
+fn encoding_layout() {
+  let algorithm = CompositeAlgorithm::CURRENT;
+  let keys = composite();
+  let artifact_seed = keys.seed[..ESSENCEBYTES].try_into().unwrap();
+  let artifact = KeyPair::from_seed(artifact_seed);
+
+  // Keys: artifact encoding, then Ed25519.
+  assert_eq!(keys.public_key.len(), algorithm.public_key_bytes());
+  assert_eq!(
+    algorithm.public_key_bytes(),
+    ARTIFACTKEYBYTES + ED25519_PUBLIC_KEY_BYTES
+  );
+  let public_key = public_key(&keys);
+  assert_eq!(public_key.artifact_key(), &artifact.public[..]);
+  assert_eq!(
+    public_key.ed25519_key().as_bytes()[..],
+    keys.public_key[ARTIFACTKEYBYTES..]
+  );
+  assert_eq!(public_key.to_bytes(), keys.public_key);
+  assert_eq!(&keys.secret_key[..SECRETKEYBYTES], artifact.expose_secret());
+  assert_eq!(keys.secret_key[SECRETKEYBYTES..], keys.seed[ESSENCEBYTES..]);
+
+  // M' = Prefix || Label || len(ctx) || ctx || SHA-512(M)
+  let representative =
+    algorithm.message_representative(MESSAGE, b"ctx").unwrap();
+  let label = algorithm.label();
+  let head = [COMPOSITE_PREFIX, label, &[3], b"ctx"].concat();
+  assert!(representative.starts_with(&head));
+  assert_eq!(representative.len(), head.len() + 64);
+  assert!(algorithm
+    .message_representative(MESSAGE, &[0; MAX_CONTEXT_BYTES + 1])
+    .is_err());
+
+  // Signatures: artifact signature over M' with an empty context, then
+  // Ed25519.
+  let signature = sign(&keys, MESSAGE);
+  let bytes = signature.to_bytes();
+  assert_eq!(bytes.len(), algorithm.signature_bytes());
+  assert_eq!(
+    algorithm.signature_bytes(),
+    SIGNATUREBYTES + ED25519_SIGNATURE_BYTES
+  );
+  let representative = algorithm.message_representative(MESSAGE, &[]).unwrap();
+  assert_eq!(
+    signature.artifact_signature(),
+    &artifact.sign(&representative)
+  );
+  assert_eq!(&bytes[..SIGNATUREBYTES], signature.artifact_signature());
+  assert_eq!(
+    bytes[SIGNATUREBYTES..],
+    signature.ed25519_signature().to_bytes()
+  );
+}
+
+#[test]
+fn both_components_must_verify() {
+  let keys = composite();
+  let signature = sign(&keys, MESSAGE);
+  assert!(verify(&keys, &signature));
+
+  let bytes = signature.to_bytes();
+  for index in [0, SIGNATUREBYTES / 2, SIGNATUREBYTES, bytes.len() - 1] {
+    let mut tampered = bytes.clone();
+    tampered[index] ^= 1;
+    let tampered =
+      CompositeSignature::from_bytes(CompositeAlgorithm::CURRENT, &tampered)
+        .unwrap();
+    assert!(!verify(&keys, &tampered), "byte {index}");
+  }
+
+  // Each component valid on its own, but over a different message.
+  let other = sign(&keys, b"other");
+  let ed25519 = other.ed25519_signature().to_bytes();
+  let mixed = CompositeSignature::from_components(
+    CompositeAlgorithm::CURRENT,
+    signature.artifact_signature(),
+    &ed25519,
+  )
+  .unwrap();
+  assert!(!verify(&keys, &mixed));
+
+  let ed25519 = signature.ed25519_signature().to_bytes();
+  let mixed = CompositeSignature::from_components(
+    CompositeAlgorithm::CURRENT,
+    other.artifact_signature(),
+    &ed25519,
+  )
+  .unwrap();
+  assert!(!verify(&keys, &mixed));
+}
+
+#[test]
+fn context_is_bound() {
+  let keys = composite();
+  let signature = sign(&keys, MESSAGE);
+  let public_key = public_key(&keys);
+  assert!(public_key.verify(MESSAGE, &[], &signature).is_ok());
+  assert!(public_key.verify(MESSAGE, b"ctx", &signature).is_err());
+}
+
+#[test]
+fn wrong_lengths_are_rejected() {
+  let algorithm = CompositeAlgorithm::CURRENT;
+  let keys = composite();
+  let signature = sign(&keys, MESSAGE).to_bytes();
+  let short_key = &keys.public_key[1..];
+  assert!(CompositePublicKey::from_bytes(algorithm, short_key).is_err());
+  let short_signature = &signature[..signature.len() - 1];
+  assert!(CompositeSignature::from_bytes(algorithm, short_signature).is_err());
+}
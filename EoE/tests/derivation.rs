@@ -0,0 +1,95 @@
+use echoes_of_eternity::*;
+
+/// The SLIP-0010 test seed `000102..0f`.
+const MASTER_SEED: [u8; 16] = [
+  0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b,
+  0x0c, 0x0d, 0x0e, 0x0f,
+];
+
+// Computed independently from the construction in the `derivation` module
+// docs with Python's hashlib.shake_256.
+const MASTER_NODE: (&str, &str) = (
+  "fdcd9f41dde1043fb31ed567398138550d75026ebcddae160d0d026b8e413ebd",
+  "73d1d5f638f844b30c6e4187b345314dc153abc570ee4e7422aa478790ce46d0",
+);
+const PATH_NODE: (&str, &str) = (
+  "dc6b0f8541bd2575e330e91b5faf811dacdbc4cb8368e5a86d753518ae297391",
+  "14133b8bb3ff26b0b33d19c0c59d18d5067d533be77928de021dc2a1c8031d85",
+);
+
+fn node(seed: &ExtendedSeed) -> (String, String) {
+  (hex_encode(seed.seed()), hex_encode(seed.chain_code()))
+}
+
+fn owned((seed, chain_code): (&str, &str)) -> (String, String) {
+  (seed.to_owned(), chain_code.to_owned())
+}
+
+#[test]
+fn master_node_vector() {
+  let master = ExtendedSeed::from_master(&MASTER_SEED).unwrap();
+  assert_eq!(node(&master), owned(MASTER_NODE));
+  assert_eq!(master.depth(), 0);
+}
+
+#[test]
+fn path_vector() {
+  let path: DerivationPath = "m/44'/7'/0'".parse().unwrap();
+  let master = ExtendedSeed::from_master(&MASTER_SEED).unwrap();
+  let child = master.derive_path(&path).unwrap();
+  assert_eq!(node(&child), owned(PATH_NODE));
+  assert_eq!(child.depth(), 3);
+
+  let stepwise = [44, 7, 0].iter().fold(master, |parent, &index| {
+    parent.derive_child(HARDENED + index).unwrap()
+  });
+  assert_eq!(node(&stepwise), owned(PATH_NODE));
+
+  let keys = derive_keypair(&MASTER_SEED, &path).unwrap();
+  assert_eq!(keys.public, child.keypair().public);
+}
+
+#[test]
+fn paths_parse_and_display() {
+  let path: DerivationPath = "m/44h/7'/0h".parse().unwrap();
+  assert_eq!(path.indices(), [HARDENED + 44, HARDENED + 7, HARDENED]);
+  assert_eq!(path.to_string(), "m/44'/7'/0'");
+
+  let root: DerivationPath = "m".parse().unwrap();
+  assert!(root.indices().is_empty());
+  assert_eq!(root.to_string(), "m");
+
+  let last: DerivationPath = "m/2147483647'".parse().unwrap();
+  assert_eq!(last.indices(), [u32::MAX]);
+
+  for bad in [
+    "", "M", "m/", "44'", "m//0'", "m/x'", "m/+1'", "m/-1'", "m/1'/",
+    "m/2147483648'", "m/0'h",
+  ] {
+    assert!(bad.parse::<DerivationPath>().is_err(), "{bad:?}");
+  }
+}
+
+#[test]
+fn non_hardened_indices_are_rejected() {
+  assert!("m/0".parse::<DerivationPath>().is_err());
+  assert!("m/44'/7".parse::<DerivationPath>().is_err());
+  assert_eq!(
+    DerivationPath::from_indices(&[HARDENED, 1]),
+    Err(EternityError::InvalidInput)
+  );
+
+  let master = ExtendedSeed::from_master(&MASTER_SEED).unwrap();
+  assert!(master.derive_child(HARDENED - 1).is_err());
+  assert!(master.derive_child(HARDENED).is_ok());
+}
+
+#[test]
+fn master_seed_length_is_bounded() {
+  for len in [0, MIN_MASTER_SEED_BYTES - 1, MAX_MASTER_SEED_BYTES + 1] {
+    assert!(ExtendedSeed::from_master(&vec![0; len]).is_err(), "{len}");
+  }
+  for len in [MIN_MASTER_SEED_BYTES, MAX_MASTER_SEED_BYTES] {
+    assert!(ExtendedSeed::from_master(&vec![0; len]).is_ok(), "{len}");
+  }
+}
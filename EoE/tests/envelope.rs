@@ -0,0 +1,165 @@
+use echoes_of_eternity::*;
+
+fn keys() -> KeyPair {
+  KeyPair::from_seed(&[4; ESSENCEBYTES])
+}
+
+#[test]
+fn seal_and_open_every_kind() {
+  for algorithm in AlgorithmId::ALL {
+    for (kind, len) in [
+      (EnvelopeKind::PublicKey, algorithm.public_key_bytes()),
+      (EnvelopeKind::SecretKey, algorithm.secret_key_bytes()),
+      (EnvelopeKind::Signature, algorithm.signature_bytes()),
+    ] {
+      let payload: Vec<u8> = (0..len).map(|i| i as u8).collect();
+      let sealed = seal_envelope(algorithm, &payload).unwrap();
+      assert_eq!(sealed.len(), ENVELOPE_HEADER_BYTES + len);
+      assert_eq!(sealed[..2], [ENVELOPE_VERSION, algorithm as u8]);
+
+      let envelope = open_envelope(&sealed).unwrap();
+      assert_eq!(
+        envelope,
+        Envelope {
+          version: ENVELOPE_VERSION,
+          algorithm,
+          kind,
+          payload: &payload,
+        }
+      );
+    }
+  }
+}
+
+#[test]
+fn key_types_round_trip() {
+  let keys = keys();
+  let public = keys.public_key();
+  let secret = keys.secret_key();
+  let signature = secret.sign(b"enveloped");
+
+  let sealed = public.to_envelope();
+  assert_eq!(
+    sealed,
+    seal_envelope(AlgorithmId::CURRENT, public.as_bytes()).unwrap()
+  );
+  assert_eq!(
+    open_envelope(&sealed).unwrap().kind,
+    EnvelopeKind::PublicKey
+  );
+  assert_eq!(ArtifactPublicKey::from_envelope(&sealed).unwrap(), public);
+
+  let sealed = secret.to_envelope();
+  assert_eq!(
+    open_envelope(&sealed).unwrap().kind,
+    EnvelopeKind::SecretKey
+  );
+  assert_eq!(
+    ArtifactSecretKey::from_envelope(&sealed)
+      .unwrap()
+      .as_bytes(),
+    secret.as_bytes()
+  );
+
+  let sealed = signature.to_envelope();
+  assert_eq!(
+    open_envelope(&sealed).unwrap().kind,
+    EnvelopeKind::Signature
+  );
+  assert_eq!(
+    ArtifactSignature::from_envelope(&sealed).unwrap(),
+    signature
+  );
+
+  // A well-formed envelope of the wrong kind fails the length check.
+  assert!(ArtifactSignature::from_envelope(&public.to_envelope()).is_err());
+}
+
+#[test]
+fn enveloped_signatures_verify() {
+  let keys = keys();
+  let public = keys.public_key().to_envelope();
+  let signature = keys.secret_key().sign(b"enveloped").to_envelope();
+  assert!(verify_envelope(&signature, b"enveloped", &public).is_ok());
+  assert_eq!(
+    verify_envelope(&signature, b"other", &public).unwrap_err(),
+    EternityError::VerificationFailed
+  );
+  // Swapped arguments carry the wrong kinds.
+  assert_eq!(
+    verify_envelope(&public, b"enveloped", &signature).unwrap_err(),
+    EternityError::InvalidInput
+  );
+}
+
+#[test]
+fn unknown_version_is_rejected() {
+  let mut sealed = keys().public_key().to_envelope();
+  for version in [0, ENVELOPE_VERSION + 1, u8::MAX] {
+    sealed[0] = version;
+    assert_eq!(
+      open_envelope(&sealed).unwrap_err(),
+      EternityError::InvalidInput
+    );
+    assert_eq!(
+      ArtifactPublicKey::from_envelope(&sealed).unwrap_err(),
+      EternityError::InvalidInput
+    );
+  }
+  assert_eq!(open_envelope(&[]).unwrap_err(), EternityError::InvalidInput);
+  assert_eq!(
+    open_envelope(&[ENVELOPE_VERSION]).unwrap_err(),
+    EternityError::InvalidInput
+  );
+}
+
+#[test]
+fn unknown_algorithm_is_rejected() {
+  let mut sealed = keys().public_key().to_envelope();
+  for id in (0..=u8::MAX).filter(|&id| AlgorithmId::from_u8(id).is_none()) {
+    sealed[1] = id;
+    assert_eq!(
+      open_envelope(&sealed).unwrap_err(),
+      EternityError::UnsupportedAlgorithm
+    );
+  }
+
+  // Known, but not the algorithm the wrapper types are compiled for.
+  let other = AlgorithmId::ALL
+    .into_iter()
+    .find(|&algorithm| {
+      algorithm != AlgorithmId::CURRENT
+        && algorithm.public_key_bytes() == ARTIFACTKEYBYTES
+    })
+    .unwrap();
+  sealed[1] = other as u8;
+  assert_eq!(open_envelope(&sealed).unwrap().algorithm, other);
+  assert_eq!(
+    ArtifactPublicKey::from_envelope(&sealed).unwrap_err(),
+    EternityError::UnsupportedAlgorithm
+  );
+}
+
+#[test]
+fn payload_lengths_of_no_kind_are_rejected() {
+  let algorithm = AlgorithmId::CURRENT;
+  let lengths = [
+    0,
+    algorithm.public_key_bytes() - 1,
+    algorithm.secret_key_bytes() + 1,
+    algorithm.signature_bytes() + 1,
+  ];
+  for len in lengths {
+    assert_eq!(EnvelopeKind::from_payload_len(algorithm, len), None);
+    assert_eq!(
+      seal_envelope(algorithm, &vec![0; len]).unwrap_err(),
+      EternityError::InvalidInput
+    );
+    let mut sealed = vec![ENVELOPE_VERSION, algorithm as u8];
+    sealed.resize(ENVELOPE_HEADER_BYTES + len, 0);
+    assert_eq!(
+      open_envelope(&sealed).unwrap_err(),
+      EternityError::InvalidInput
+    );
+  }
+}
@@ -0,0 +1,108 @@
+use echoes_of_eternity::*;
+
+/// Known-answer vectors for the compiled parameter set.
+#[cfg(feature = "artifact_mode2")]
+const KAT: &str = include_str!("data/dilithium2.rsp");
+#[cfg(not(any(feature = "artifact_mode2", feature = "artifact_mode5")))]
+const KAT: &str = include_str!("data/dilithium3.rsp");
+#[cfg(feature = "artifact_mode5")]
+const KAT: &str = include_str!("data/dilithium5.rsp");
+
+struct Vector {
+  count: usize,
+  keyseed: [u8; ESSENCEBYTES],
+  msg: Vec<u8>,
+  pk: Vec<u8>,
+  sk: Vec<u8>,
+  sm: Vec<u8>,
+}
+
+fn hex(text: &str) -> Vec<u8> {
+  (0..text.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+    .collect()
+}
+
+/// Parse the `.rsp` entries, one block of `key = value` lines each.
+fn vectors() -> Vec<Vector> {
+  let mut vectors = Vec::new();
+  for block in KAT.split("\n\n") {
+    let field = |name: &str| {
+      block
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(" = "))
+        .unwrap_or_else(|| panic!("missing {name}"))
+    };
+    if block.trim_start().starts_with('#') || block.trim().is_empty() {
+      continue;
+    }
+    let vector = Vector {
+      count: field("count").parse().unwrap(),
+      keyseed: hex(field("keyseed")).try_into().unwrap(),
+      msg: hex(field("msg")),
+      pk: hex(field("pk")),
+      sk: hex(field("sk")),
+      sm: hex(field("sm")),
+    };
+    assert_eq!(vector.msg.len(), field("mlen").parse::<usize>().unwrap());
+    assert_eq!(vector.sm.len(), field("smlen").parse::<usize>().unwrap());
+    vectors.push(vector);
+  }
+  assert_eq!(vectors.len(), 2);
+  vectors
+}
+
+#[test]
+fn keypair_matches_reference() {
+  for vector in vectors() {
+    assert_eq!(vector.pk.len(), ARTIFACTKEYBYTES);
+    assert_eq!(vector.sk.len(), SECRETKEYBYTES);
+    let keys = KeyPair::from_seed(&vector.keyseed);
+    assert_eq!(keys.public[..], vector.pk[..], "count {}", vector.count);
+    assert_eq!(
+      keys.expose_secret(),
+      &vector.sk[..],
+      "count {}",
+      vector.count
+    );
+  }
+}
+
+#[test]
+fn signature_matches_reference() {
+  for vector in vectors() {
+    let (signature, message) = vector.sm.split_at(SIGNATUREBYTES);
+    assert_eq!(message, &vector.msg[..]);
+
+    let keys = KeyPair::from_seed(&vector.keyseed);
+    assert_eq!(
+      keys.sign(&vector.msg)[..],
+      signature[..],
+      "count {}",
+      vector.count
+    );
+    assert_eq!(
+      keys.secret_key().sign(&vector.msg).as_bytes(),
+      signature,
+      "count {}",
+      vector.count
+    );
+  }
+}
+
+#[test]
+fn reference_signature_verifies() {
+  for vector in vectors() {
+    let signature = &vector.sm[..SIGNATUREBYTES];
+    assert_eq!(
+      verify_transformation(signature, &vector.msg, &vector.pk),
+      Ok(())
+    );
+
+    let mut tampered = signature.to_vec();
+    tampered[SIGNATUREBYTES / 2] ^= 1;
+    assert!(verify_transformation(&tampered, &vector.msg, &vector.pk).is_err());
+    assert!(verify_transformation(signature, b"other", &vector.pk).is_err());
+  }
+}
@@ -0,0 +1,297 @@
+use echoes_of_eternity::*;
+
+fn keys() -> KeyPair {
+  KeyPair::from_seed(&[3; ESSENCEBYTES])
+}
+
+#[test]
+fn lengths_are_checked() {
+  let keys = keys();
+  let public = keys.public_key();
+  let secret = keys.secret_key();
+  let signature = secret.sign(b"lengths");
+
+  assert_eq!(ArtifactPublicKey::BYTES, ARTIFACTKEYBYTES);
+  assert_eq!(ArtifactSecretKey::BYTES, SECRETKEYBYTES);
+  assert_eq!(ArtifactSignature::BYTES, SIGNATUREBYTES);
+
+  for (bytes, len) in [
+    (public.as_bytes(), ARTIFACTKEYBYTES),
+    (secret.as_bytes(), SECRETKEYBYTES),
+    (signature.as_bytes(), SIGNATUREBYTES),
+  ] {
+    assert_eq!(bytes.len(), len);
+    let mut longer = bytes.to_vec();
+    longer.push(0);
+    for stray in [&[][..], &bytes[..len - 1], &longer[..]] {
+      assert_eq!(
+        ArtifactPublicKey::from_bytes(stray).unwrap_err(),
+        EternityError::InvalidInput
+      );
+      assert_eq!(
+        ArtifactSecretKey::from_bytes(stray).unwrap_err(),
+        EternityError::InvalidInput
+      );
+      assert_eq!(
+        ArtifactSignature::from_bytes(stray).unwrap_err(),
+        EternityError::InvalidInput
+      );
+    }
+  }
+
+  assert_eq!(
+    ArtifactPublicKey::from_bytes(public.as_bytes()).unwrap(),
+    public
+  );
+  assert_eq!(
+    ArtifactSecretKey::from_bytes(secret.as_bytes())
+      .unwrap()
+      .as_bytes(),
+    secret.as_bytes()
+  );
+  assert_eq!(
+    ArtifactSignature::try_from(signature.as_bytes()).unwrap(),
+    signature
+  );
+}
+
+#[test]
+fn other_parameter_sets_are_told_apart() {
+  assert_eq!(ArtifactPublicKey::PARAMETER_SET, ParameterSet::CURRENT);
+  assert_eq!(ArtifactSecretKey::PARAMETER_SET, ParameterSet::CURRENT);
+  assert_eq!(ArtifactSignature::PARAMETER_SET, ParameterSet::CURRENT);
+
+  for set in ParameterSet::ALL {
+    let public = vec![0; set.artifact_key_bytes()];
+    let secret = vec![0; set.secret_key_bytes()];
+    let signature = vec![0; set.signature_bytes()];
+    assert_eq!(
+      ArtifactPublicKey::parameter_set_for_len(public.len()),
+      Some(set)
+    );
+    assert_eq!(
+      ArtifactSecretKey::parameter_set_for_len(secret.len()),
+      Some(set)
+    );
+    assert_eq!(
+      ArtifactSignature::parameter_set_for_len(signature.len()),
+      Some(set)
+    );
+    if set == ParameterSet::CURRENT {
+      continue;
+    }
+    assert_eq!(
+      ArtifactPublicKey::from_bytes(&public).unwrap_err(),
+      EternityError::ParameterMismatch
+    );
+    assert_eq!(
+      ArtifactSecretKey::from_bytes(&secret).unwrap_err(),
+      EternityError::ParameterMismatch
+    );
+    assert_eq!(
+      ArtifactSignature::from_bytes(&signature).unwrap_err(),
+      EternityError::ParameterMismatch
+    );
+  }
+  assert_eq!(ArtifactPublicKey::parameter_set_for_len(0), None);
+  assert_eq!(
+    ArtifactSignature::parameter_set_for_len(SIGNATUREBYTES + 1),
+    None
+  );
+}
+
+#[test]
+fn hex_round_trip() {
+  let keys = keys();
+  let public = keys.public_key();
+  let secret = keys.secret_key();
+  let signature = secret.sign(b"hex");
+
+  let hex = public.to_hex();
+  assert_eq!(hex.len(), 2 * ARTIFACTKEYBYTES);
+  assert!(hex
+    .bytes()
+    .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+  assert_eq!(ArtifactPublicKey::from_hex(&hex).unwrap(), public);
+  assert_eq!(
+    ArtifactPublicKey::from_hex(&hex.to_uppercase()).unwrap(),
+    public
+  );
+  assert_eq!(
+    ArtifactPublicKey::from_hex(&format!(" {hex}\n")).unwrap(),
+    public
+  );
+  assert_eq!(
+    ArtifactSecretKey::from_hex(&secret.to_hex())
+      .unwrap()
+      .as_bytes(),
+    secret.as_bytes()
+  );
+  assert_eq!(
+    ArtifactSignature::from_hex(&signature.to_hex()).unwrap(),
+    signature
+  );
+}
+
+#[test]
+fn bad_hex_is_rejected() {
+  let hex = keys().public_key().to_hex();
+  let mut non_hex = hex.clone();
+  non_hex.replace_range(..1, "g");
+  for bad in [&hex[1..], &non_hex[..], &hex[..hex.len() - 2]] {
+    assert_eq!(
+      ArtifactPublicKey::from_hex(bad).unwrap_err(),
+      EternityError::InvalidInput
+    );
+  }
+  let secret = keys().secret_key().to_hex();
+  assert!(ArtifactSecretKey::from_hex(&secret[1..]).is_err());
+}
+
+#[test]
+fn base64_round_trip() {
+  let keys = keys();
+  let public = keys.public_key();
+  let secret = keys.secret_key();
+  let signature = secret.sign(b"base64");
+
+  let base64 = public.to_base64();
+  assert_eq!(base64.len(), ARTIFACTKEYBYTES.div_ceil(3) * 4);
+  assert_eq!(ArtifactPublicKey::from_base64(&base64).unwrap(), public);
+  assert_eq!(
+    ArtifactSecretKey::from_base64(&secret.to_base64())
+      .unwrap()
+      .as_bytes(),
+    secret.as_bytes()
+  );
+  assert_eq!(
+    ArtifactSignature::from_base64(&signature.to_base64()).unwrap(),
+    signature
+  );
+}
+
+#[test]
+fn bad_base64_is_rejected() {
+  let base64 = keys().public_key().to_base64();
+  let mut outside_alphabet = base64.clone();
+  outside_alphabet.replace_range(..1, "-");
+  let mut misplaced_padding = base64.clone();
+  misplaced_padding.replace_range(..1, "=");
+  for bad in [
+    &base64[1..],
+    &outside_alphabet[..],
+    &misplaced_padding[..],
+    &base64[..base64.len() - 4],
+  ] {
+    assert_eq!(
+      ArtifactPublicKey::from_base64(bad).unwrap_err(),
+      EternityError::InvalidInput
+    );
+  }
+}
+
+#[test]
+fn from_array_matches_from_bytes() {
+  let keys = keys();
+  let secret = keys.secret_key();
+  let array: &[u8; SECRETKEYBYTES] = secret.as_bytes().try_into().unwrap();
+  let copied = ArtifactSecretKey::from_array(array);
+  assert_eq!(copied.as_bytes(), secret.as_bytes());
+  assert_eq!(copied.clone().as_bytes(), secret.as_bytes());
+  assert_eq!(format!("{copied:?}"), "ArtifactSecretKey(..)");
+  assert_eq!(copied.sign(b"array"), secret.sign(b"array"));
+}
+
+#[test]
+fn seed_expands_lazily_and_once() {
+  let seed = ArtifactSeed::from_array(&[3; ESSENCEBYTES]);
+  assert!(!seed.is_expanded());
+  assert_eq!(seed.as_bytes(), [3; ESSENCEBYTES]);
+
+  let public = seed.public_key();
+  assert!(seed.is_expanded());
+  assert_eq!(public, &keys().public_key());
+  assert_eq!(seed.secret_key().as_bytes(), keys().expose_secret());
+
+  // Later calls hand out the cached keys rather than deriving new ones.
+  assert!(std::ptr::eq(public, seed.public_key()));
+  assert!(std::ptr::eq(seed.secret_key(), seed.secret_key()));
+  assert_eq!(seed.sign(b"seed"), keys().secret_key().sign(b"seed"));
+}
+
+#[test]
+fn clearing_the_cache_keeps_the_seed() {
+  let mut seed = ArtifactSeed::from_bytes(&[3; ESSENCEBYTES]).unwrap();
+  let signature = seed.sign(b"cache");
+  assert!(seed.is_expanded());
+
+  // A clone carries the cache along and is unaffected by clearing it.
+  let clone = seed.clone();
+  seed.clear_cache();
+  assert!(!seed.is_expanded());
+  assert!(clone.is_expanded());
+  assert_eq!(seed.as_bytes(), [3; ESSENCEBYTES]);
+
+  // Clearing an empty cache is harmless, and expanding again gives the
+  // same keys.
+  seed.clear_cache();
+  assert_eq!(seed.sign(b"cache"), signature);
+  assert!(seed.is_expanded());
+  assert_eq!(seed.public_key(), clone.public_key());
+}
+
+#[test]
+fn seed_length_is_checked() {
+  for len in [0, ESSENCEBYTES - 1, ESSENCEBYTES + 1] {
+    assert_eq!(
+      ArtifactSeed::from_bytes(&vec![3; len]).unwrap_err(),
+      EternityError::InvalidInput
+    );
+  }
+  let seed = ArtifactSeed::from_array(&[3; ESSENCEBYTES]);
+  assert_eq!(format!("{seed:?}"), "ArtifactSeed(..)");
+}
+
+#[cfg(feature = "serde")]
+mod serde_encoding {
+  use super::*;
+  use serde_test::{
+    assert_de_tokens_error, assert_ser_tokens, assert_tokens, Configure, Token,
+  };
+
+  #[test]
+  fn human_readable_formats_use_hex() {
+    let keys = keys();
+    let public = keys.public_key();
+    let hex: &'static str = Box::leak(public.to_hex().into_boxed_str());
+    assert_tokens(&public.readable(), &[Token::Str(hex)]);
+
+    let signature = keys.sign(b"serde").to_vec();
+    let signature = ArtifactSignature::from_bytes(&signature).unwrap();
+    let hex: &'static str = Box::leak(signature.to_hex().into_boxed_str());
+    assert_tokens(&signature.readable(), &[Token::Str(hex)]);
+
+    assert_de_tokens_error::<serde_test::Readable<ArtifactPublicKey>>(
+      &[Token::Str("zz")],
+      "invalid hex encoding",
+    );
+  }
+
+  #[test]
+  fn binary_formats_use_raw_bytes() {
+    let keys = keys();
+    let public = keys.public_key();
+    let bytes: &'static [u8] = Box::leak(public.as_bytes().into());
+    assert_tokens(&public.compact(), &[Token::Bytes(bytes)]);
+
+    // Secret keys are not comparable, so only their encoding is checked.
+    let secret = keys.secret_key();
+    let bytes: &'static [u8] = Box::leak(secret.as_bytes().into());
+    assert_ser_tokens(&secret.compact(), &[Token::Bytes(bytes)]);
+
+    assert_de_tokens_error::<serde_test::Compact<ArtifactPublicKey>>(
+      &[Token::Bytes(&[0; 3])],
+      "invalid input",
+    );
+  }
+}
@@ -0,0 +1,268 @@
+#![cfg(feature = "keystore")]
+
+use std::time::{Duration, SystemTime};
+
+use echoes_of_eternity::*;
+
+/// Cheap enough for tests; never use this cost for real keystores.
+const KDF: KeystoreKdf = KeystoreKdf {
+  log_n: 4,
+  r: 8,
+  p: 1,
+};
+
+const PASSPHRASE: &str = "correct horse battery staple";
+
+/// File header and entry count.
+const FILE_HEADER_BYTES: usize = 8;
+
+/// Offset of the first entry's label: algorithm id, created, label length.
+const LABEL_OFFSET: usize = FILE_HEADER_BYTES + 1 + 8 + 2;
+
+fn seed(n: u8) -> ArtifactSeed {
+  ArtifactSeed::from_array(&[n; ESSENCEBYTES])
+}
+
+fn keystore(labels: &[&str]) -> Keystore {
+  let mut keystore = Keystore::new();
+  for (n, label) in labels.iter().enumerate() {
+    keystore
+      .insert_with_kdf(label, &seed(n as u8), PASSPHRASE, KDF)
+      .unwrap();
+  }
+  keystore
+}
+
+#[test]
+fn insert_and_unlock() {
+  let keystore = keystore(&["signing", "backup"]);
+  for (n, label) in ["signing", "backup"].into_iter().enumerate() {
+    let unlocked = keystore.unlock(label, PASSPHRASE).unwrap();
+    assert_eq!(unlocked.as_bytes(), seed(n as u8).as_bytes());
+  }
+  assert_eq!(
+    keystore.unlock("missing", PASSPHRASE).unwrap_err(),
+    EternityError::InvalidInput
+  );
+}
+
+#[test]
+fn bytes_round_trip() {
+  let keystore = keystore(&["signing", "backup"]);
+  let bytes = keystore.to_bytes();
+  assert!(bytes.starts_with(KEYSTORE_MAGIC));
+  assert_eq!(bytes[5], KEYSTORE_VERSION);
+  assert_eq!(bytes[6..8], [0, 2]);
+
+  let decoded = Keystore::from_bytes(&bytes).unwrap();
+  assert_eq!(decoded, keystore);
+  assert_eq!(decoded.to_bytes(), bytes);
+  let unlocked = decoded.unlock("backup", PASSPHRASE).unwrap();
+  assert_eq!(unlocked.as_bytes(), seed(1).as_bytes());
+}
+
+#[test]
+fn save_and_load() {
+  let path = std::env::temp_dir()
+    .join(format!("eoe-keystore-{}.eoeks", std::process::id()));
+  let keystore = keystore(&["signing"]);
+  keystore.save(&path).unwrap();
+  let loaded = Keystore::load(&path);
+  #[cfg(unix)]
+  let mode = {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(&path).unwrap().permissions().mode() & 0o777
+  };
+  std::fs::remove_file(&path).unwrap();
+
+  let loaded = loaded.unwrap();
+  assert_eq!(loaded, keystore);
+  let unlocked = loaded.unlock("signing", PASSPHRASE).unwrap();
+  assert_eq!(unlocked.as_bytes(), seed(0).as_bytes());
+  #[cfg(unix)]
+  assert_eq!(mode, 0o600);
+}
+
+#[test]
+fn entries_are_listed_without_a_passphrase() {
+  let before = SystemTime::now() - Duration::from_secs(1);
+  let keystore =
+    Keystore::from_bytes(&keystore(&["a", "b"]).to_bytes()).unwrap();
+
+  let labels: Vec<&str> = keystore
+    .entries()
+    .iter()
+    .map(KeystoreEntry::label)
+    .collect();
+  assert_eq!(labels, ["a", "b"]);
+  for (n, entry) in keystore.entries().iter().enumerate() {
+    let seed = seed(n as u8);
+    let public_key = seed.public_key();
+    assert_eq!(entry.algorithm(), AlgorithmId::CURRENT);
+    assert_eq!(entry.parameter_set(), ParameterSet::CURRENT);
+    assert_eq!(entry.kdf(), KDF);
+    assert_eq!(entry.public_key_bytes(), public_key.as_bytes());
+    assert_eq!(
+      entry.public_key().unwrap().as_bytes(),
+      public_key.as_bytes()
+    );
+    assert!(entry.created() >= before && entry.created() <= SystemTime::now());
+  }
+}
+
+#[test]
+fn wrong_passphrase_fails_verification() {
+  let keystore = keystore(&["signing"]);
+  assert_eq!(
+    keystore.unlock("signing", "wrong").unwrap_err(),
+    EternityError::VerificationFailed
+  );
+  assert_eq!(
+    keystore.get("signing").unwrap().decrypt("").unwrap_err(),
+    EternityError::VerificationFailed
+  );
+}
+
+#[test]
+fn tampered_metadata_fails_decryption() {
+  let bytes = keystore(&["signing"]).to_bytes();
+
+  // Same length label, so the file still parses.
+  let mut tampered = bytes.clone();
+  tampered[LABEL_OFFSET] = b'S';
+  let keystore = Keystore::from_bytes(&tampered).unwrap();
+  assert_eq!(
+    keystore.unlock("Signing", PASSPHRASE).unwrap_err(),
+    EternityError::VerificationFailed
+  );
+
+  // The public key follows the label and its 2-byte length.
+  let mut tampered = bytes.clone();
+  tampered[LABEL_OFFSET + "signing".len() + 2] ^= 1;
+  let keystore = Keystore::from_bytes(&tampered).unwrap();
+  assert_eq!(
+    keystore.unlock("signing", PASSPHRASE).unwrap_err(),
+    EternityError::VerificationFailed
+  );
+
+  // The created timestamp is authenticated too.
+  let mut tampered = bytes;
+  tampered[LABEL_OFFSET - 3] ^= 1;
+  let keystore = Keystore::from_bytes(&tampered).unwrap();
+  assert_eq!(
+    keystore.unlock("signing", PASSPHRASE).unwrap_err(),
+    EternityError::VerificationFailed
+  );
+}
+
+#[test]
+fn duplicate_labels_are_rejected() {
+  let mut keystore = keystore(&["signing"]);
+  assert_eq!(
+    keystore
+      .insert_with_kdf("signing", &seed(9), PASSPHRASE, KDF)
+      .unwrap_err(),
+    EternityError::InvalidInput
+  );
+  assert_eq!(
+    keystore
+      .insert_with_kdf("", &seed(9), PASSPHRASE, KDF)
+      .unwrap_err(),
+    EternityError::InvalidInput
+  );
+  assert_eq!(keystore.entries().len(), 1);
+
+  // A file listing the same label twice.
+  let entry = &keystore.to_bytes()[FILE_HEADER_BYTES..];
+  let mut bytes = keystore.to_bytes();
+  bytes[6..8].copy_from_slice(&2u16.to_be_bytes());
+  bytes.extend_from_slice(entry);
+  assert_eq!(
+    Keystore::from_bytes(&bytes).unwrap_err(),
+    EternityError::InvalidInput
+  );
+
+  // The label is free again once removed.
+  assert!(keystore.remove("signing").is_some());
+  assert!(keystore
+    .insert_with_kdf("signing", &seed(9), PASSPHRASE, KDF)
+    .is_ok());
+}
+
+#[test]
+fn trailing_and_missing_bytes_are_rejected() {
+  let bytes = keystore(&["signing"]).to_bytes();
+  let mut trailing = bytes.clone();
+  trailing.push(0);
+  assert_eq!(
+    Keystore::from_bytes(&trailing).unwrap_err(),
+    EternityError::InvalidInput
+  );
+  assert!(Keystore::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+  let mut version = bytes;
+  version[5] = KEYSTORE_VERSION + 1;
+  assert!(Keystore::from_bytes(&version).is_err());
+}
+
+#[test]
+fn excessive_kdf_costs_are_rejected() {
+  let excessive = [
+    KeystoreKdf {
+      log_n: KEYSTORE_MAX_LOG_N + 1,
+      ..KDF
+    },
+    KeystoreKdf { log_n: 0, ..KDF },
+    KeystoreKdf {
+      r: KEYSTORE_MAX_R + 1,
+      ..KDF
+    },
+    KeystoreKdf { r: u32::MAX, ..KDF },
+    KeystoreKdf {
+      p: KEYSTORE_MAX_P + 1,
+      ..KDF
+    },
+    KeystoreKdf { p: 0, ..KDF },
+    // 128 * 2^20 * 16 bytes is past the memory cap.
+    KeystoreKdf {
+      log_n: 20,
+      r: 16,
+      p: 1,
+    },
+    // 2^20 * 8 * 2 is past the work cap, at 1 GiB of memory.
+    KeystoreKdf {
+      log_n: 20,
+      r: 8,
+      p: 2,
+    },
+  ];
+  let bytes = keystore(&["signing"]).to_bytes();
+  let kdf_offset = LABEL_OFFSET + "signing".len() + 2 + ARTIFACTKEYBYTES;
+  for kdf in excessive {
+    let mut keystore = Keystore::new();
+    assert_eq!(
+      keystore
+        .insert_with_kdf("signing", &seed(0), PASSPHRASE, kdf)
+        .unwrap_err(),
+      EternityError::InvalidInput
+    );
+
+    // Parsing fails before any key derivation is attempted.
+    let mut crafted = bytes.clone();
+    crafted[kdf_offset] = kdf.log_n;
+    crafted[kdf_offset + 1..kdf_offset + 5]
+      .copy_from_slice(&kdf.r.to_be_bytes());
+    crafted[kdf_offset + 5..kdf_offset + 9]
+      .copy_from_slice(&kdf.p.to_be_bytes());
+    assert_eq!(
+      Keystore::from_bytes(&crafted).unwrap_err(),
+      EternityError::InvalidInput
+    );
+  }
+
+  // The unmodified file still parses with the cheap test cost.
+  assert_eq!(
+    Keystore::from_bytes(&bytes).unwrap().entries()[0].kdf(),
+    KDF
+  );
+}
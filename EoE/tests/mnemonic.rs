@@ -0,0 +1,120 @@
+#![cfg(feature = "mnemonic")]
+
+use echoes_of_eternity::*;
+
+/// BIP39 vectors for 256-bit entropy, which the unmasked encoding follows.
+const BIP39_VECTORS: [(u8, &str); 4] = [
+  (
+    0x00,
+    "abandon abandon abandon abandon abandon abandon abandon abandon \
+     abandon abandon abandon abandon abandon abandon abandon abandon \
+     abandon abandon abandon abandon abandon abandon abandon art",
+  ),
+  (
+    0x7f,
+    "legal winner thank year wave sausage worth useful legal winner thank \
+     year wave sausage worth useful legal winner thank year wave sausage \
+     worth title",
+  ),
+  (
+    0x80,
+    "letter advice cage absurd amount doctor acoustic avoid letter advice \
+     cage absurd amount doctor acoustic avoid letter advice cage absurd \
+     amount doctor acoustic bless",
+  ),
+  (
+    0xff,
+    "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo \
+     zoo zoo zoo zoo zoo vote",
+  ),
+];
+
+/// Seed 00 01 .. 1f masked with a passphrase.
+const PASSPHRASE_VECTORS: [(&str, &str); 3] = [
+  (
+    "",
+    "abandon amount liar amount expire adjust cage candy arch gather drum \
+     bullet absurd math era live bid rhythm alien crouch range attend \
+     journey unaware",
+  ),
+  (
+    "TREZOR",
+    "viable insane just ethics report puppy protect sorry crazy oak demise \
+     sight youth pony hockey clump venture sun rigid amount tonight mammal \
+     mother remember",
+  ),
+  (
+    "caf\u{e9}",
+    "ancient evidence bacon corn slide avocado zoo host engine dumb upper \
+     time harsh siege shield rally always amateur level permit library \
+     danger fine industry",
+  ),
+];
+
+fn counting_seed() -> [u8; 32] {
+  core::array::from_fn(|i| i as u8)
+}
+
+#[test]
+fn bip39_vectors() {
+  for (byte, phrase) in BIP39_VECTORS {
+    let mnemonic = Mnemonic::from_seed(&[byte; 32], "");
+    assert_eq!(mnemonic.phrase(), phrase);
+    assert_eq!(Mnemonic::parse(phrase).unwrap().to_seed(""), [byte; 32]);
+  }
+}
+
+#[test]
+fn passphrase_vectors() {
+  let seed = counting_seed();
+  for (passphrase, phrase) in PASSPHRASE_VECTORS {
+    assert_eq!(Mnemonic::from_seed(&seed, passphrase).phrase(), phrase);
+    assert_eq!(Mnemonic::parse(phrase).unwrap().to_seed(passphrase), seed);
+  }
+}
+
+#[test]
+fn passphrase_is_normalized() {
+  let seed = counting_seed();
+  let composed = Mnemonic::from_seed(&seed, "caf\u{e9}");
+  let decomposed = Mnemonic::from_seed(&seed, "cafe\u{301}");
+  assert_eq!(composed, decomposed);
+}
+
+#[test]
+fn wrong_passphrase_restores_another_seed() {
+  let seed = counting_seed();
+  let mnemonic = Mnemonic::from_seed(&seed, "TREZOR");
+  assert_ne!(mnemonic.to_seed("trezor"), seed);
+  assert_ne!(mnemonic.to_seed(""), seed);
+}
+
+#[test]
+fn restores_keypair() {
+  let seed = counting_seed();
+  let mnemonic = Mnemonic::from_seed(&seed, "");
+  let restored: Mnemonic = mnemonic.phrase().parse().unwrap();
+  assert_eq!(restored.to_keypair("").public, KeyPair::from_seed(&seed).public);
+}
+
+#[test]
+fn parse_is_lenient_about_case_and_spacing() {
+  let phrase = BIP39_VECTORS[3].1.to_uppercase().replace(' ', "\n  ");
+  assert_eq!(Mnemonic::parse(&phrase).unwrap().to_seed(""), [0xff; 32]);
+}
+
+#[test]
+fn parse_rejects_bad_phrases() {
+  let phrase = BIP39_VECTORS[0].1;
+
+  // Checksum mismatch: "abandon" x 24
+  let wrong_checksum = phrase.replace("art", "abandon");
+  assert!(Mnemonic::parse(&wrong_checksum).is_err());
+
+  // Unknown word
+  assert!(Mnemonic::parse(&phrase.replace("art", "artifact")).is_err());
+
+  // Too few and too many words
+  assert!(Mnemonic::parse(&phrase.replace(" art", "")).is_err());
+  assert!(Mnemonic::parse(&format!("{} abandon", phrase)).is_err());
+}
@@ -0,0 +1,171 @@
+use echoes_of_eternity::*;
+
+/// ML-DSA-44 keys from seed `000102..1f`, written by OpenSSL 3.5.
+const OPENSSL_KEYS: &str = include_str!("data/mldsa44.pem");
+
+fn seed() -> ArtifactSeed {
+  ArtifactSeed::from_array(&[7; ESSENCEBYTES])
+}
+
+/// Re-encode the outermost length of `der` in a longer form than needed.
+fn widen_outer_length(der: &[u8]) -> Vec<u8> {
+  let (len, header) = match der[1] {
+    len @ 0..=0x7f => (len as usize, 2),
+    0x81 => (der[2] as usize, 3),
+    0x82 => (u16::from_be_bytes([der[2], der[3]]) as usize, 4),
+    other => panic!("unexpected length octet {other:#x}"),
+  };
+  let mut widened = vec![der[0], 0x83, 0];
+  widened.extend_from_slice(&(len as u16).to_be_bytes());
+  widened.extend_from_slice(&der[header..]);
+  widened
+}
+
+#[test]
+fn openssl_spki_vector() {
+  let der = pem_decode(OPENSSL_KEYS, PEM_PUBLIC_KEY).unwrap();
+  let (algorithm, public_key) = decode_public_key_der(&der).unwrap();
+  assert_eq!(algorithm, AlgorithmId::MlDsa44);
+  assert_eq!(public_key.len(), 1312);
+  assert_eq!(encode_public_key_der(algorithm, public_key).unwrap(), der);
+
+  let pem = pem_encode(PEM_PUBLIC_KEY, &der);
+  assert!(OPENSSL_KEYS.contains(&pem));
+}
+
+#[test]
+fn openssl_private_key_vector() {
+  let der = pem_decode(OPENSSL_KEYS, PEM_PRIVATE_KEY).unwrap();
+  let document = decode_private_key_der(&der).unwrap();
+  assert_eq!(document.algorithm, AlgorithmId::MlDsa44);
+  assert_eq!(document.seed, Some(core::array::from_fn(|i| i as u8)));
+  assert_eq!(document.expanded.as_ref().map(Vec::len), Some(2560));
+  assert!(document.public_key.is_none());
+  assert_eq!(encode_private_key_der(&document).unwrap(), der);
+}
+
+#[test]
+fn public_key_round_trip() {
+  let seed = seed();
+  let public_key = seed.public_key();
+  let der = public_key.to_public_key_der();
+  assert_eq!(
+    decode_public_key_der(&der).unwrap(),
+    (AlgorithmId::CURRENT, public_key.as_bytes())
+  );
+  assert_eq!(
+    ArtifactPublicKey::from_public_key_der(&der).unwrap().as_bytes(),
+    public_key.as_bytes()
+  );
+  let pem = public_key.to_public_key_pem();
+  assert_eq!(
+    ArtifactPublicKey::from_public_key_pem(&pem).unwrap().as_bytes(),
+    public_key.as_bytes()
+  );
+}
+
+#[test]
+fn seed_only_round_trip() {
+  let seed = seed();
+  let der = seed.to_pkcs8_der();
+  let document = decode_private_key_der(&der).unwrap();
+  assert_eq!(document.seed.as_ref(), Some(&[7; ESSENCEBYTES]));
+  assert!(document.expanded.is_none());
+
+  let decoded = ArtifactSeed::from_pkcs8_der(&der).unwrap();
+  assert_eq!(decoded.as_bytes(), seed.as_bytes());
+  let decoded = ArtifactSecretKey::from_pkcs8_der(&der).unwrap();
+  assert_eq!(decoded.as_bytes(), seed.secret_key().as_bytes());
+}
+
+#[test]
+fn expanded_round_trip() {
+  let seed = seed();
+  let der = seed.secret_key().to_pkcs8_der();
+  let document = decode_private_key_der(&der).unwrap();
+  assert!(document.seed.is_none());
+  assert_eq!(
+    document.expanded.as_deref(),
+    Some(seed.secret_key().as_bytes())
+  );
+
+  let pem = seed.secret_key().to_pkcs8_pem();
+  let decoded = ArtifactSecretKey::from_pkcs8_pem(&pem).unwrap();
+  assert_eq!(decoded.as_bytes(), seed.secret_key().as_bytes());
+  assert!(ArtifactSeed::from_pkcs8_der(&der).is_err());
+}
+
+#[test]
+fn both_form_round_trip() {
+  let seed = seed();
+  let document = PrivateKeyDocument {
+    algorithm: AlgorithmId::CURRENT,
+    seed: Some([7; ESSENCEBYTES]),
+    expanded: Some(seed.secret_key().as_bytes().to_vec()),
+    public_key: Some(seed.public_key().as_bytes().to_vec()),
+  };
+  let der = encode_private_key_der(&document).unwrap();
+  let decoded = decode_private_key_der(&der).unwrap();
+  assert_eq!(decoded.seed, document.seed);
+  assert_eq!(decoded.expanded, document.expanded);
+  assert_eq!(decoded.public_key, document.public_key);
+  assert_eq!(
+    ArtifactSeed::from_pkcs8_der(&der).unwrap().as_bytes(),
+    seed.as_bytes()
+  );
+
+  // A stored expanded key that does not belong to the seed is rejected.
+  let mut mismatched = document.clone();
+  if let Some(expanded) = mismatched.expanded.as_mut() {
+    expanded[0] ^= 1;
+  }
+  let der = encode_private_key_der(&mismatched).unwrap();
+  assert!(ArtifactSeed::from_pkcs8_der(&der).is_err());
+}
+
+#[test]
+fn non_minimal_lengths_are_rejected() {
+  let spki = seed().public_key().to_public_key_der();
+  assert_eq!(
+    decode_public_key_der(&widen_outer_length(&spki)),
+    Err(EternityError::InvalidInput)
+  );
+
+  let pkcs8 = seed().to_pkcs8_der();
+  assert!(pkcs8[1] < 0x80, "seed-only documents use the short form");
+  let mut long_form = vec![pkcs8[0], 0x81];
+  long_form.extend_from_slice(&pkcs8[1..]);
+  assert!(decode_private_key_der(&long_form).is_err());
+  assert!(decode_private_key_der(&widen_outer_length(&pkcs8)).is_err());
+}
+
+#[test]
+fn malformed_documents_are_rejected() {
+  let spki = seed().public_key().to_public_key_der();
+  let mut trailing = spki.clone();
+  trailing.push(0);
+  assert!(decode_public_key_der(&trailing).is_err());
+  assert!(decode_public_key_der(&spki[..spki.len() - 1]).is_err());
+  assert!(decode_public_key_der(&[]).is_err());
+}
+
+#[test]
+fn unknown_and_foreign_oids_are_rejected() {
+  // SEQUENCE (4-byte header) { SEQUENCE { OID .. } .. }: the OID length
+  // sits at offset 7.
+  let spki = seed().public_key().to_public_key_der();
+  let last_arc = 7 + spki[7] as usize;
+
+  let mut unknown = spki.clone();
+  unknown[last_arc] = 0x7f;
+  assert_eq!(
+    decode_public_key_der(&unknown),
+    Err(EternityError::UnsupportedAlgorithm)
+  );
+
+  let der = pem_decode(OPENSSL_KEYS, PEM_PUBLIC_KEY).unwrap();
+  assert_eq!(
+    ArtifactPublicKey::from_public_key_der(&der).unwrap_err(),
+    EternityError::UnsupportedAlgorithm
+  );
+}
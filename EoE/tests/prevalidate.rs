@@ -0,0 +1,155 @@
+//! The cheap structural checks must reject what full verification rejects
+//! for malformed encodings, and accept everything full verification accepts.
+
+use echoes_of_eternity::{
+  prevalidate_transformation, verify_transformation, EternityError, KeyPair,
+  ParameterSet, ESSENCEBYTES, K, OMEGA, SIGNATUREBYTES,
+};
+
+const HINTS: usize = SIGNATUREBYTES - OMEGA - K;
+
+fn signed(message: &[u8]) -> (KeyPair, Vec<u8>) {
+  let keys = KeyPair::from_seed(&[11; ESSENCEBYTES]);
+  let signature = keys.sign(message).to_vec();
+  (keys, signature)
+}
+
+/// Cumulative hint count after row `row`.
+fn hint_count(signature: &[u8], row: usize) -> usize {
+  signature[HINTS + OMEGA + row] as usize
+}
+
+#[test]
+fn valid_signatures_agree_with_full_verification() {
+  for index in 0..8u8 {
+    let message = [index; 24];
+    let (keys, signature) = signed(&message);
+    assert!(verify_transformation(&signature, &message, &keys.public).is_ok());
+    assert_eq!(prevalidate_transformation(&signature, &keys.public), Ok(()));
+  }
+}
+
+#[test]
+fn challenge_tampering_is_left_to_full_verification() {
+  let (keys, mut signature) = signed(b"challenge");
+  signature[0] ^= 1;
+  assert_eq!(prevalidate_transformation(&signature, &keys.public), Ok(()));
+  assert!(
+    verify_transformation(&signature, b"challenge", &keys.public).is_err()
+  );
+}
+
+#[test]
+fn stray_lengths_are_invalid_input() {
+  let (keys, signature) = signed(b"lengths");
+  for len in [0, SIGNATUREBYTES - 1, SIGNATUREBYTES + 1] {
+    let mut stretched = signature.clone();
+    stretched.resize(len, 0);
+    assert_eq!(
+      prevalidate_transformation(&stretched, &keys.public),
+      Err(EternityError::InvalidInput)
+    );
+  }
+  let short = &keys.public[..keys.public.len() - 1];
+  assert_eq!(
+    prevalidate_transformation(&signature, short),
+    Err(EternityError::InvalidInput)
+  );
+}
+
+#[test]
+fn other_parameter_set_lengths_are_mismatches() {
+  let (keys, signature) = signed(b"lengths");
+  for set in ParameterSet::ALL {
+    if set == ParameterSet::CURRENT {
+      continue;
+    }
+    let foreign_signature = vec![0; set.signature_bytes()];
+    assert_eq!(
+      prevalidate_transformation(&foreign_signature, &keys.public),
+      Err(EternityError::ParameterMismatch)
+    );
+    let foreign_key = vec![0; set.artifact_key_bytes()];
+    assert_eq!(
+      prevalidate_transformation(&signature, &foreign_key),
+      Err(EternityError::ParameterMismatch)
+    );
+  }
+}
+
+#[test]
+fn out_of_order_hints_are_rejected() {
+  for index in 0..64u8 {
+    let message = [index; 16];
+    let (keys, mut signature) = signed(&message);
+    let mut start = 0;
+    for row in 0..K {
+      let end = hint_count(&signature, row);
+      if end - start >= 2 {
+        signature.swap(HINTS + start, HINTS + start + 1);
+        assert_eq!(
+          prevalidate_transformation(&signature, &keys.public),
+          Err(EternityError::InvalidInput)
+        );
+        assert!(
+          verify_transformation(&signature, &message, &keys.public).is_err()
+        );
+        return;
+      }
+      start = end;
+    }
+  }
+  panic!("no signature had two hints in one row");
+}
+
+#[test]
+fn hint_counts_beyond_omega_are_rejected() {
+  let (keys, mut signature) = signed(b"omega");
+  signature[HINTS + OMEGA + K - 1] = OMEGA as u8 + 1;
+  assert_eq!(
+    prevalidate_transformation(&signature, &keys.public),
+    Err(EternityError::InvalidInput)
+  );
+  assert!(verify_transformation(&signature, b"omega", &keys.public).is_err());
+}
+
+#[test]
+fn decreasing_hint_counts_are_rejected() {
+  let (keys, mut signature) = signed(b"decreasing");
+  let last = hint_count(&signature, K - 1);
+  assert!(last > 0, "signature carries no hints");
+  signature[HINTS + OMEGA] = last as u8;
+  signature[HINTS + OMEGA + 1] = 0;
+  assert_eq!(
+    prevalidate_transformation(&signature, &keys.public),
+    Err(EternityError::InvalidInput)
+  );
+}
+
+#[test]
+fn nonzero_hint_padding_is_rejected() {
+  let (keys, mut signature) = signed(b"padding");
+  assert!(
+    hint_count(&signature, K - 1) < OMEGA,
+    "no padding to corrupt"
+  );
+  signature[HINTS + OMEGA - 1] = 1;
+  assert_eq!(
+    prevalidate_transformation(&signature, &keys.public),
+    Err(EternityError::InvalidInput)
+  );
+  assert!(verify_transformation(&signature, b"padding", &keys.public).is_err());
+}
+
+#[test]
+fn fragments_beyond_the_norm_bound_are_rejected() {
+  let (keys, mut signature) = signed(b"norm");
+  // z is packed as GAMMA1 - z, so zero bytes put the first coefficient at
+  // GAMMA1, past the GAMMA1 - BETA bound.
+  signature[ESSENCEBYTES..ESSENCEBYTES + 3].fill(0);
+  assert_eq!(
+    prevalidate_transformation(&signature, &keys.public),
+    Err(EternityError::InvalidInput)
+  );
+  assert!(verify_transformation(&signature, b"norm", &keys.public).is_err());
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "signature")]
+
+use echoes_of_eternity::*;
+use sha2::{Digest, Sha256, Sha512};
+use signature::{
+  DigestSigner, DigestVerifier, Keypair, SignatureEncoding, Signer, Verifier,
+};
+
+const MESSAGE: &[u8] = b"generic artifact";
+
+/// What code generic over the RustCrypto traits would do.
+fn round_trip<S, V, Sig>(signer: &S, verifier: &V) -> Sig
+where
+  S: Signer<Sig>,
+  V: Verifier<Sig>,
+  Sig: SignatureEncoding,
+{
+  let signature = signer.sign(MESSAGE);
+  assert!(verifier.verify(MESSAGE, &signature).is_ok());
+  assert!(verifier.verify(b"other", &signature).is_err());
+
+  let bytes = signature.to_bytes();
+  assert_eq!(bytes.as_ref().len(), signature.encoded_len());
+  let decoded = Sig::try_from(bytes.as_ref()).ok().unwrap();
+  assert!(verifier.verify(MESSAGE, &decoded).is_ok());
+  signature
+}
+
+#[test]
+fn generic_sign_and_verify() {
+  let seed = ArtifactSeed::from_array(&[2; ESSENCEBYTES]);
+  let signature: ArtifactSignature =
+    round_trip(seed.secret_key(), seed.public_key());
+  assert_eq!(signature.encoded_len(), SIGNATUREBYTES);
+
+  let keys = KeyPair::from_seed(&[2; ESSENCEBYTES]);
+  let signature: ArtifactSignature = round_trip(&keys, &keys.verifying_key());
+  assert!(seed.public_key().verify(MESSAGE, &signature).is_ok());
+  assert_eq!(
+    keys.verifying_key().as_bytes(),
+    seed.public_key().as_bytes()
+  );
+}
+
+#[test]
+fn digest_signatures_cover_the_finalized_digest() {
+  let keys = KeyPair::from_seed(&[2; ESSENCEBYTES]);
+  let public_key = keys.verifying_key();
+  let signature: ArtifactSignature =
+    keys.sign_digest(Sha256::new_with_prefix(MESSAGE));
+
+  assert!(public_key
+    .verify_digest(Sha256::new_with_prefix(MESSAGE), &signature)
+    .is_ok());
+  assert!(public_key
+    .verify(&Sha256::digest(MESSAGE), &signature)
+    .is_ok());
+
+  let seed = ArtifactSeed::from_array(&[2; ESSENCEBYTES]);
+  let signature: ArtifactSignature = seed
+    .secret_key()
+    .sign_digest(Sha256::new_with_prefix(MESSAGE));
+  assert!(public_key
+    .verify_digest(Sha256::new_with_prefix(MESSAGE), &signature)
+    .is_ok());
+}
+
+#[test]
+fn digest_verifier_rejects_another_digest() {
+  let keys = KeyPair::from_seed(&[2; ESSENCEBYTES]);
+  let public_key = keys.verifying_key();
+  let signature: ArtifactSignature =
+    keys.sign_digest(Sha256::new_with_prefix(MESSAGE));
+
+  // Another message, or the same message under another hash function.
+  assert!(public_key
+    .verify_digest(Sha256::new_with_prefix(b"other"), &signature)
+    .is_err());
+  assert!(public_key
+    .verify_digest(Sha512::new_with_prefix(MESSAGE), &signature)
+    .is_err());
+  // The digest is what gets signed, not the message itself.
+  assert!(public_key.verify(MESSAGE, &signature).is_err());
+}
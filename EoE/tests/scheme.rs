@@ -0,0 +1,130 @@
+use echoes_of_eternity::*;
+
+use AlgorithmId::*;
+use Resolution::*;
+
+/// How an identifier resolves in a build of one parameter set.
+#[derive(Clone, Copy)]
+enum Resolution {
+  Scheme,
+  Mismatch,
+  Unsupported,
+  /// `Scheme` with the `composite` feature, `Unsupported` without.
+  CompositeScheme,
+  /// `Mismatch` with the `composite` feature, `Unsupported` without.
+  CompositeMismatch,
+}
+
+/// Expected resolution of every identifier in Mode2, Mode3 and Mode5 builds.
+const RESOLUTIONS: [(AlgorithmId, [Resolution; 3]); 14] = [
+  (Dilithium2, [Scheme, Mismatch, Mismatch]),
+  (Dilithium3, [Mismatch, Scheme, Mismatch]),
+  (Dilithium5, [Mismatch, Mismatch, Scheme]),
+  (MlDsa44, [Unsupported; 3]),
+  (MlDsa65, [Unsupported; 3]),
+  (MlDsa87, [Unsupported; 3]),
+  (HashMlDsa44Sha512, [Unsupported; 3]),
+  (HashMlDsa65Sha512, [Unsupported; 3]),
+  (HashMlDsa87Sha512, [Unsupported; 3]),
+  (
+    Dilithium2Ed25519,
+    [CompositeScheme, CompositeMismatch, CompositeMismatch],
+  ),
+  (
+    Dilithium3Ed25519,
+    [CompositeMismatch, CompositeScheme, CompositeMismatch],
+  ),
+  (
+    Dilithium5Ed25519,
+    [CompositeMismatch, CompositeMismatch, CompositeScheme],
+  ),
+  (MlDsa44Ed25519, [Unsupported; 3]),
+  (MlDsa65Ed25519, [Unsupported; 3]),
+];
+
+fn expected(algorithm: AlgorithmId) -> Result<AlgorithmId, EternityError> {
+  let column = match ParameterSet::CURRENT {
+    ParameterSet::Mode2 => 0,
+    ParameterSet::Mode3 => 1,
+    ParameterSet::Mode5 => 2,
+  };
+  let (_, resolutions) = RESOLUTIONS
+    .into_iter()
+    .find(|&(id, _)| id == algorithm)
+    .expect("every algorithm is in the table");
+  let composite = cfg!(feature = "composite");
+  match resolutions[column] {
+    Scheme => Ok(algorithm),
+    CompositeScheme if composite => Ok(algorithm),
+    Mismatch => Err(EternityError::ParameterMismatch),
+    CompositeMismatch if composite => Err(EternityError::ParameterMismatch),
+    Unsupported | CompositeScheme | CompositeMismatch => {
+      Err(EternityError::UnsupportedAlgorithm)
+    }
+  }
+}
+
+#[test]
+fn table_lists_every_algorithm_in_order() {
+  let listed: Vec<AlgorithmId> =
+    RESOLUTIONS.into_iter().map(|(id, _)| id).collect();
+  assert_eq!(listed, AlgorithmId::ALL);
+}
+
+#[test]
+fn every_algorithm_resolves_or_says_why_not() {
+  for algorithm in AlgorithmId::ALL {
+    let resolved = resolve_scheme(algorithm).map(|scheme| scheme.algorithm());
+    assert_eq!(resolved, expected(algorithm), "{}", algorithm.name());
+    assert_eq!(scheme(algorithm).is_some(), resolved.is_ok());
+  }
+}
+
+#[test]
+fn supported_algorithms_are_those_of_this_build() {
+  let mut want = vec![AlgorithmId::CURRENT];
+  if cfg!(feature = "composite") {
+    want.push(AlgorithmId::CURRENT_COMPOSITE);
+  }
+  assert_eq!(supported_algorithms(), want);
+  assert_eq!(
+    negotiate(&[AlgorithmId::MlDsa65, AlgorithmId::CURRENT]),
+    Some(AlgorithmId::CURRENT)
+  );
+}
+
+#[test]
+fn verification_dispatches_by_identifier() {
+  let keys = KeyPair::from_seed(&[3; ESSENCEBYTES]);
+  let signature = keys.sign(b"dispatch");
+  assert!(verify_with_algorithm(
+    AlgorithmId::CURRENT,
+    &signature,
+    b"dispatch",
+    &keys.public,
+  )
+  .is_ok());
+
+  for algorithm in AlgorithmId::ALL {
+    if let Err(error) = expected(algorithm) {
+      let result =
+        verify_with_algorithm(algorithm, &signature, b"dispatch", &keys.public);
+      assert_eq!(result, Err(error), "{}", algorithm.name());
+    }
+  }
+}
+
+#[test]
+fn schemes_round_trip_through_their_own_keys() {
+  for algorithm in supported_algorithms() {
+    let scheme = resolve_scheme(algorithm).expect("supported");
+    let seed = vec![5; scheme.seed_bytes()];
+    let (public_key, secret_key) =
+      scheme.keypair_from_seed(&seed).expect("key pair");
+    let signature = scheme.sign(&secret_key, b"round trip").expect("sign");
+    assert!(scheme
+      .verify(&signature, b"round trip", &public_key)
+      .is_ok());
+    assert!(scheme.verify(&signature, b"other", &public_key).is_err());
+  }
+}
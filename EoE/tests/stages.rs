@@ -0,0 +1,79 @@
+#![cfg(feature = "internals")]
+
+//! Verification run one stage at a time, the way the benchmarks and
+//! external audits drive it through `internals`.
+//!
+//! Run with `cargo test --features internals --test stages`.
+
+use echoes_of_eternity::{
+  internals::{
+    artifact_verify_stage1, artifact_verify_stage2, artifact_verify_stage3,
+    artifact_verify_stage4, artifact_verify_stage5, artifact_verify_stage6,
+    artifact_verify_stage7, artifact_verify_stage8,
+  },
+  verify_transformation, KeyPair, ESSENCEBYTES, K,
+};
+
+const MESSAGE: &[u8] = b"staged artifact";
+
+fn signed() -> (KeyPair, Vec<u8>) {
+  let keys = KeyPair::from_seed(&[5; ESSENCEBYTES]);
+  let signature = keys.sign(MESSAGE).to_vec();
+  (keys, signature)
+}
+
+/// Chain stages 1 to 8, stopping at the first one that rejects.
+fn stages(signature: &[u8], message: &[u8], public: &[u8]) -> Option<()> {
+  let (mut essence, elements) =
+    artifact_verify_stage1(signature, public).ok()?;
+  let (mut seal, fragments, glyphs) = artifact_verify_stage2(signature).ok()?;
+  let digest = artifact_verify_stage3(public, message);
+  let challenge = artifact_verify_stage4(&mut seal);
+  let matrix = artifact_verify_stage5(&mut essence);
+  assert_eq!(matrix.len(), K);
+  let (transformed, scaled) =
+    artifact_verify_stage6(challenge, matrix, fragments, elements).ok()?;
+  let refined = artifact_verify_stage7(transformed, scaled).ok()?;
+  artifact_verify_stage8(
+    Box::new(core::array::from_fn(|_| 0)),
+    refined,
+    &glyphs,
+    digest,
+    &seal,
+    &mut [0u8; ESSENCEBYTES],
+  )
+  .ok()
+}
+
+#[test]
+fn valid_signature_passes_every_stage() {
+  let (keys, signature) = signed();
+  assert_eq!(stages(&signature, MESSAGE, &keys.public), Some(()));
+  assert_eq!(
+    verify_transformation(&signature, MESSAGE, &keys.public),
+    Ok(())
+  );
+}
+
+#[test]
+fn tampered_signature_is_rejected() {
+  let (keys, signature) = signed();
+
+  // A flipped bit in the challenge seed passes the structural stages and
+  // is caught when stage 8 recomputes it.
+  let mut tampered = signature.clone();
+  tampered[0] ^= 1;
+  assert!(artifact_verify_stage2(&tampered).is_ok());
+  assert_eq!(stages(&tampered, MESSAGE, &keys.public), None);
+  assert!(verify_transformation(&tampered, MESSAGE, &keys.public).is_err());
+
+  // So is a change to the response z.
+  let mut tampered = signature.clone();
+  tampered[ESSENCEBYTES] ^= 1;
+  assert_eq!(stages(&tampered, MESSAGE, &keys.public), None);
+
+  // And the untouched signature under another message or key.
+  assert_eq!(stages(&signature, b"other", &keys.public), None);
+  let other = KeyPair::from_seed(&[6; ESSENCEBYTES]);
+  assert_eq!(stages(&signature, MESSAGE, &other.public), None);
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "artifact-trace")]
+
+use echoes_of_eternity::*;
+
+const MESSAGE: &[u8] = b"traced artifact";
+
+/// Attempts the reference implementation makes to sign MESSAGE under the
+/// key from seed 7; at least one is rejected in every parameter set.
+#[cfg(feature = "artifact_mode2")]
+const ATTEMPTS: u64 = 4;
+#[cfg(not(any(feature = "artifact_mode2", feature = "artifact_mode5")))]
+const ATTEMPTS: u64 = 4;
+#[cfg(feature = "artifact_mode5")]
+const ATTEMPTS: u64 = 2;
+
+fn hex(label: &str, trace: &ArtifactTrace) -> String {
+  match trace.get(label) {
+    Some(TraceValue::Bytes(bytes)) => hex_encode(bytes),
+    other => panic!("{label}: expected bytes, got {other:?}"),
+  }
+}
+
+#[test]
+fn json_layout_is_stable() {
+  let trace = ArtifactTrace {
+    operation: "verify",
+    entries: vec![
+      TraceEntry {
+        label: "mu",
+        value: TraceValue::Bytes(vec![0xab, 0x01]),
+      },
+      TraceEntry {
+        label: "w1",
+        value: TraceValue::Artifacts(vec![vec![1, -2], vec![3]]),
+      },
+      TraceEntry {
+        label: "attempt",
+        value: TraceValue::Counter(3),
+      },
+    ],
+  };
+  let expected = format!(
+    "{{\"operation\":\"verify\",\"k\":{K},\"l\":{L},\"entries\":[\
+     {{\"label\":\"mu\",\"hex\":\"ab01\"}},\
+     {{\"label\":\"w1\",\"elements\":[[1,-2],[3]]}},\
+     {{\"label\":\"attempt\",\"value\":3}}]}}"
+  );
+  assert_eq!(trace.to_json(), expected);
+}
+
+#[test]
+fn verification_trace_records_each_value_once() {
+  let keys = KeyPair::from_seed(&[1; ESSENCEBYTES]);
+  let signature = keys.sign(MESSAGE);
+  let (result, trace) =
+    verify_transformation_traced(&signature, MESSAGE, &keys.public);
+  assert!(result.is_ok());
+
+  let labels: Vec<&str> = trace.entries.iter().map(|e| e.label).collect();
+  assert_eq!(
+    labels,
+    [
+      "mu",
+      "c_tilde",
+      "c",
+      "w_approx",
+      "w1",
+      "w1_encoded",
+      "c_tilde_computed"
+    ]
+  );
+
+  // The challenge seed leads the signature and is recomputed from w1.
+  let c_tilde = hex("c_tilde", &trace);
+  assert_eq!(c_tilde, hex_encode(&signature[..ESSENCEBYTES]));
+  assert_eq!(hex("c_tilde_computed", &trace), c_tilde);
+  assert_eq!(hex("mu", &trace).len(), 2 * CRYSTALBYTES);
+
+  let json = trace.to_json();
+  assert!(json.starts_with(&format!(
+    "{{\"operation\":\"verify\",\"k\":{K},\"l\":{L},\"entries\":[\
+     {{\"label\":\"mu\",\"hex\":\"{}\"}},\
+     {{\"label\":\"c_tilde\",\"hex\":\"{c_tilde}\"}},",
+    hex("mu", &trace)
+  )));
+  assert_eq!(json.matches("\"label\":\"c_tilde\"").count(), 1);
+}
+
+#[test]
+fn failed_verification_still_traces_the_recomputed_challenge() {
+  let keys = KeyPair::from_seed(&[1; ESSENCEBYTES]);
+  let signature = keys.sign(MESSAGE);
+  let (result, trace) =
+    verify_transformation_traced(&signature, b"other", &keys.public);
+  assert_eq!(result, Err(EternityError::VerificationFailed));
+  assert_ne!(hex("c_tilde_computed", &trace), hex("c_tilde", &trace));
+}
+
+fn artifacts(value: &TraceValue) -> &[Vec<i32>] {
+  match value {
+    TraceValue::Artifacts(artifacts) => artifacts,
+    other => panic!("expected artifacts, got {other:?}"),
+  }
+}
+
+#[test]
+fn signing_trace_records_every_attempt() {
+  let keys = KeyPair::from_seed(&[7; ESSENCEBYTES]);
+  let (signature, trace) = sign_transformation_traced(&keys, MESSAGE);
+  assert_eq!(signature, keys.sign(MESSAGE));
+  assert_eq!(trace.operation, "sign");
+  assert_eq!(trace.entries[0].label, "mu");
+
+  // Split the entries at each attempt counter.
+  let mut attempts: Vec<Vec<&str>> = Vec::new();
+  for entry in &trace.entries[1..] {
+    match entry.value {
+      TraceValue::Counter(attempt) => {
+        assert_eq!(entry.label, "attempt");
+        assert_eq!(attempt, attempts.len() as u64);
+        attempts.push(Vec::new());
+      }
+      _ => attempts.last_mut().unwrap().push(entry.label),
+    }
+  }
+  assert_eq!(attempts.len() as u64, ATTEMPTS);
+  let (accepted, rejected) = attempts.split_last().unwrap();
+  assert_eq!(accepted, &["y", "w1", "c_tilde", "z", "h"]);
+  for labels in rejected {
+    assert!(labels.starts_with(&["y", "w1", "c_tilde", "z"]));
+  }
+
+  // The accepted attempt's challenge seed leads the signature.
+  let c_tilde = trace.get_all("c_tilde").last().unwrap();
+  assert_eq!(
+    c_tilde,
+    &TraceValue::Bytes(signature[..ESSENCEBYTES].to_vec())
+  );
+  assert_ne!(trace.get("c_tilde"), Some(c_tilde));
+
+  for value in trace.get_all("y").chain(trace.get_all("z")) {
+    assert_eq!(artifacts(value).len(), L);
+  }
+  for value in trace.get_all("w1").chain(trace.get_all("h")) {
+    assert_eq!(artifacts(value).len(), K);
+  }
+  assert!(trace
+    .to_json()
+    .contains("{\"label\":\"attempt\",\"value\":1},{\"label\":\"y\","));
+}
+
+#[test]
+fn keypair_trace_holds_the_public_values() {
+  let (keys, trace) = keypair_traced(&[7; ESSENCEBYTES]);
+  assert_eq!(keys.public, KeyPair::from_seed(&[7; ESSENCEBYTES]).public);
+  assert_eq!(trace.operation, "keypair");
+
+  let labels: Vec<&str> = trace.entries.iter().map(|e| e.label).collect();
+  assert_eq!(labels, ["rho", "t1", "tr"]);
+  assert_eq!(hex("rho", &trace), hex_encode(&keys.public[..ESSENCEBYTES]));
+  assert_eq!(artifacts(trace.get("t1").unwrap()).len(), K);
+}
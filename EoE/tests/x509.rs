@@ -0,0 +1,335 @@
+use echoes_of_eternity::*;
+
+/// 2026-06-01T00:00:00Z, inside every validity period below but the
+/// expired one.
+const NOW: i64 = 1_780_272_000;
+
+/// 2025-01-01 to 2035-01-01.
+const VALID: (&str, &str) = ("250101000000Z", "350101000000Z");
+
+/// 2025-01-01 to 2026-01-01 (1_767_225_600).
+const EXPIRED: (&str, &str) = ("250101000000Z", "260101000000Z");
+
+fn tlv(tag: u8, children: &[&[u8]]) -> Vec<u8> {
+  let contents = children.concat();
+  let len = contents.len();
+  let mut out = vec![tag];
+  if len < 0x80 {
+    out.push(len as u8);
+  } else {
+    let bytes = (len as u32).to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    out.push(0x80 | (4 - skip) as u8);
+    out.extend_from_slice(&bytes[skip..]);
+  }
+  out.extend_from_slice(&contents);
+  out
+}
+
+fn bit_string(bytes: &[u8]) -> Vec<u8> {
+  tlv(0x03, &[&[0], bytes])
+}
+
+/// `Name` with a single commonName.
+fn name(common_name: &str) -> Vec<u8> {
+  let common_name = tlv(0x0c, &[common_name.as_bytes()]);
+  let attribute = tlv(0x30, &[&[0x06, 0x03, 0x55, 0x04, 0x03], &common_name]);
+  tlv(0x30, &[&tlv(0x31, &[&attribute])])
+}
+
+fn algorithm() -> Vec<u8> {
+  encode_algorithm_identifier(AlgorithmId::CURRENT).unwrap()
+}
+
+fn spki(key: &KeyPair) -> Vec<u8> {
+  encode_public_key_der(AlgorithmId::CURRENT, &key.public).unwrap()
+}
+
+fn keys(n: u8) -> KeyPair {
+  KeyPair::from_seed(&[n; ESSENCEBYTES])
+}
+
+/// A critical basicConstraints extension.
+fn basic_constraints(constraints: BasicConstraints) -> Vec<u8> {
+  let mut value = Vec::new();
+  if constraints.ca {
+    value.extend(tlv(0x01, &[&[0xff]]));
+  }
+  if let Some(path_len) = constraints.path_len {
+    value.extend(tlv(0x02, &[&[path_len as u8]]));
+  }
+  tlv(
+    0x30,
+    &[
+      &[0x06, 0x03, 0x55, 0x1d, 0x13],
+      &tlv(0x01, &[&[0xff]]),
+      &tlv(0x04, &[&tlv(0x30, &[&value])]),
+    ],
+  )
+}
+
+fn ca(path_len: Option<u64>) -> Option<BasicConstraints> {
+  Some(BasicConstraints { ca: true, path_len })
+}
+
+struct Party<'a> {
+  name: &'a str,
+  keys: &'a KeyPair,
+}
+
+/// A v3 certificate for `subject`, signed by `issuer`.
+fn certificate(
+  issuer: &Party<'_>,
+  subject: &Party<'_>,
+  constraints: Option<BasicConstraints>,
+  (not_before, not_after): (&str, &str),
+) -> Vec<u8> {
+  let validity = tlv(
+    0x30,
+    &[
+      &tlv(0x17, &[not_before.as_bytes()]),
+      &tlv(0x17, &[not_after.as_bytes()]),
+    ],
+  );
+  let extensions = match constraints {
+    Some(constraints) => {
+      tlv(0xa3, &[&tlv(0x30, &[&basic_constraints(constraints)])])
+    }
+    None => Vec::new(),
+  };
+  let tbs = tlv(
+    0x30,
+    &[
+      &tlv(0xa0, &[&[0x02, 0x01, 0x02]]),
+      &[0x02, 0x01, 0x01],
+      &algorithm(),
+      &name(issuer.name),
+      &validity,
+      &name(subject.name),
+      &spki(subject.keys),
+      &extensions,
+    ],
+  );
+  let signature = issuer.keys.sign(&tbs);
+  tlv(0x30, &[&tbs, &algorithm(), &bit_string(&signature)])
+}
+
+fn parse(der: &[u8]) -> Certificate<'_> {
+  Certificate::from_der(der).unwrap()
+}
+
+struct Chain {
+  root: Vec<u8>,
+  intermediate: Vec<u8>,
+  leaf: Vec<u8>,
+}
+
+fn issue_chain(
+  root_constraints: Option<BasicConstraints>,
+  intermediate_constraints: Option<BasicConstraints>,
+  leaf_validity: (&str, &str),
+) -> Chain {
+  let (root_keys, intermediate_keys, leaf_keys) = (keys(1), keys(2), keys(3));
+  let root = Party {
+    name: "Root",
+    keys: &root_keys,
+  };
+  let intermediate = Party {
+    name: "Intermediate",
+    keys: &intermediate_keys,
+  };
+  let leaf = Party {
+    name: "Leaf",
+    keys: &leaf_keys,
+  };
+  Chain {
+    root: certificate(&root, &root, root_constraints, VALID),
+    intermediate: certificate(
+      &root,
+      &intermediate,
+      intermediate_constraints,
+      VALID,
+    ),
+    leaf: certificate(&intermediate, &leaf, None, leaf_validity),
+  }
+}
+
+fn verify(chain: &Chain, time: i64) -> Result<(), CertificateError> {
+  let path = [parse(&chain.leaf), parse(&chain.intermediate)];
+  verify_chain(&path, &parse(&chain.root), time)
+}
+
+#[test]
+fn valid_chain_to_trust_anchor() {
+  let chain = issue_chain(ca(None), ca(Some(0)), VALID);
+  assert_eq!(verify(&chain, NOW), Ok(()));
+
+  // The anchor may also end the chain.
+  let (leaf, intermediate, root) = (
+    parse(&chain.leaf),
+    parse(&chain.intermediate),
+    parse(&chain.root),
+  );
+  let path = [leaf.clone(), intermediate.clone(), root.clone()];
+  assert_eq!(verify_chain(&path, &root, NOW), Ok(()));
+
+  assert_eq!(leaf.version, 2);
+  assert_eq!(leaf.signature_algorithm, Some(AlgorithmId::CURRENT));
+  assert_eq!(leaf.issuer, intermediate.subject);
+  let leaf_key = keys(3).public;
+  assert_eq!(leaf.public_key(), Ok((AlgorithmId::CURRENT, &leaf_key[..])));
+  assert_eq!(leaf.basic_constraints, None);
+  assert_eq!(intermediate.basic_constraints, ca(Some(0)));
+  assert!(root.is_self_issued() && !leaf.is_self_issued());
+  assert!(!leaf.unknown_critical_extension);
+
+  // Chains that do not reach the anchor are rejected.
+  assert_eq!(
+    verify_chain(core::slice::from_ref(&leaf), &root, NOW),
+    Err(CertificateError::IssuerMismatch)
+  );
+  assert_eq!(
+    verify_chain(&[], &root, NOW),
+    Err(CertificateError::EmptyChain)
+  );
+  assert_eq!(
+    verify_chain(core::slice::from_ref(&root), &root, NOW),
+    Err(CertificateError::EmptyChain)
+  );
+}
+
+#[test]
+fn expired_certificate() {
+  let chain = issue_chain(ca(None), ca(None), EXPIRED);
+  let leaf = parse(&chain.leaf);
+  assert!(leaf.is_valid_at(leaf.not_after));
+  assert!(!leaf.is_valid_at(leaf.not_after + 1));
+  assert_eq!(leaf.not_after, 1_767_225_600);
+
+  assert_eq!(verify(&chain, NOW), Err(CertificateError::NotValidAt));
+  assert_eq!(verify(&chain, leaf.not_after), Ok(()));
+  // Before the anchor's own validity period.
+  assert_eq!(
+    verify(&chain, leaf.not_before - 1),
+    Err(CertificateError::NotValidAt)
+  );
+}
+
+#[test]
+fn path_length_constraint() {
+  // One intermediate below the root.
+  let exceeded = issue_chain(ca(Some(0)), ca(None), VALID);
+  assert_eq!(
+    verify(&exceeded, NOW),
+    Err(CertificateError::PathLengthExceeded)
+  );
+  let allowed = issue_chain(ca(Some(1)), ca(None), VALID);
+  assert_eq!(verify(&allowed, NOW), Ok(()));
+}
+
+#[test]
+fn intermediate_must_be_a_ca() {
+  let not_ca = Some(BasicConstraints {
+    ca: false,
+    path_len: None,
+  });
+  let chain = issue_chain(ca(None), not_ca, VALID);
+  assert_eq!(parse(&chain.intermediate).basic_constraints, not_ca);
+  assert_eq!(
+    verify(&chain, NOW),
+    Err(CertificateError::NotCertificateAuthority)
+  );
+
+  let chain = issue_chain(ca(None), None, VALID);
+  assert_eq!(
+    verify(&chain, NOW),
+    Err(CertificateError::NotCertificateAuthority)
+  );
+}
+
+#[test]
+fn bad_signature() {
+  let mut chain = issue_chain(ca(None), ca(None), VALID);
+  // Flip a bit of the challenge seed that leads the leaf signature.
+  let at = chain.leaf.len() - SIGNATUREBYTES;
+  chain.leaf[at] ^= 1;
+  assert_eq!(verify(&chain, NOW), Err(CertificateError::BadSignature));
+
+  // Signed by a key other than the issuer's.
+  let (leaf_keys, other) = (keys(3), keys(4));
+  let forged = Party {
+    name: "Intermediate",
+    keys: &other,
+  };
+  let leaf = Party {
+    name: "Leaf",
+    keys: &leaf_keys,
+  };
+  let chain = Chain {
+    leaf: certificate(&forged, &leaf, None, VALID),
+    ..chain
+  };
+  assert_eq!(verify(&chain, NOW), Err(CertificateError::BadSignature));
+}
+
+/// A PKCS#10 request for `subject`, signed by its own key.
+fn request(subject: &Party<'_>) -> Vec<u8> {
+  let info = tlv(
+    0x30,
+    &[
+      &[0x02, 0x01, 0x00],
+      &name(subject.name),
+      &spki(subject.keys),
+      &[0xa0, 0x00],
+    ],
+  );
+  let signature = subject.keys.sign(&info);
+  tlv(0x30, &[&info, &algorithm(), &bit_string(&signature)])
+}
+
+#[test]
+fn certification_request_round_trip() {
+  let (root_keys, leaf_keys) = (keys(1), keys(3));
+  let root = Party {
+    name: "Root",
+    keys: &root_keys,
+  };
+  let der = request(&Party {
+    name: "Leaf",
+    keys: &leaf_keys,
+  });
+  let csr = CertificationRequest::from_der(&der).unwrap();
+  assert_eq!(csr.verify(), Ok(()));
+  assert_eq!(csr.subject, name("Leaf"));
+  assert_eq!(csr.signature_algorithm, Some(AlgorithmId::CURRENT));
+  assert!(csr.attributes.is_empty());
+  assert_eq!(
+    csr.public_key(),
+    Ok((AlgorithmId::CURRENT, &leaf_keys.public[..]))
+  );
+
+  // Issue a certificate for the requested key and verify it.
+  let root_der = certificate(&root, &root, ca(None), VALID);
+  let leaf_der = certificate(
+    &root,
+    &Party {
+      name: "Leaf",
+      keys: &leaf_keys,
+    },
+    None,
+    VALID,
+  );
+  let leaf = parse(&leaf_der);
+  assert_eq!(leaf.subject, csr.subject);
+  assert_eq!(leaf.public_key_info, csr.public_key_info);
+  assert_eq!(verify_chain(&[leaf], &parse(&root_der), NOW), Ok(()));
+
+  // A corrupted signature fails proof of possession.
+  let mut tampered = der.clone();
+  let at = tampered.len() - SIGNATUREBYTES;
+  tampered[at] ^= 1;
+  assert_eq!(
+    CertificationRequest::from_der(&tampered).unwrap().verify(),
+    Err(CertificateError::BadSignature)
+  );
+}